@@ -0,0 +1,109 @@
+// eBPF 프로그램 CO-RE 빌드
+//
+// ebpf/reverse_shell_detector.c 를 vmlinux.h 기준으로 컴파일하여 OUT_DIR 에 저장하고,
+// src/source/ebpf.rs 에서 include_bytes_aligned! 로 에이전트 바이너리에 포함합니다.
+//
+// 환경 변수:
+// - VMLINUX_H: 사용할 vmlinux.h 경로 (크로스 빌드 시 대상 아키텍처의 헤더 지정)
+// - VMLINUX_BTF: vmlinux.h 를 생성할 커널 BTF 경로 (기본값 /sys/kernel/btf/vmlinux)
+// - LIBBPF_INCLUDE: libbpf 헤더 경로 (기본값 /usr/include)
+// - CLANG, BPFTOOL: 사용할 clang, bpftool 실행 파일
+// - LINUX_AGENT_SKIP_EBPF: 설정하면 eBPF 빌드를 건너뜀 (procfs/replay 소스만 사용 가능)
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const EBPF_SOURCE: &str = "ebpf/reverse_shell_detector.c";
+const EBPF_OBJECT: &str = "reverse_shell_detector.o";
+
+fn main() {
+    println!("cargo:rerun-if-changed={}", EBPF_SOURCE);
+    for var in ["VMLINUX_H", "VMLINUX_BTF", "LIBBPF_INCLUDE", "CLANG", "BPFTOOL", "LINUX_AGENT_SKIP_EBPF"] {
+        println!("cargo:rerun-if-env-changed={}", var);
+    }
+
+    let out_dir = PathBuf::from(env::var("OUT_DIR").expect("OUT_DIR not set"));
+    let object = out_dir.join(EBPF_OBJECT);
+
+    // eBPF 툴체인이 없는 환경 (CI 등) 에서는 빈 오브젝트를 포함하고 실행 시 procfs 로 전환
+    if env::var_os("LINUX_AGENT_SKIP_EBPF").is_some() {
+        println!("cargo:warning=LINUX_AGENT_SKIP_EBPF is set, building without eBPF programs");
+        write_empty_object(&object);
+        return;
+    }
+
+    // 지원하지 않는 아키텍처이거나 clang, bpftool 이 없어도 빌드는 계속 (실행 시 procfs 로 전환)
+    if let Err(reason) = build_ebpf(&out_dir, &object) {
+        println!("cargo:warning={}, building without eBPF programs", reason);
+        write_empty_object(&object);
+    }
+}
+
+/// eBPF 프로그램 컴파일 (툴체인을 사용할 수 없으면 Err, 소스 컴파일 실패는 패닉)
+fn build_ebpf(out_dir: &Path, object: &Path) -> Result<(), String> {
+    let target_arch = env::var("CARGO_CFG_TARGET_ARCH").expect("CARGO_CFG_TARGET_ARCH not set");
+    let bpf_arch = match target_arch.as_str() {
+        "x86_64" => "x86",
+        "aarch64" => "arm64",
+        other => return Err(format!("unsupported target architecture for eBPF programs: {}", other)),
+    };
+
+    let include_dir = out_dir.join("include");
+    fs::create_dir_all(&include_dir).expect("failed to create eBPF include directory");
+    write_vmlinux_h(&include_dir.join("vmlinux.h"))?;
+
+    let libbpf_include = env::var("LIBBPF_INCLUDE").unwrap_or_else(|_| "/usr/include".to_string());
+    let clang = env::var("CLANG").unwrap_or_else(|_| "clang".to_string());
+
+    // -g: 로드 시 CO-RE 재배치에 필요한 BTF 포함
+    let status = Command::new(&clang)
+        .args(["-O2", "-g", "-target", "bpf"])
+        .arg(format!("-D__TARGET_ARCH_{}", bpf_arch))
+        .arg("-I")
+        .arg(&include_dir)
+        .arg("-I")
+        .arg(&libbpf_include)
+        .arg("-c")
+        .arg(EBPF_SOURCE)
+        .arg("-o")
+        .arg(object)
+        .status()
+        .map_err(|e| format!("failed to run {} (set CLANG or LINUX_AGENT_SKIP_EBPF): {}", clang, e))?;
+
+    if !status.success() {
+        panic!("failed to compile {} ({})", EBPF_SOURCE, status);
+    }
+    Ok(())
+}
+
+/// 빈 eBPF 오브젝트 (src/source/ebpf.rs 는 실행 시 eBPF 소스를 거부하고 procfs 로 전환)
+fn write_empty_object(object: &Path) {
+    fs::write(object, []).expect("failed to write empty eBPF object");
+}
+
+/// vmlinux.h 준비 (VMLINUX_H 가 없으면 커널 BTF 에서 생성)
+fn write_vmlinux_h(path: &Path) -> Result<(), String> {
+    if let Ok(vmlinux_h) = env::var("VMLINUX_H") {
+        println!("cargo:rerun-if-changed={}", vmlinux_h);
+        fs::copy(&vmlinux_h, path).unwrap_or_else(|e| panic!("failed to copy {}: {}", vmlinux_h, e));
+        return Ok(());
+    }
+
+    let btf = env::var("VMLINUX_BTF").unwrap_or_else(|_| "/sys/kernel/btf/vmlinux".to_string());
+    let bpftool = env::var("BPFTOOL").unwrap_or_else(|_| "bpftool".to_string());
+
+    let output = Command::new(&bpftool)
+        .args(["btf", "dump", "file", &btf, "format", "c"])
+        .output()
+        .map_err(|e| format!("failed to run {} (set BPFTOOL or VMLINUX_H): {}", bpftool, e))?;
+
+    // 커널 BTF 가 없는 빌드 환경도 툴체인이 없는 것과 같이 취급
+    if !output.status.success() {
+        return Err(format!("failed to generate vmlinux.h from {}: {}", btf, String::from_utf8_lossy(&output.stderr).trim()));
+    }
+
+    fs::write(path, output.stdout).expect("failed to write vmlinux.h");
+    Ok(())
+}
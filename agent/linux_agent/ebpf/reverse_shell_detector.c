@@ -1,19 +1,18 @@
-#include <linux/bpf.h>
-#include <linux/if_ether.h>
-#include <linux/ip.h>
-#include <linux/tcp.h>
-#include <linux/in.h>
-#include <linux/ptrace.h>
-#include <linux/sched.h>
-#include <linux/nsproxy.h>
-#include <linux/ns_common.h>
-#include <linux/pid_namespace.h>
-#include <linux/net_namespace.h>
-#include <linux/utsname.h>
-#include <linux/version.h>
+// CO-RE: 커널 구조체는 vmlinux.h (BTF) 기준으로 컴파일하고 로드 시 재배치됨
+#include "vmlinux.h"
 #include <bpf/bpf_helpers.h>
+#include <bpf/bpf_tracing.h>
+#include <bpf/bpf_core_read.h>
 #include <bpf/bpf_endian.h>
 
+// vmlinux.h 에는 #define 상수가 포함되지 않음
+#define ETH_P_IP 0x0800
+#define AT_EMPTY_PATH 0x1000
+#define S_IFMT 00170000
+#define S_IFSOCK 0140000
+#define AF_INET 2
+#define EPERM 1
+
 // 탐지된 이벤트를 저장할 맵
 struct {
     __uint(type, BPF_MAP_TYPE_PERF_EVENT_ARRAY);
@@ -38,6 +37,108 @@ struct {
     __uint(max_entries, 10000);
 } connection_tracker SEC(".maps");
 
+// 억제 규칙: 허용된 원격 주소 대역 (LPM)
+struct allow_cidr_key {
+    u32 prefixlen;
+    u32 addr;
+};
+
+struct {
+    __uint(type, BPF_MAP_TYPE_LPM_TRIE);
+    __type(key, struct allow_cidr_key);
+    __type(value, u8);
+    __uint(map_flags, BPF_F_NO_PREALLOC);
+    __uint(max_entries, 256);
+} allow_cidrs SEC(".maps");
+
+// 억제 규칙: 허용된 원격 포트
+struct {
+    __uint(type, BPF_MAP_TYPE_HASH);
+    __uint(key_size, sizeof(u16));
+    __uint(value_size, sizeof(u8));
+    __uint(max_entries, 256);
+} allow_ports SEC(".maps");
+
+// 억제 규칙: 허용된 UID
+struct {
+    __uint(type, BPF_MAP_TYPE_HASH);
+    __uint(key_size, sizeof(u32));
+    __uint(value_size, sizeof(u8));
+    __uint(max_entries, 256);
+} allow_uids SEC(".maps");
+
+// 대응 엔진: 차단할 연결 (주소와 포트는 호스트 바이트 순서)
+struct flow_key {
+    u32 local_addr;
+    u32 remote_addr;
+    u16 local_port;
+    u16 remote_port;
+    u8 protocol;
+    u8 pad[3];
+};
+
+struct {
+    __uint(type, BPF_MAP_TYPE_HASH);
+    __type(key, struct flow_key);
+    __type(value, u8);
+    __uint(max_entries, 1024);
+} blocked_flows SEC(".maps");
+
+// 대응 엔진: 차단할 원격 주소 (호스트 바이트 순서)
+struct {
+    __uint(type, BPF_MAP_TYPE_HASH);
+    __uint(key_size, sizeof(u32));
+    __uint(value_size, sizeof(u8));
+    __uint(max_entries, 1024);
+} blocked_destinations SEC(".maps");
+
+// LSM 차단: 적용할 cgroup v2 ID (0 이면 모든 프로세스)
+struct {
+    __uint(type, BPF_MAP_TYPE_HASH);
+    __uint(key_size, sizeof(u64));
+    __uint(value_size, sizeof(u8));
+    __uint(max_entries, 256);
+} enforced_cgroups SEC(".maps");
+
+// LSM 차단: 연결을 막을 원격 포트
+struct {
+    __uint(type, BPF_MAP_TYPE_HASH);
+    __uint(key_size, sizeof(u16));
+    __uint(value_size, sizeof(u8));
+    __uint(max_entries, 256);
+} blocked_ports SEC(".maps");
+
+// LSM 차단: 연결을 막을 원격 주소 대역 (allow_cidrs 와 같은 키 형식)
+struct {
+    __uint(type, BPF_MAP_TYPE_LPM_TRIE);
+    __type(key, struct allow_cidr_key);
+    __type(value, u8);
+    __uint(map_flags, BPF_F_NO_PREALLOC);
+    __uint(max_entries, 256);
+} blocked_cidrs SEC(".maps");
+
+// LSM 차단: 실행을 막을 파일 (커널 dev_t, inode)
+struct exec_key {
+    u64 ino;
+    u32 dev;
+    u32 pad;
+};
+
+struct {
+    __uint(type, BPF_MAP_TYPE_HASH);
+    __type(key, struct exec_key);
+    __type(value, u8);
+    __uint(max_entries, 256);
+} blocked_execs SEC(".maps");
+
+// LSM 차단 설정 (0번 항목이 1 이면 거부하지 않고 이벤트만 보냄)
+struct {
+    __uint(type, BPF_MAP_TYPE_ARRAY);
+    __uint(key_size, sizeof(u32));
+    __uint(value_size, sizeof(u32));
+    __uint(max_entries, 1);
+} lsm_audit_only SEC(".maps");
+
 // 이벤트 구조체
 struct reverse_shell_event {
     u32 local_addr;
@@ -50,6 +151,100 @@ struct reverse_shell_event {
     u8 severity;
 };
 
+// 억제 규칙에 등록된 UID 인지 확인
+static inline int is_allowed_uid(void)
+{
+    u32 uid = bpf_get_current_uid_gid() & 0xFFFFFFFF;
+    return bpf_map_lookup_elem(&allow_uids, &uid) != NULL;
+}
+
+// 프라이빗 IP 주소 확인 함수
+static inline int is_private_ip(u32 ip)
+{
+    // 127.0.0.0/8
+    if ((ip & 0xFF000000) == 0x7F000000)
+        return 1;
+    
+    // 10.0.0.0/8
+    if ((ip & 0xFF000000) == 0x0A000000)
+        return 1;
+    
+    // 172.16.0.0/12
+    if ((ip & 0xFFF00000) == 0xAC100000)
+        return 1;
+    
+    // 192.168.0.0/16
+    if ((ip & 0xFFFF0000) == 0xC0A80000)
+        return 1;
+    
+    return 0;
+}
+
+// 문자열 비교 함수 (libbpf 의 bpf_strncmp 헬퍼와 이름이 겹치지 않도록 comm_strncmp 사용)
+static inline int comm_strncmp(const char *s1, const char *s2, int n)
+{
+    for (int i = 0; i < n; i++) {
+        if (s1[i] != s2[i])
+            return s1[i] - s2[i];
+        if (s1[i] == '\0')
+            return 0;
+    }
+    return 0;
+}
+
+// 의심스러운 프로세스 확인 함수
+static inline int is_suspicious_process(char *comm)
+{
+    // 간단한 패턴 매칭
+    char suspicious_patterns[][16] = {
+        "nc", "netcat", "bash", "sh", "python", "perl", "ruby", "php",
+        "wget", "curl", "ftp", "telnet", "ssh", "scp", "rsync"
+    };
+    
+    for (int i = 0; i < 15; i++) {
+        if (comm_strncmp(comm, suspicious_patterns[i], 16) == 0)
+            return 1;
+    }
+    
+    return 0;
+}
+
+// 바인드 쉘로 사용되는 쉘/인터프리터 확인 함수
+static inline int is_shell_interpreter(char *comm)
+{
+    char interpreters[][16] = {
+        "sh", "bash", "dash", "zsh", "nc", "ncat", "netcat", "socat",
+        "python", "python3", "perl", "ruby", "php"
+    };
+
+    for (int i = 0; i < 13; i++) {
+        if (comm_strncmp(comm, interpreters[i], 16) == 0)
+            return 1;
+    }
+
+    return 0;
+}
+
+// 대응 엔진이 차단한 연결인지 확인 (수신 패킷과 송신 방향 패킷 모두)
+static __always_inline int is_blocked_flow(struct iphdr *ip, struct tcphdr *tcp)
+{
+    struct flow_key key;
+    __builtin_memset(&key, 0, sizeof(key));
+    key.local_addr = bpf_ntohl(ip->daddr);
+    key.remote_addr = bpf_ntohl(ip->saddr);
+    key.local_port = bpf_ntohs(tcp->dest);
+    key.remote_port = bpf_ntohs(tcp->source);
+    key.protocol = IPPROTO_TCP;
+    if (bpf_map_lookup_elem(&blocked_flows, &key))
+        return 1;
+
+    key.local_addr = bpf_ntohl(ip->saddr);
+    key.remote_addr = bpf_ntohl(ip->daddr);
+    key.local_port = bpf_ntohs(tcp->source);
+    key.remote_port = bpf_ntohs(tcp->dest);
+    return bpf_map_lookup_elem(&blocked_flows, &key) != NULL;
+}
+
 // 의심스러운 포트 초기화
 SEC("xdp")
 int reverse_shell_detector(struct xdp_md *ctx)
@@ -69,6 +264,12 @@ int reverse_shell_detector(struct xdp_md *ctx)
     if ((void *)(ip + 1) > data_end)
         return XDP_PASS;
     
+    // 대응 엔진이 차단한 주소와 주고받는 패킷은 버림
+    u32 saddr = bpf_ntohl(ip->saddr);
+    u32 daddr = bpf_ntohl(ip->daddr);
+    if (bpf_map_lookup_elem(&blocked_destinations, &saddr) || bpf_map_lookup_elem(&blocked_destinations, &daddr))
+        return XDP_DROP;
+    
     // TCP 패킷인지 확인
     if (ip->protocol != IPPROTO_TCP)
         return XDP_PASS;
@@ -77,15 +278,29 @@ int reverse_shell_detector(struct xdp_md *ctx)
     if ((void *)(tcp + 1) > data_end)
         return XDP_PASS;
     
+    if (is_blocked_flow(ip, tcp))
+        return XDP_DROP;
+    
     // SYN 패킷인지 확인 (새로운 연결)
     if (!(tcp->syn && !tcp->ack))
         return XDP_PASS;
     
-    u32 local_addr = ip->saddr;
-    u32 remote_addr = ip->daddr;
+    // 이벤트의 주소는 호스트 바이트 순서 (유저스페이스의 u32 주소와 동일)
+    u32 local_addr = bpf_ntohl(ip->saddr);
+    u32 remote_addr = bpf_ntohl(ip->daddr);
     u16 local_port = bpf_ntohs(tcp->source);
     u16 remote_port = bpf_ntohs(tcp->dest);
     
+    // 억제 규칙에 해당하는 연결은 유저스페이스로 보내지 않음 (LPM 키는 네트워크 바이트 순서)
+    struct allow_cidr_key allow_key = {
+        .prefixlen = 32,
+        .addr = ip->daddr,
+    };
+    if (bpf_map_lookup_elem(&allow_cidrs, &allow_key))
+        return XDP_PASS;
+    if (bpf_map_lookup_elem(&allow_ports, &remote_port))
+        return XDP_PASS;
+    
     // 의심스러운 포트인지 확인
     u8 *is_suspicious = bpf_map_lookup_elem(&suspicious_ports, &remote_port);
     if (is_suspicious) {
@@ -128,6 +343,9 @@ int reverse_shell_detector(struct xdp_md *ctx)
 SEC("tracepoint/syscalls/sys_enter_execve")
 int trace_execve(struct trace_event_raw_sys_enter *ctx)
 {
+    if (is_allowed_uid())
+        return 0;
+
     u32 pid = bpf_get_current_pid_tgid() >> 32;
     u32 tgid = bpf_get_current_pid_tgid() & 0xFFFFFFFF;
     
@@ -158,6 +376,9 @@ int trace_execve(struct trace_event_raw_sys_enter *ctx)
 SEC("tracepoint/syscalls/sys_enter_socket")
 int trace_socket(struct trace_event_raw_sys_enter *ctx)
 {
+    if (is_allowed_uid())
+        return 0;
+
     u32 pid = bpf_get_current_pid_tgid() >> 32;
     
     // 소켓 생성 시도 모니터링
@@ -181,6 +402,9 @@ int trace_socket(struct trace_event_raw_sys_enter *ctx)
 SEC("tracepoint/syscalls/sys_enter_connect")
 int trace_connect(struct trace_event_raw_sys_enter *ctx)
 {
+    if (is_allowed_uid())
+        return 0;
+
     u32 pid = bpf_get_current_pid_tgid() >> 32;
     
     // connect 시스템 콜 모니터링
@@ -200,54 +424,253 @@ int trace_connect(struct trace_event_raw_sys_enter *ctx)
     return 0;
 }
 
-// 프라이빗 IP 주소 확인 함수
-static inline int is_private_ip(u32 ip)
+// listen 시스템 콜 후킹 (바인드 쉘)
+SEC("tracepoint/syscalls/sys_enter_listen")
+int trace_listen(struct trace_event_raw_sys_enter *ctx)
 {
-    // 127.0.0.0/8
-    if ((ip & 0xFF000000) == 0x7F000000)
-        return 1;
-    
-    // 10.0.0.0/8
-    if ((ip & 0xFF000000) == 0x0A000000)
-        return 1;
-    
-    // 172.16.0.0/12
-    if ((ip & 0xFFF00000) == 0xAC100000)
-        return 1;
-    
-    // 192.168.0.0/16
-    if ((ip & 0xFFFF0000) == 0xC0A80000)
-        return 1;
-    
+    if (is_allowed_uid())
+        return 0;
+
+    u32 pid = bpf_get_current_pid_tgid() >> 32;
+
+    char comm[16];
+    bpf_get_current_comm(&comm, sizeof(comm));
+
+    // 쉘/인터프리터가 직접 listen 하는 경우만 전송
+    if (!is_shell_interpreter(comm))
+        return 0;
+
+    struct reverse_shell_event event = {
+        .local_addr = 0,
+        .remote_addr = 0,
+        .local_port = 0,
+        .remote_port = 0,
+        .pid = pid,
+        .timestamp = bpf_ktime_get_ns(),
+        .event_type = 6, // BindShell
+        .severity = 3,   // Critical
+    };
+
+    bpf_perf_event_output(ctx, &events, BPF_F_CURRENT_CPU, &event, sizeof(event));
+
     return 0;
 }
 
-// 의심스러운 프로세스 확인 함수
-static inline int is_suspicious_process(char *comm)
+// TCP 리스닝 시작 후킹 (바인드 쉘)
+// BPF_KPROBE 는 __TARGET_ARCH_* 에 맞춰 x86_64/arm64 의 pt_regs 에서 인자를 읽음
+SEC("kprobe/inet_csk_listen_start")
+int BPF_KPROBE(trace_inet_csk_listen_start, struct sock *sk)
 {
-    // 간단한 패턴 매칭
-    char suspicious_patterns[][16] = {
-        "nc", "netcat", "bash", "sh", "python", "perl", "ruby", "php",
-        "wget", "curl", "ftp", "telnet", "ssh", "scp", "rsync"
+    if (is_allowed_uid())
+        return 0;
+
+    u32 pid = bpf_get_current_pid_tgid() >> 32;
+
+    char comm[16];
+    bpf_get_current_comm(&comm, sizeof(comm));
+
+    // skc_num 은 호스트 바이트 순서, skc_rcv_saddr 는 네트워크 바이트 순서
+    u16 local_port = BPF_CORE_READ(sk, __sk_common.skc_num);
+    u32 local_addr = bpf_ntohl(BPF_CORE_READ(sk, __sk_common.skc_rcv_saddr));
+
+    // 모든 새 리스닝 소켓을 전송하고 인터프리터만 Critical 로 표시
+    struct reverse_shell_event event = {
+        .local_addr = local_addr,
+        .remote_addr = 0,
+        .local_port = local_port,
+        .remote_port = 0,
+        .pid = pid,
+        .timestamp = bpf_ktime_get_ns(),
+        .event_type = 6, // BindShell
+        .severity = is_shell_interpreter(comm) ? 3 : 1,
     };
-    
-    for (int i = 0; i < 15; i++) {
-        if (bpf_strncmp(comm, suspicious_patterns[i], 16) == 0)
-            return 1;
-    }
-    
+
+    bpf_perf_event_output(ctx, &events, BPF_F_CURRENT_CPU, &event, sizeof(event));
+
     return 0;
 }
 
-// 문자열 비교 함수
-static inline int bpf_strncmp(const char *s1, const char *s2, int n)
+// memfd_create 후킹 (파일리스 실행 준비 단계)
+SEC("tracepoint/syscalls/sys_enter_memfd_create")
+int trace_memfd_create(struct trace_event_raw_sys_enter *ctx)
 {
-    for (int i = 0; i < n; i++) {
-        if (s1[i] != s2[i])
-            return s1[i] - s2[i];
-        if (s1[i] == '\0')
-            return 0;
-    }
+    if (is_allowed_uid())
+        return 0;
+
+    u32 pid = bpf_get_current_pid_tgid() >> 32;
+
+    struct reverse_shell_event event = {
+        .local_addr = 0,
+        .remote_addr = 0,
+        .local_port = 0,
+        .remote_port = 0,
+        .pid = pid,
+        .timestamp = bpf_ktime_get_ns(),
+        .event_type = 7, // MemfdCreate
+        .severity = 1,   // Medium
+    };
+
+    bpf_perf_event_output(ctx, &events, BPF_F_CURRENT_CPU, &event, sizeof(event));
+
+    return 0;
+}
+
+// execveat 후킹 (fexecve 는 AT_EMPTY_PATH 로 fd 를 직접 실행)
+SEC("tracepoint/syscalls/sys_enter_execveat")
+int trace_execveat(struct trace_event_raw_sys_enter *ctx)
+{
+    if (is_allowed_uid())
+        return 0;
+
+    u32 pid = bpf_get_current_pid_tgid() >> 32;
+    long flags = ctx->args[4];
+
+    struct reverse_shell_event event = {
+        .local_addr = 0,
+        .remote_addr = 0,
+        .local_port = 0,
+        .remote_port = 0,
+        .pid = pid,
+        .timestamp = bpf_ktime_get_ns(),
+        .event_type = 8, // FilelessExec
+        .severity = (flags & AT_EMPTY_PATH) ? 3 : 1, // fd 를 직접 실행하면 Critical
+    };
+
+    bpf_perf_event_output(ctx, &events, BPF_F_CURRENT_CPU, &event, sizeof(event));
+
+    return 0;
+}
+
+// fd 가 소켓을 가리키는지 확인 (task->files->fdt->fd[fd]->f_inode->i_mode)
+static __always_inline int fd_is_socket(int fd)
+{
+    if (fd < 0 || fd >= 65536)
+        return 0;
+
+    struct task_struct *task = (struct task_struct *)bpf_get_current_task();
+    struct file **fds = BPF_CORE_READ(task, files, fdt, fd);
+    unsigned int max_fds = BPF_CORE_READ(task, files, fdt, max_fds);
+    if (!fds || (unsigned int)fd >= max_fds)
+        return 0;
+
+    struct file *file = NULL;
+    bpf_probe_read_kernel(&file, sizeof(file), &fds[fd]);
+    if (!file)
+        return 0;
+
+    umode_t mode = BPF_CORE_READ(file, f_inode, i_mode);
+    return (mode & S_IFMT) == S_IFSOCK;
+}
+
+// 소켓을 표준 입출력으로 복제하는 경우 전송 (리버스 쉘의 dup2(sock, 0..2))
+static __always_inline int report_stdio_redirect(void *ctx, long oldfd, long newfd)
+{
+    if (newfd < 0 || newfd > 2)
+        return 0;
+    if (is_allowed_uid())
+        return 0;
+    if (!fd_is_socket(oldfd))
+        return 0;
+
+    u32 pid = bpf_get_current_pid_tgid() >> 32;
+
+    struct reverse_shell_event event = {
+        .local_addr = 0,
+        .remote_addr = 0,
+        .local_port = 0,
+        .remote_port = newfd,
+        .pid = pid,
+        .timestamp = bpf_ktime_get_ns(),
+        .event_type = 9, // StdioRedirect
+        .severity = 2,   // High
+    };
+
+    bpf_perf_event_output(ctx, &events, BPF_F_CURRENT_CPU, &event, sizeof(event));
+
+    return 0;
+}
+
+// dup2 후킹 (arm64 에는 dup2 시스템 콜이 없어 dup3 만 부착됨)
+SEC("tracepoint/syscalls/sys_enter_dup2")
+int trace_dup2(struct trace_event_raw_sys_enter *ctx)
+{
+    return report_stdio_redirect(ctx, ctx->args[0], ctx->args[1]);
+}
+
+// dup3 후킹
+SEC("tracepoint/syscalls/sys_enter_dup3")
+int trace_dup3(struct trace_event_raw_sys_enter *ctx)
+{
+    return report_stdio_redirect(ctx, ctx->args[0], ctx->args[1]);
+}
+
+// LSM 차단 대상 cgroup 의 프로세스인지 확인
+static __always_inline int is_enforced_cgroup(void)
+{
+    u64 cgroup_id = bpf_get_current_cgroup_id();
+    u64 all = 0;
+    return bpf_map_lookup_elem(&enforced_cgroups, &cgroup_id) || bpf_map_lookup_elem(&enforced_cgroups, &all);
+}
+
+// 거부 이벤트를 보내고 LSM 반환값 결정 (감사 모드면 허용)
+static __always_inline int deny(void *ctx, u8 event_type, u32 remote_addr, u16 remote_port)
+{
+    struct reverse_shell_event event = {
+        .remote_addr = remote_addr,
+        .remote_port = remote_port,
+        .pid = bpf_get_current_pid_tgid() >> 32,
+        .timestamp = bpf_ktime_get_ns(),
+        .event_type = event_type,
+        .severity = 2,
+    };
+    bpf_perf_event_output(ctx, &events, BPF_F_CURRENT_CPU, &event, sizeof(event));
+
+    u32 key = 0;
+    u32 *audit_only = bpf_map_lookup_elem(&lsm_audit_only, &key);
+    return audit_only && *audit_only ? 0 : -EPERM;
+}
+
+// 차단된 포트, 주소 대역으로의 IPv4 연결 거부 (lsm=bpf 필요)
+SEC("lsm/socket_connect")
+int BPF_PROG(enforce_connect, struct socket *sock, struct sockaddr *address, int addrlen, int ret)
+{
+    // 앞선 LSM 이 이미 거부한 경우
+    if (ret != 0)
+        return ret;
+    if (addrlen < (int)sizeof(struct sockaddr_in) || BPF_CORE_READ(address, sa_family) != AF_INET)
+        return 0;
+    if (!is_enforced_cgroup())
+        return 0;
+
+    struct sockaddr_in *sin = (struct sockaddr_in *)address;
+    u16 port = bpf_ntohs(BPF_CORE_READ(sin, sin_port));
+    u32 daddr = BPF_CORE_READ(sin, sin_addr.s_addr);
+    struct allow_cidr_key key = {
+        .prefixlen = 32,
+        .addr = daddr,
+    };
+
+    if (bpf_map_lookup_elem(&blocked_ports, &port) || bpf_map_lookup_elem(&blocked_cidrs, &key))
+        return deny(ctx, 10, bpf_ntohl(daddr), port); // ConnectDenied
+    return 0;
+}
+
+// 차단된 실행 파일의 실행 거부 (lsm=bpf 필요)
+SEC("lsm/bprm_check_security")
+int BPF_PROG(enforce_exec, struct linux_binprm *bprm, int ret)
+{
+    if (ret != 0)
+        return ret;
+    if (!is_enforced_cgroup())
+        return 0;
+
+    struct exec_key key;
+    __builtin_memset(&key, 0, sizeof(key));
+    key.ino = BPF_CORE_READ(bprm, file, f_inode, i_ino);
+    key.dev = BPF_CORE_READ(bprm, file, f_inode, i_sb, s_dev);
+
+    if (bpf_map_lookup_elem(&blocked_execs, &key))
+        return deny(ctx, 11, 0, 0); // ExecDenied
     return 0;
 }
 
@@ -0,0 +1,461 @@
+use std::sync::Arc;
+use anyhow::{anyhow, Result};
+use log::{info, warn};
+use serde::Serialize;
+use tokio::sync::mpsc;
+
+use crate::http::{self, Listener, Request, Response};
+use crate::plugins::reverse_shell::{
+    unix_millis, ConnectionInfo, EventType, ReverseShellDetector, ReverseShellEvent, Severity,
+};
+
+const JSON: &str = "application/json";
+const NDJSON: &str = "application/x-ndjson";
+const CSV: &str = "text/csv";
+const TEXT: &str = "text/plain; charset=utf-8";
+
+/// `/risk` 에서 기본으로 보여줄 프로세스 트리 수
+const RISK_TREES: usize = 20;
+
+/// 스트리밍 연결별 전송 대기 줄 수
+const STREAM_BUFFER: usize = 64;
+
+/// 이벤트 조회 조건
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EventFilter {
+    /// 이 시각(유닉스 밀리초) 이후 이벤트만
+    pub since: Option<u64>,
+    /// 이 심각도 이상만
+    pub severity: Option<Severity>,
+    pub event_type: Option<EventType>,
+    pub rule_id: Option<String>,
+    /// 최근 이벤트 최대 개수
+    pub limit: Option<usize>,
+}
+
+/// 연결 테이블
+#[derive(Serialize)]
+struct ConnectionTable {
+    connections: Vec<ConnectionInfo>,
+    listeners: Vec<ConnectionInfo>,
+}
+
+impl EventFilter {
+    /// 쿼리 파라미터 (`since`, `severity`, `type`, `rule_id`, `limit`) 파싱
+    pub fn from_request(request: &Request) -> Result<Self> {
+        let parse_number = |key: &str| -> Result<Option<u64>> {
+            request.query(key)
+                .map(|value| value.parse().map_err(|_| anyhow!("invalid {}: {}", key, value)))
+                .transpose()
+        };
+
+        Ok(Self {
+            since: parse_number("since")?,
+            severity: request.query("severity")
+                .map(|value| Severity::parse(value).ok_or_else(|| anyhow!("invalid severity: {}", value)))
+                .transpose()?,
+            event_type: request.query("type")
+                .map(|value| EventType::parse(value).ok_or_else(|| anyhow!("invalid type: {}", value)))
+                .transpose()?,
+            rule_id: request.query("rule_id").map(str::to_string),
+            limit: parse_number("limit")?.map(|limit| limit as usize),
+        })
+    }
+
+    pub fn matches(&self, event: &ReverseShellEvent) -> bool {
+        self.since.is_none_or(|since| unix_millis(event.timestamp) >= since)
+            && self.severity.as_ref().is_none_or(|severity| event.severity >= *severity)
+            && self.event_type.as_ref().is_none_or(|event_type| event.event_type == *event_type)
+            && self.rule_id.as_ref().is_none_or(|rule_id| event.rule_id == *rule_id)
+    }
+
+    /// 조건에 맞는 이벤트 (limit 이 있으면 최근 이벤트만)
+    pub fn apply(&self, events: Vec<ReverseShellEvent>) -> Vec<ReverseShellEvent> {
+        let mut matched: Vec<ReverseShellEvent> = events.into_iter().filter(|event| self.matches(event)).collect();
+        if let Some(limit) = self.limit {
+            matched.drain(..matched.len().saturating_sub(limit));
+        }
+        matched
+    }
+}
+
+/// 인증 없는 API 를 외부에 노출하지 않도록 루프백 주소나 유닉스 소켓이 아니면 명시적으로 허용한 경우만 사용
+pub fn check_exposure(listener: &Listener, allow_remote: bool) -> Result<()> {
+    if !listener.is_local() && !allow_remote {
+        return Err(anyhow!(
+            "query API address {} is not loopback or a unix socket; the API has no authentication and can switch rounds (pass --api-allow-remote to expose it anyway)",
+            listener.local_addr()
+        ));
+    }
+    Ok(())
+}
+
+/// 로컬 조회 API 제공
+pub async fn serve(listener: Listener, detector: Arc<ReverseShellDetector>) {
+    if !listener.is_local() {
+        warn!("Query API is listening on a non-loopback address ({}) without authentication", listener.local_addr());
+    }
+    info!("Serving query API on {}", listener.local_addr());
+
+    http::serve(listener, move |request: Request| {
+        let detector = Arc::clone(&detector);
+        async move { handle(&detector, request).await }
+    }).await;
+}
+
+async fn handle(detector: &Arc<ReverseShellDetector>, request: Request) -> Response {
+    if request.method == "POST" {
+        return control(detector, request).await;
+    }
+    if request.method != "GET" {
+        return Response::error(405, "method not allowed");
+    }
+
+    match request.path.as_str() {
+        "/health" => json(&detector.health().await),
+        "/events" => match EventFilter::from_request(&request) {
+            Ok(filter) => json(&filter.apply(detector.get_detected_events().await)),
+            Err(e) => Response::error(400, &e.to_string()),
+        },
+        "/events/stream" => match EventFilter::from_request(&request) {
+            Ok(filter) => stream_events(detector, filter).await,
+            Err(e) => Response::error(400, &e.to_string()),
+        },
+        "/connections" => json(&ConnectionTable {
+            connections: detector.get_suspicious_connections().await,
+            listeners: detector.get_listening_sockets().await,
+        }),
+        "/processes" => json(&detector.process_tree().await),
+        "/rules" => json(&detector.rules().await),
+        "/coverage" => json(&detector.attack_coverage().await),
+        "/incidents" => json(&detector.get_incidents().await),
+        path if path.starts_with("/incidents/") => match path["/incidents/".len()..].parse() {
+            Ok(id) => match detector.get_incident(id).await {
+                Some(incident) => json(&incident),
+                None => Response::not_found(),
+            },
+            Err(_) => Response::error(400, "invalid incident id"),
+        },
+        "/scoreboard" => json(&detector.scoreboard().await),
+        "/scoreboard.csv" => Response::ok(CSV, detector.scoreboard_csv().await),
+        "/rounds" => json(&detector.round_summaries().await),
+        path if path.starts_with("/rounds/") => {
+            let target = &path["/rounds/".len()..];
+            let (id, report) = target.strip_suffix("/report").map_or((target, false), |id| (id, true));
+            match id.parse() {
+                Ok(id) if report => match detector.generate_round_report(id).await {
+                    Some(report) => Response::ok(TEXT, report),
+                    None => Response::not_found(),
+                },
+                Ok(id) => match detector.round_summary(id).await {
+                    Some(summary) => json(&summary),
+                    None => Response::not_found(),
+                },
+                Err(_) => Response::error(400, "invalid round id"),
+            }
+        }
+        "/risk" => match request.query("limit").map(str::parse::<usize>).transpose() {
+            Ok(limit) => json(&detector.risk_summary(limit.unwrap_or(RISK_TREES)).await),
+            Err(_) => Response::error(400, "invalid limit"),
+        },
+        _ => Response::not_found(),
+    }
+}
+
+/// 라운드 시작/종료 (`POST /rounds/start?id=3`, `POST /rounds/stop`)
+async fn control(detector: &ReverseShellDetector, request: Request) -> Response {
+    match request.path.as_str() {
+        "/rounds/start" => match request.query("id").map(str::parse::<u64>).transpose() {
+            Ok(id) => match detector.start_round(id).await {
+                Ok(round) => json(&round),
+                Err(e) => Response::error(409, &e.to_string()),
+            },
+            Err(_) => Response::error(400, "invalid round id"),
+        },
+        "/rounds/stop" => match detector.stop_round().await {
+            Some(round) => json(&round),
+            None => Response::error(409, "no round in progress"),
+        },
+        _ => Response::error(405, "method not allowed"),
+    }
+}
+
+fn json<T: Serialize>(value: &T) -> Response {
+    match serde_json::to_vec(value) {
+        Ok(body) => Response::ok(JSON, body),
+        Err(e) => Response::error(500, &e.to_string()),
+    }
+}
+
+/// 이후 탐지되는 이벤트를 한 줄에 하나씩 전송 (클라이언트가 끊거나 에이전트가 종료될 때까지)
+async fn stream_events(detector: &ReverseShellDetector, filter: EventFilter) -> Response {
+    let mut subscription = detector.subscribe("api_stream").await;
+    let (tx, rx) = mpsc::channel(STREAM_BUFFER);
+
+    tokio::spawn(async move {
+        loop {
+            let event = tokio::select! {
+                event = subscription.recv() => event,
+                _ = tx.closed() => break,
+            };
+            let Some(event) = event else { break };
+            if !filter.matches(&event) {
+                continue;
+            }
+
+            let Ok(mut line) = serde_json::to_vec(&*event) else { continue };
+            line.push(b'\n');
+            if tx.send(line).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    Response::stream(NDJSON, rx)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use std::time::Instant;
+    use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::UnixStream;
+    use crate::source::replay::ReplaySource;
+
+    fn replay_path() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/replay/ctf_reverse_shell.jsonl")
+    }
+
+    fn socket_path(name: &str) -> String {
+        std::env::temp_dir().join(format!("api-{}-{}.sock", name, std::process::id())).display().to_string()
+    }
+
+    async fn request(method: &str, path: &str, target: &str) -> (String, String) {
+        let mut stream = UnixStream::connect(path).await.unwrap();
+        stream.write_all(format!("{} {} HTTP/1.1\r\n\r\n", method, target).as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+
+        let (head, body) = response.split_once("\r\n\r\n").unwrap();
+        (head.to_string(), body.to_string())
+    }
+
+    async fn get_text(path: &str, target: &str) -> (String, String) {
+        request("GET", path, target).await
+    }
+
+    async fn get(path: &str, target: &str) -> (String, serde_json::Value) {
+        let (head, body) = get_text(path, target).await;
+        (head, serde_json::from_str(&body).unwrap_or(serde_json::Value::Null))
+    }
+
+    #[test]
+    fn test_event_filter() {
+        let request = |target: &str| crate::http::Request {
+            method: "GET".to_string(),
+            path: "/events".to_string(),
+            query: target.split('&')
+                .filter_map(|pair| pair.split_once('='))
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        };
+        let event = |severity: Severity, rule_id: &str| ReverseShellEvent {
+            timestamp: Instant::now(),
+            event_type: EventType::BindShellDetected,
+            severity,
+            rule_id: rule_id.to_string(),
+            details: String::new(),
+            connection_info: None,
+            process: None,
+            incident_id: None,
+            round_id: None,
+            techniques: Vec::new(),
+        };
+
+        let filter = EventFilter::from_request(&request("severity=high&type=bind_shell_detected&limit=1")).unwrap();
+        let events = filter.apply(vec![
+            event(Severity::Critical, "interpreter_listen"),
+            event(Severity::Medium, "new_listen_port"),
+            event(Severity::High, "kernel_listen"),
+        ]);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].rule_id, "kernel_listen");
+
+        assert!(!EventFilter::from_request(&request("since=99999999999999")).unwrap()
+            .matches(&event(Severity::Critical, "interpreter_listen")));
+        assert!(EventFilter::from_request(&request("severity=urgent")).is_err());
+        assert!(EventFilter::from_request(&request("type=fileless_execution")).unwrap().event_type.is_some());
+        assert!(EventFilter::from_request(&request("limit=-1")).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_check_exposure() {
+        let remote = Listener::bind("0.0.0.0:0").await.unwrap();
+        assert!(check_exposure(&remote, false).is_err());
+        assert!(check_exposure(&remote, true).is_ok());
+        assert!(check_exposure(&Listener::bind("127.0.0.1:0").await.unwrap(), false).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_query_endpoints() {
+        let detector = Arc::new(ReverseShellDetector::new());
+        detector.start_with_source(Box::new(ReplaySource::new(replay_path(), false))).await.unwrap();
+        detector.wait_until_drained().await;
+
+        let path = socket_path("query");
+        tokio::spawn(serve(Listener::bind(&path).await.unwrap(), Arc::clone(&detector)));
+
+        let (head, events) = get(&path, "/events?severity=critical&type=bind_shell_detected").await;
+        assert!(head.contains("Content-Type: application/json"));
+        assert_eq!(events.as_array().unwrap().len(), 1);
+        assert_eq!(events[0]["rule_id"], "interpreter_listen");
+
+        let (_, health) = get(&path, "/health").await;
+        assert_eq!(health["source"], "replay");
+        assert_eq!(health["events"], 4);
+        assert_eq!(health["listeners"], 3);
+
+        let (_, connections) = get(&path, "/connections").await;
+        assert_eq!(connections["connections"][0]["remote_addr"], "203.0.113.10");
+        assert_eq!(connections["connections"][0]["remote_port"], 9001);
+
+        let (_, rules) = get(&path, "/rules").await;
+        assert!(rules["detection"].as_array().unwrap().iter().any(|rule| rule["rule_id"] == "memfd_executable"));
+        assert_eq!(rules["baseline_mode"], "disabled");
+
+        let (_, coverage) = get(&path, "/coverage").await;
+        assert_eq!(coverage[0]["tactic"], "execution");
+        assert_eq!(events[0]["techniques"][0]["id"], "T1059.004");
+
+        let (_, incidents) = get(&path, "/incidents").await;
+        assert_eq!(incidents.as_array().unwrap().len(), 3);
+        assert_eq!(incidents[0]["timeline"].as_array().unwrap().len(), 2);
+        let (_, incident) = get(&path, "/incidents/1").await;
+        assert_eq!(incident["pids"], serde_json::json!([31337]));
+        assert_eq!(events[0]["incident_id"], 2);
+        let (head, _) = get(&path, "/incidents/7").await;
+        assert!(head.starts_with("HTTP/1.1 404"));
+
+        let (_, processes) = get(&path, "/processes").await;
+        assert!(processes.is_array());
+
+        // 가장 위험한 프로세스 트리는 memfd 실행 프로세스
+        let (_, risk) = get(&path, "/risk?limit=1").await;
+        assert_eq!(risk["processes"].as_array().unwrap().len(), 1);
+        assert_eq!(risk["processes"][0]["pid"], 31337);
+        assert_eq!(risk["processes"][0]["signals"][0], "fileless");
+        assert!(health["host_risk_score"].as_f64().unwrap() > 0.0);
+
+        let (head, _) = get(&path, "/events?severity=urgent").await;
+        assert!(head.starts_with("HTTP/1.1 400"));
+        let (head, _) = get(&path, "/nope").await;
+        assert!(head.starts_with("HTTP/1.1 404"));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_scoreboard_feed() {
+        use crate::scoring::{ScoringConfig, TeamMapping};
+
+        let detector = Arc::new(ReverseShellDetector::new());
+        let team = |name: &str, host: &str| TeamMapping { team: name.to_string(), hosts: vec![host.to_string()], cgroups: Vec::new() };
+        detector.set_scoring_config(ScoringConfig {
+            host: Some("vuln-3".to_string()),
+            teams: vec![team("team-3", "vuln-3"), team("team-4", "vuln-4")],
+            ..ScoringConfig::default()
+        }).await;
+        detector.start_with_source(Box::new(ReplaySource::new(replay_path(), false))).await.unwrap();
+        detector.wait_until_drained().await;
+
+        let path = socket_path("scoreboard");
+        tokio::spawn(serve(Listener::bind(&path).await.unwrap(), Arc::clone(&detector)));
+
+        // CTFd 형식 피드를 받아 점수 순으로 순위를 매기는 모의 스코어보드
+        let (_, feed) = get(&path, "/scoreboard").await;
+        assert_eq!(feed["success"], true);
+        let mut board: Vec<(String, i64)> = feed["data"].as_array().unwrap().iter()
+            .map(|team| (team["name"].as_str().unwrap().to_string(), team["score"].as_i64().unwrap()))
+            .collect();
+        board.sort_by_key(|(_, score)| std::cmp::Reverse(*score));
+        assert_eq!(board, vec![("team-4".to_string(), 0), ("team-3".to_string(), -155)]);
+        assert_eq!(feed["data"][1]["rules"]["memfd_executable"]["penalty"], 50);
+
+        let (head, csv) = get_text(&path, "/scoreboard.csv").await;
+        assert!(head.contains("Content-Type: text/csv"));
+        assert_eq!(csv.lines().nth(2), Some("2,team-3,-155,155,4"));
+        assert!(detector.generate_report().await.contains("Scoreboard: 1. team-4 0 (0 detections), 2. team-3 -155 (4 detections)"));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_round_control() {
+        let detector = Arc::new(ReverseShellDetector::new());
+        let path = socket_path("rounds");
+        tokio::spawn(serve(Listener::bind(&path).await.unwrap(), Arc::clone(&detector)));
+
+        let round: serde_json::Value = serde_json::from_slice(&crate::http::post(&path, "/rounds/start?id=3").await.unwrap()).unwrap();
+        assert_eq!(round["id"], 3);
+        assert!(round["ended_at"].is_null());
+        let (_, health) = get(&path, "/health").await;
+        assert_eq!(health["round"], 3);
+
+        let (head, _) = request("POST", &path, "/rounds/start?id=3").await;
+        assert!(head.starts_with("HTTP/1.1 409 Conflict\r\n"));
+        let (head, _) = request("POST", &path, "/rounds/start?id=x").await;
+        assert!(head.starts_with("HTTP/1.1 400 Bad Request\r\n"));
+
+        detector.start_with_source(Box::new(ReplaySource::new(replay_path(), false))).await.unwrap();
+        detector.wait_until_drained().await;
+        let (head, _) = request("POST", &path, "/rounds/stop").await;
+        assert!(head.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(crate::http::post(&path, "/rounds/stop").await.is_err());
+
+        let (_, rounds) = get(&path, "/rounds").await;
+        assert_eq!(rounds[0]["id"], 3);
+        assert_eq!(rounds[0]["events"], 4);
+        assert_eq!(rounds[0]["incidents"], serde_json::json!([1, 2, 3]));
+        let (_, events) = get(&path, "/events").await;
+        assert_eq!(events[0]["round_id"], 3);
+
+        let (head, report) = get_text(&path, "/rounds/3/report").await;
+        assert!(head.contains("Content-Type: text/plain"));
+        assert!(report.starts_with("Round 3 Report"));
+        assert!(report.contains("Status: ended after "));
+        let (head, _) = get(&path, "/rounds/9").await;
+        assert!(head.starts_with("HTTP/1.1 404"));
+        let (head, _) = request("DELETE", &path, "/rounds/3").await;
+        assert!(head.starts_with("HTTP/1.1 405"));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_event_stream() {
+        let detector = Arc::new(ReverseShellDetector::new());
+        let path = socket_path("stream");
+        tokio::spawn(serve(Listener::bind(&path).await.unwrap(), Arc::clone(&detector)));
+
+        let mut stream = BufReader::new(UnixStream::connect(&path).await.unwrap());
+        stream.get_mut().write_all(b"GET /events/stream?severity=critical HTTP/1.1\r\n\r\n").await.unwrap();
+
+        // 헤더를 받은 시점에는 구독이 등록되어 있음
+        let mut line = String::new();
+        while line != "\r\n" {
+            line.clear();
+            stream.read_line(&mut line).await.unwrap();
+        }
+
+        detector.start_with_source(Box::new(ReplaySource::new(replay_path(), false))).await.unwrap();
+        detector.shutdown().await;
+
+        // High 인 kernel_memfd_create 는 제외되고, 버스가 닫히면 스트림도 끝남
+        let mut rule_ids = Vec::new();
+        let mut lines = stream.lines();
+        while let Some(line) = lines.next_line().await.unwrap() {
+            let event: serde_json::Value = serde_json::from_str(&line).unwrap();
+            rule_ids.push(event["rule_id"].as_str().unwrap().to_string());
+        }
+        assert_eq!(rule_ids, vec!["memfd_executable", "interpreter_listen", "suspicious_connection"]);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,172 @@
+use std::collections::{BTreeMap, BTreeSet};
+use serde::Serialize;
+
+/// MITRE ATT&CK 전술 (Enterprise)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Tactic {
+    Execution,
+    DefenseEvasion,
+    CommandAndControl,
+}
+
+/// MITRE ATT&CK 기법
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Technique {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub tactic: Tactic,
+}
+
+pub const UNIX_SHELL: Technique = Technique {
+    id: "T1059.004",
+    name: "Command and Scripting Interpreter: Unix Shell",
+    tactic: Tactic::Execution,
+};
+
+pub const APPLICATION_LAYER_PROTOCOL: Technique = Technique {
+    id: "T1071",
+    name: "Application Layer Protocol",
+    tactic: Tactic::CommandAndControl,
+};
+
+pub const NON_APPLICATION_LAYER_PROTOCOL: Technique = Technique {
+    id: "T1095",
+    name: "Non-Application Layer Protocol",
+    tactic: Tactic::CommandAndControl,
+};
+
+pub const NON_STANDARD_PORT: Technique = Technique {
+    id: "T1571",
+    name: "Non-Standard Port",
+    tactic: Tactic::CommandAndControl,
+};
+
+pub const INGRESS_TOOL_TRANSFER: Technique = Technique {
+    id: "T1105",
+    name: "Ingress Tool Transfer",
+    tactic: Tactic::CommandAndControl,
+};
+
+pub const REFLECTIVE_CODE_LOADING: Technique = Technique {
+    id: "T1620",
+    name: "Reflective Code Loading",
+    tactic: Tactic::DefenseEvasion,
+};
+
+pub const FILE_DELETION: Technique = Technique {
+    id: "T1070.004",
+    name: "Indicator Removal: File Deletion",
+    tactic: Tactic::DefenseEvasion,
+};
+
+/// 전술별 탐지 현황
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TacticCoverage {
+    pub tactic: Tactic,
+    pub tactic_id: &'static str,
+    pub detections: usize,
+    /// 탐지된 기법 ID
+    pub observed: BTreeSet<&'static str>,
+    /// 탐지 규칙이 다루는 기법 ID
+    pub covered: BTreeSet<&'static str>,
+}
+
+impl Tactic {
+    pub const ALL: [Tactic; 3] = [Tactic::Execution, Tactic::DefenseEvasion, Tactic::CommandAndControl];
+
+    pub fn id(&self) -> &'static str {
+        match self {
+            Tactic::Execution => "TA0002",
+            Tactic::DefenseEvasion => "TA0005",
+            Tactic::CommandAndControl => "TA0011",
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Tactic::Execution => "execution",
+            Tactic::DefenseEvasion => "defense-evasion",
+            Tactic::CommandAndControl => "command-and-control",
+        }
+    }
+}
+
+/// 기법 ID 목록 (쉼표 구분, syslog 출력용)
+pub fn technique_ids(techniques: &[Technique]) -> String {
+    techniques.iter().map(|technique| technique.id).collect::<Vec<_>>().join(",")
+}
+
+/// 전술 이름 목록 (중복 제거, 쉼표 구분, syslog 출력용)
+pub fn tactic_names(techniques: &[Technique]) -> String {
+    techniques.iter()
+        .map(|technique| technique.tactic)
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .map(|tactic| tactic.as_str())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// 전술별 탐지 현황 (rules 는 규칙별 기법, detections 는 탐지 이벤트별 기법)
+pub fn coverage<'a>(
+    rules: impl IntoIterator<Item = &'a [Technique]>,
+    detections: impl IntoIterator<Item = &'a [Technique]>,
+) -> Vec<TacticCoverage> {
+    let mut by_tactic: BTreeMap<Tactic, TacticCoverage> = Tactic::ALL.iter()
+        .map(|&tactic| (tactic, TacticCoverage {
+            tactic,
+            tactic_id: tactic.id(),
+            detections: 0,
+            observed: BTreeSet::new(),
+            covered: BTreeSet::new(),
+        }))
+        .collect();
+
+    for technique in rules.into_iter().flatten() {
+        if let Some(coverage) = by_tactic.get_mut(&technique.tactic) {
+            coverage.covered.insert(technique.id);
+        }
+    }
+    for techniques in detections {
+        // 한 이벤트가 같은 전술의 기법을 여러 개 가져도 탐지 수는 한 번만 셈
+        for tactic in techniques.iter().map(|technique| technique.tactic).collect::<BTreeSet<_>>() {
+            if let Some(coverage) = by_tactic.get_mut(&tactic) {
+                coverage.detections += 1;
+            }
+        }
+        for technique in techniques {
+            if let Some(coverage) = by_tactic.get_mut(&technique.tactic) {
+                coverage.observed.insert(technique.id);
+            }
+        }
+    }
+
+    by_tactic.into_values().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_coverage_by_tactic() {
+        let rules: [&[Technique]; 3] = [
+            &[UNIX_SHELL, NON_STANDARD_PORT],
+            &[REFLECTIVE_CODE_LOADING],
+            &[APPLICATION_LAYER_PROTOCOL, NON_STANDARD_PORT],
+        ];
+        let detections: [&[Technique]; 2] = [&[UNIX_SHELL, NON_STANDARD_PORT], &[NON_STANDARD_PORT, APPLICATION_LAYER_PROTOCOL]];
+
+        let coverage = coverage(rules, detections);
+        assert_eq!(coverage.iter().map(|c| c.tactic_id).collect::<Vec<_>>(), vec!["TA0002", "TA0005", "TA0011"]);
+        assert_eq!((coverage[0].detections, coverage[0].observed.len()), (1, 1));
+        assert_eq!((coverage[1].detections, coverage[1].covered.len()), (0, 1));
+        assert_eq!(coverage[2].detections, 2);
+        assert_eq!(coverage[2].observed, BTreeSet::from(["T1071", "T1571"]));
+
+        assert_eq!(technique_ids(&[UNIX_SHELL, NON_STANDARD_PORT]), "T1059.004,T1571");
+        assert_eq!(tactic_names(&[NON_STANDARD_PORT, UNIX_SHELL, APPLICATION_LAYER_PROTOCOL]), "execution,command-and-control");
+        assert_eq!(serde_json::to_value(UNIX_SHELL).unwrap()["tactic"], "execution");
+    }
+}
@@ -0,0 +1,344 @@
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use anyhow::Result;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+
+/// 기준선 항목에서 모든 값과 일치하는 와일드카드
+pub const WILDCARD: &str = "*";
+
+/// 정상 리스닝 포트 (프로세스, 포트)
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct ListenEntry {
+    pub process: String,
+    pub port: u16,
+}
+
+/// 정상 외부 연결 (프로세스, 목적지, 포트)
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct OutboundEntry {
+    pub process: String,
+    pub destination: String,
+    pub port: u16,
+}
+
+/// 파일로 저장되는 기준선 데이터
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BaselineData {
+    pub listen: BTreeSet<ListenEntry>,
+    pub outbound: BTreeSet<OutboundEntry>,
+}
+
+/// 기준선 동작 모드
+#[derive(Debug, Clone, PartialEq)]
+pub enum BaselineMode {
+    /// 기준선 미사용 (기존 휴리스틱만 사용)
+    Disabled,
+    /// 지정된 시각까지 정상 동작 학습
+    Learning { until: Instant },
+    /// 기준선에서 벗어난 동작만 탐지
+    Enforcing,
+}
+
+impl BaselineMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BaselineMode::Disabled => "disabled",
+            BaselineMode::Learning { .. } => "learning",
+            BaselineMode::Enforcing => "enforcing",
+        }
+    }
+}
+
+/// 기준선 판정 결과
+#[derive(Debug, Clone, PartialEq)]
+pub enum BaselineVerdict {
+    /// 기준선이 설정되지 않음
+    NotConfigured,
+    /// 학습 중이므로 기록만 함
+    Learning,
+    /// 기준선에 포함된 정상 동작
+    Known,
+    /// 기준선에서 벗어난 동작
+    Deviation,
+}
+
+/// 리스닝 포트 및 외부 연결 기준선
+#[derive(Debug, Clone)]
+pub struct Baseline {
+    data: BaselineData,
+    mode: BaselineMode,
+    path: Option<PathBuf>,
+    /// 학습 모드로 시작했을 때의 학습 기간 (라운드마다 다시 학습할 때 사용)
+    learn_for: Option<Duration>,
+}
+
+impl BaselineData {
+    /// 파일에서 기준선 읽기
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    /// 기준선을 파일로 저장
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            if !parent.as_os_str().is_empty() {
+                std::fs::create_dir_all(parent)?;
+            }
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// 리스닝 포트가 기준선에 포함되는지 확인
+    pub fn contains_listen(&self, process: &str, port: u16) -> bool {
+        self.listen.iter().any(|e| e.port == port && Self::matches(&e.process, process))
+    }
+
+    /// 외부 연결이 기준선에 포함되는지 확인
+    pub fn contains_outbound(&self, process: &str, destination: &str, port: u16) -> bool {
+        self.outbound.iter().any(|e| {
+            e.port == port
+                && Self::matches(&e.process, process)
+                && Self::matches(&e.destination, destination)
+        })
+    }
+
+    fn matches(pattern: &str, value: &str) -> bool {
+        pattern == WILDCARD || pattern == value
+    }
+}
+
+impl Baseline {
+    /// 기준선 미사용
+    pub fn disabled() -> Self {
+        Self {
+            data: BaselineData::default(),
+            mode: BaselineMode::Disabled,
+            path: None,
+            learn_for: None,
+        }
+    }
+
+    /// 학습 모드 시작 (기존 파일이 있으면 이어서 학습)
+    pub fn learning(path: PathBuf, duration: Duration) -> Result<Self> {
+        let data = if path.exists() {
+            BaselineData::load(&path)?
+        } else {
+            BaselineData::default()
+        };
+
+        info!("Baseline learning started for {}s ({})", duration.as_secs(), path.display());
+
+        Ok(Self {
+            data,
+            mode: BaselineMode::Learning { until: Instant::now() + duration },
+            path: Some(path),
+            learn_for: Some(duration),
+        })
+    }
+
+    /// 저장된 기준선으로 탐지 모드 시작
+    pub fn enforcing(path: PathBuf) -> Result<Self> {
+        let data = BaselineData::load(&path)?;
+
+        info!("Baseline loaded: {} listen, {} outbound entries ({})",
+              data.listen.len(), data.outbound.len(), path.display());
+
+        Ok(Self {
+            data,
+            mode: BaselineMode::Enforcing,
+            path: Some(path),
+            learn_for: None,
+        })
+    }
+
+    /// 현재 모드
+    pub fn mode(&self) -> &BaselineMode {
+        &self.mode
+    }
+
+    /// 현재 기준선 데이터
+    pub fn data(&self) -> &BaselineData {
+        &self.data
+    }
+
+    /// 학습 기간이 끝났으면 저장 후 탐지 모드로 전환 (재생 시에는 가상 시각 사용)
+    pub fn tick(&mut self, now: Instant) {
+        if let BaselineMode::Learning { until } = self.mode {
+            if now >= until {
+                self.finish_learning();
+            }
+        }
+    }
+
+    /// 리스닝 포트 관찰
+    pub fn observe_listen(&mut self, process: &str, port: u16, now: Instant) -> BaselineVerdict {
+        self.tick(now);
+
+        match self.mode {
+            BaselineMode::Disabled => BaselineVerdict::NotConfigured,
+            BaselineMode::Learning { .. } => {
+                self.data.listen.insert(ListenEntry {
+                    process: process.to_string(),
+                    port,
+                });
+                BaselineVerdict::Learning
+            }
+            BaselineMode::Enforcing => {
+                if self.data.contains_listen(process, port) {
+                    BaselineVerdict::Known
+                } else {
+                    BaselineVerdict::Deviation
+                }
+            }
+        }
+    }
+
+    /// 외부 연결 관찰
+    pub fn observe_outbound(&mut self, process: &str, destination: &str, port: u16, now: Instant) -> BaselineVerdict {
+        self.tick(now);
+
+        match self.mode {
+            BaselineMode::Disabled => BaselineVerdict::NotConfigured,
+            BaselineMode::Learning { .. } => {
+                self.data.outbound.insert(OutboundEntry {
+                    process: process.to_string(),
+                    destination: destination.to_string(),
+                    port,
+                });
+                BaselineVerdict::Learning
+            }
+            BaselineMode::Enforcing => {
+                if self.data.contains_outbound(process, destination, port) {
+                    BaselineVerdict::Known
+                } else {
+                    BaselineVerdict::Deviation
+                }
+            }
+        }
+    }
+
+    /// 학습 중이면 지금까지 학습한 기준선 저장 (종료 시 호출, 다음 실행에서 이어서 학습)
+    pub fn flush(&mut self) {
+        if let (BaselineMode::Learning { .. }, Some(path)) = (&self.mode, &self.path) {
+            match self.data.save(path) {
+                Ok(()) => info!("Baseline learning progress saved to {}", path.display()),
+                Err(e) => warn!("Failed to save baseline to {}: {}", path.display(), e),
+            }
+        }
+    }
+
+    /// 학습 모드로 시작한 기준선이면 학습한 내용을 버리고 같은 기간 동안 처음부터 다시 학습
+    /// (파일에서 읽은 기준선은 그대로 유지하고 false 반환)
+    pub fn relearn(&mut self, now: Instant) -> bool {
+        let Some(duration) = self.learn_for else {
+            return false;
+        };
+
+        self.data = BaselineData::default();
+        self.mode = BaselineMode::Learning { until: now + duration };
+        info!("Baseline learning restarted for {}s", duration.as_secs());
+        true
+    }
+
+    fn finish_learning(&mut self) {
+        if let Some(path) = &self.path {
+            if let Err(e) = self.data.save(path) {
+                warn!("Failed to save baseline to {}: {}", path.display(), e);
+            }
+        }
+
+        info!("Baseline learning finished: {} listen, {} outbound entries",
+              self.data.listen.len(), self.data.outbound.len());
+
+        self.mode = BaselineMode::Enforcing;
+    }
+}
+
+impl Default for Baseline {
+    fn default() -> Self {
+        Self::disabled()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_learning_then_enforcing() {
+        let path = std::env::temp_dir().join(format!("baseline-test-{}.json", std::process::id()));
+        let mut baseline = Baseline::learning(path.clone(), Duration::from_secs(3600)).unwrap();
+
+        assert_eq!(baseline.observe_outbound("curl", "10.0.0.5", 8080, Instant::now()), BaselineVerdict::Learning);
+        assert_eq!(baseline.observe_listen("nginx", 80, Instant::now()), BaselineVerdict::Learning);
+
+        baseline.finish_learning();
+        assert_eq!(baseline.mode(), &BaselineMode::Enforcing);
+        assert_eq!(baseline.observe_outbound("curl", "10.0.0.5", 8080, Instant::now()), BaselineVerdict::Known);
+        assert_eq!(baseline.observe_outbound("curl", "10.0.0.5", 4444, Instant::now()), BaselineVerdict::Deviation);
+        assert_eq!(baseline.observe_listen("nc", 80, Instant::now()), BaselineVerdict::Deviation);
+
+        let loaded = Baseline::enforcing(path.clone()).unwrap();
+        assert!(loaded.data().contains_listen("nginx", 80));
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_learning_ends_at_virtual_time() {
+        let path = std::env::temp_dir().join(format!("baseline-tick-test-{}.json", std::process::id()));
+        let started = Instant::now();
+        let mut baseline = Baseline::learning(path.clone(), Duration::from_secs(600)).unwrap();
+
+        baseline.tick(started + Duration::from_secs(300));
+        assert!(matches!(baseline.mode(), BaselineMode::Learning { .. }));
+
+        baseline.tick(started + Duration::from_secs(601));
+        assert_eq!(baseline.mode(), &BaselineMode::Enforcing);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_relearn() {
+        let path = std::env::temp_dir().join(format!("baseline-relearn-test-{}.json", std::process::id()));
+        let started = Instant::now();
+        let mut baseline = Baseline::learning(path.clone(), Duration::from_secs(600)).unwrap();
+        baseline.observe_listen("nginx", 80, started);
+        baseline.tick(started + Duration::from_secs(601));
+        assert_eq!(baseline.observe_listen("nginx", 80, started + Duration::from_secs(602)), BaselineVerdict::Known);
+
+        // 다시 학습하면 이전에 학습한 내용은 버리고 같은 기간 동안 학습
+        let restarted = started + Duration::from_secs(1000);
+        assert!(baseline.relearn(restarted));
+        assert!(!baseline.data().contains_listen("nginx", 80));
+        assert_eq!(baseline.observe_listen("sshd", 22, restarted), BaselineVerdict::Learning);
+        baseline.tick(restarted + Duration::from_secs(601));
+        assert_eq!(baseline.observe_listen("nginx", 80, restarted + Duration::from_secs(602)), BaselineVerdict::Deviation);
+        assert_eq!(baseline.observe_listen("sshd", 22, restarted + Duration::from_secs(602)), BaselineVerdict::Known);
+
+        // 파일에서 읽은 기준선은 유지
+        let mut loaded = Baseline::enforcing(path.clone()).unwrap();
+        assert!(!loaded.relearn(restarted));
+        assert_eq!(loaded.mode(), &BaselineMode::Enforcing);
+        assert!(loaded.data().contains_listen("sshd", 22));
+        assert!(!Baseline::disabled().relearn(restarted));
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_wildcard_entries() {
+        let mut data = BaselineData::default();
+        data.outbound.insert(OutboundEntry {
+            process: WILDCARD.to_string(),
+            destination: "10.0.0.1".to_string(),
+            port: 443,
+        });
+
+        assert!(data.contains_outbound("python3", "10.0.0.1", 443));
+        assert!(!data.contains_outbound("python3", "10.0.0.2", 443));
+        assert_eq!(Baseline::disabled().observe_listen("nc", 4444, Instant::now()), BaselineVerdict::NotConfigured);
+    }
+}
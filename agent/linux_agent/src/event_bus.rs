@@ -0,0 +1,242 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use log::warn;
+use serde::Serialize;
+use tokio::sync::broadcast::{self, error::{RecvError, TryRecvError}};
+use tokio::sync::{watch, RwLock};
+
+use crate::plugins::reverse_shell::ReverseShellEvent;
+
+/// 구독자별 버퍼 크기 (가득 차면 느린 구독자는 가장 오래된 이벤트부터 잃음)
+pub const EVENT_BUS_CAPACITY: usize = 1024;
+
+/// 탐지 이벤트를 여러 구독자 (로그, 파일, 수집기, API 스트림, 알림 등)에게 전달하는 버스
+///
+/// 발행은 막히지 않으므로 느린 구독자가 탐지 파이프라인을 멈추지 않으며,
+/// 대신 놓친 이벤트 수가 구독자별 lag 지표로 집계됩니다.
+#[derive(Clone)]
+pub struct EventBus {
+    tx: broadcast::Sender<Arc<ReverseShellEvent>>,
+    /// 발행된 이벤트 수
+    published: Arc<AtomicU64>,
+    /// 구독자 통계 (구독이 drop 되면 지표에서 제외)
+    subscribers: Arc<RwLock<Vec<Arc<SubscriberStats>>>>,
+    /// 종료 여부 (닫히면 구독자는 남은 이벤트만 받고 종료)
+    closed: watch::Sender<bool>,
+}
+
+/// 구독자 통계
+#[derive(Debug)]
+struct SubscriberStats {
+    name: String,
+    /// 구독 시점까지 발행된 이벤트 수
+    start: u64,
+    received: AtomicU64,
+    lagged: AtomicU64,
+}
+
+/// 이벤트 버스 구독
+pub struct Subscription {
+    rx: broadcast::Receiver<Arc<ReverseShellEvent>>,
+    closed: watch::Receiver<bool>,
+    stats: Arc<SubscriberStats>,
+}
+
+/// 구독자 지표
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SubscriberMetrics {
+    pub name: String,
+    /// 전달받은 이벤트 수
+    pub received: u64,
+    /// 버퍼가 가득 차서 놓친 이벤트 수
+    pub lagged: u64,
+    /// 아직 처리하지 않은 이벤트 수
+    pub pending: u64,
+}
+
+/// 이벤트 버스 지표
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BusMetrics {
+    pub published: u64,
+    pub subscribers: Vec<SubscriberMetrics>,
+}
+
+impl EventBus {
+    /// 구독자별 버퍼 크기를 지정하여 버스 생성
+    pub fn new(capacity: usize) -> Self {
+        let (tx, _) = broadcast::channel(capacity);
+        Self {
+            tx,
+            published: Arc::new(AtomicU64::new(0)),
+            subscribers: Arc::new(RwLock::new(Vec::new())),
+            closed: watch::Sender::new(false),
+        }
+    }
+
+    /// 이벤트 발행 (전달받은 구독자 수 반환)
+    pub fn publish(&self, event: ReverseShellEvent) -> usize {
+        self.published.fetch_add(1, Ordering::Relaxed);
+        self.tx.send(Arc::new(event)).unwrap_or(0)
+    }
+
+    /// 이름을 붙여 구독 (이후 발행되는 이벤트부터 전달됨)
+    pub async fn subscribe(&self, name: &str) -> Subscription {
+        let stats = Arc::new(SubscriberStats {
+            name: name.to_string(),
+            start: self.published.load(Ordering::Relaxed),
+            received: AtomicU64::new(0),
+            lagged: AtomicU64::new(0),
+        });
+        let rx = self.tx.subscribe();
+
+        self.subscribers.write().await.push(Arc::clone(&stats));
+        Subscription { rx, closed: self.closed.subscribe(), stats }
+    }
+
+    /// 버스 종료 (구독자는 버퍼에 남은 이벤트를 모두 받은 뒤 None 을 받음)
+    pub fn close(&self) {
+        self.closed.send_replace(true);
+    }
+
+    /// 발행 및 구독자별 지표
+    pub async fn metrics(&self) -> BusMetrics {
+        let published = self.published.load(Ordering::Relaxed);
+        let mut subscribers_guard = self.subscribers.write().await;
+        subscribers_guard.retain(|stats| Arc::strong_count(stats) > 1);
+
+        BusMetrics {
+            published,
+            subscribers: subscribers_guard.iter().map(|stats| stats.metrics(published)).collect(),
+        }
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new(EVENT_BUS_CAPACITY)
+    }
+}
+
+impl SubscriberStats {
+    fn metrics(&self, published: u64) -> SubscriberMetrics {
+        let received = self.received.load(Ordering::Relaxed);
+        let lagged = self.lagged.load(Ordering::Relaxed);
+        SubscriberMetrics {
+            name: self.name.clone(),
+            received,
+            lagged,
+            pending: published.saturating_sub(self.start + received + lagged),
+        }
+    }
+}
+
+impl Subscription {
+    /// 다음 이벤트 수신 (버스가 닫히면 None, 놓친 이벤트는 lag 로 집계하고 계속 수신)
+    pub async fn recv(&mut self) -> Option<Arc<ReverseShellEvent>> {
+        loop {
+            let result = tokio::select! {
+                biased;
+                result = self.rx.recv() => result,
+                _ = self.closed.wait_for(|closed| *closed) => match self.rx.try_recv() {
+                    Ok(event) => Ok(event),
+                    Err(TryRecvError::Lagged(skipped)) => Err(RecvError::Lagged(skipped)),
+                    Err(TryRecvError::Empty | TryRecvError::Closed) => Err(RecvError::Closed),
+                },
+            };
+
+            match result {
+                Ok(event) => {
+                    self.stats.received.fetch_add(1, Ordering::Relaxed);
+                    return Some(event);
+                }
+                Err(RecvError::Lagged(skipped)) => {
+                    self.stats.lagged.fetch_add(skipped, Ordering::Relaxed);
+                    warn!("Event subscriber '{}' lagged behind, {} events dropped", self.stats.name, skipped);
+                }
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+    use crate::plugins::reverse_shell::{EventType, Severity};
+
+    fn event(rule_id: &str) -> ReverseShellEvent {
+        ReverseShellEvent {
+            timestamp: Instant::now(),
+            event_type: EventType::ProcessInjection,
+            severity: Severity::High,
+            rule_id: rule_id.to_string(),
+            details: String::new(),
+            connection_info: None,
+            process: None,
+            incident_id: None,
+            round_id: None,
+            techniques: Vec::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fan_out_to_subscribers() {
+        let bus = EventBus::default();
+        let mut file = bus.subscribe("file").await;
+        let mut api = bus.subscribe("api").await;
+
+        assert_eq!(bus.publish(event("suspicious_process")), 2);
+
+        assert_eq!(file.recv().await.unwrap().rule_id, "suspicious_process");
+        assert_eq!(api.recv().await.unwrap().rule_id, "suspicious_process");
+    }
+
+    #[tokio::test]
+    async fn test_slow_subscriber_lag() {
+        let bus = EventBus::new(2);
+        let mut slow = bus.subscribe("slow").await;
+
+        for i in 0..5 {
+            bus.publish(event(&format!("rule_{}", i)));
+        }
+
+        // 버퍼에 남은 가장 최근 이벤트부터 수신하고 놓친 이벤트 수 집계
+        assert_eq!(slow.recv().await.unwrap().rule_id, "rule_3");
+        let metrics = bus.metrics().await;
+        assert_eq!(metrics.published, 5);
+        assert_eq!(metrics.subscribers, vec![SubscriberMetrics {
+            name: "slow".to_string(),
+            received: 1,
+            lagged: 3,
+            pending: 1,
+        }]);
+    }
+
+    #[tokio::test]
+    async fn test_close_delivers_remaining_events() {
+        let bus = EventBus::default();
+        let mut subscription = bus.subscribe("file").await;
+
+        bus.publish(event("first"));
+        bus.publish(event("second"));
+        bus.close();
+
+        assert_eq!(subscription.recv().await.unwrap().rule_id, "first");
+        assert_eq!(subscription.recv().await.unwrap().rule_id, "second");
+        assert!(subscription.recv().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_dropped_subscription_removed() {
+        let bus = EventBus::default();
+        bus.publish(event("before_subscribe"));
+
+        let subscription = bus.subscribe("temp").await;
+        assert_eq!(bus.metrics().await.subscribers[0].pending, 0);
+
+        drop(subscription);
+        assert!(bus.metrics().await.subscribers.is_empty());
+        assert_eq!(bus.publish(event("no_subscribers")), 0);
+    }
+}
@@ -0,0 +1,457 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::os::unix::fs::{FileTypeExt, PermissionsExt};
+use anyhow::{bail, Context, Result};
+use log::{debug, warn};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::io::{Lines, ReadHalf};
+use tokio::net::{TcpListener, TcpStream, UnixListener, UnixStream};
+use tokio::sync::mpsc;
+
+/// 요청 줄과 헤더 한 줄의 최대 길이
+const MAX_LINE: usize = 8192;
+
+/// HTTP 요청 (메서드, 경로, 쿼리만 사용하며 본문은 무시)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    pub query: HashMap<String, String>,
+}
+
+/// HTTP 응답
+#[derive(Debug)]
+pub struct Response {
+    pub status: u16,
+    pub content_type: &'static str,
+    pub body: Body,
+}
+
+/// 응답 본문
+#[derive(Debug)]
+pub enum Body {
+    Full(Vec<u8>),
+    /// 송신측이 닫히거나 클라이언트가 연결을 끊을 때까지 받은 조각을 그대로 전송
+    Stream(mpsc::Receiver<Vec<u8>>),
+}
+
+/// 클라이언트 연결 (TCP 또는 유닉스 소켓)
+pub trait Connection: AsyncRead + AsyncWrite + Unpin + Send {}
+
+impl<S: AsyncRead + AsyncWrite + Unpin + Send> Connection for S {}
+
+/// 스트리밍 응답 본문의 줄 단위 읽기
+pub type ResponseLines = Lines<BufReader<ReadHalf<Box<dyn Connection>>>>;
+
+/// TCP 또는 유닉스 소켓 리스너
+pub enum Listener {
+    Tcp(TcpListener),
+    Unix(UnixListener),
+}
+
+impl Request {
+    /// 쿼리 파라미터 값
+    pub fn query(&self, key: &str) -> Option<&str> {
+        self.query.get(key).map(String::as_str)
+    }
+
+    /// 요청 줄 파싱 (예: `GET /events?severity=high HTTP/1.1`)
+    fn parse(line: &str) -> Result<Self> {
+        let mut parts = line.split_whitespace();
+        let (Some(method), Some(target), Some(_version)) = (parts.next(), parts.next(), parts.next()) else {
+            bail!("malformed request line: {}", line);
+        };
+
+        let (path, query) = target.split_once('?').unwrap_or((target, ""));
+        let query = query.split('&')
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+                (percent_decode(key), percent_decode(value))
+            })
+            .collect();
+
+        Ok(Self {
+            method: method.to_string(),
+            path: percent_decode(path),
+            query,
+        })
+    }
+}
+
+impl Response {
+    pub fn ok(content_type: &'static str, body: impl Into<Vec<u8>>) -> Self {
+        Self { status: 200, content_type, body: Body::Full(body.into()) }
+    }
+
+    /// 스트리밍 응답 (Content-Length 없이 연결이 닫힐 때까지 전송)
+    pub fn stream(content_type: &'static str, chunks: mpsc::Receiver<Vec<u8>>) -> Self {
+        Self { status: 200, content_type, body: Body::Stream(chunks) }
+    }
+
+    /// 오류 응답 (본문은 사유 한 줄)
+    pub fn error(status: u16, message: &str) -> Self {
+        Self {
+            status,
+            content_type: "text/plain; charset=utf-8",
+            body: Body::Full(format!("{}\n", message).into_bytes()),
+        }
+    }
+
+    pub fn not_found() -> Self {
+        Self::error(404, "not found")
+    }
+
+    fn reason(&self) -> &'static str {
+        match self.status {
+            200 => "OK",
+            400 => "Bad Request",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            409 => "Conflict",
+            500 => "Internal Server Error",
+            _ => "",
+        }
+    }
+
+    /// 상태 줄과 헤더
+    fn head(&self) -> String {
+        let length = match &self.body {
+            Body::Full(body) => format!("Content-Length: {}\r\n", body.len()),
+            Body::Stream(_) => String::new(),
+        };
+        format!("HTTP/1.1 {} {}\r\nContent-Type: {}\r\n{}Connection: close\r\n\r\n",
+            self.status, self.reason(), self.content_type, length)
+    }
+}
+
+impl Listener {
+    /// 주소에 `/` 가 있으면 유닉스 소켓 경로, 아니면 TCP 주소 (예: 127.0.0.1:9465)
+    pub async fn bind(address: &str) -> Result<Self> {
+        if !address.contains('/') {
+            let listener = TcpListener::bind(address).await
+                .with_context(|| format!("failed to listen on {}", address))?;
+            return Ok(Listener::Tcp(listener));
+        }
+
+        // 이전 실행이 남긴 소켓 파일만 제거
+        if std::fs::symlink_metadata(address).is_ok_and(|meta| meta.file_type().is_socket()) {
+            std::fs::remove_file(address)?;
+        }
+        let listener = UnixListener::bind(address)
+            .with_context(|| format!("failed to listen on {}", address))?;
+        std::fs::set_permissions(address, std::fs::Permissions::from_mode(0o600))?;
+        Ok(Listener::Unix(listener))
+    }
+
+    /// 로그용 주소
+    pub fn local_addr(&self) -> String {
+        match self {
+            Listener::Tcp(listener) => listener.local_addr().map_or_else(|e| e.to_string(), |addr| addr.to_string()),
+            Listener::Unix(listener) => listener.local_addr().ok()
+                .and_then(|addr| addr.as_pathname().map(|path| path.display().to_string()))
+                .unwrap_or_else(|| "unix socket".to_string()),
+        }
+    }
+
+    /// 루프백 TCP 주소 또는 유닉스 소켓인지
+    pub fn is_local(&self) -> bool {
+        match self {
+            Listener::Tcp(listener) => listener.local_addr().is_ok_and(|addr| addr.ip().is_loopback()),
+            Listener::Unix(_) => true,
+        }
+    }
+}
+
+impl From<TcpListener> for Listener {
+    fn from(listener: TcpListener) -> Self {
+        Listener::Tcp(listener)
+    }
+}
+
+/// 요청 줄과 헤더 읽기 (연결이 바로 닫히면 None)
+pub async fn read_request<S: AsyncRead + Unpin>(reader: &mut BufReader<S>) -> Result<Option<Request>> {
+    let Some(request_line) = read_line(reader).await? else {
+        return Ok(None);
+    };
+    let request = Request::parse(&request_line)?;
+
+    // 헤더는 사용하지 않으므로 빈 줄까지 건너뜀
+    while let Some(header) = read_line(reader).await? {
+        if header.is_empty() {
+            break;
+        }
+    }
+
+    Ok(Some(request))
+}
+
+async fn read_line<S: AsyncRead + Unpin>(reader: &mut BufReader<S>) -> Result<Option<String>> {
+    let mut line = Vec::new();
+    let read = (&mut *reader).take(MAX_LINE as u64).read_until(b'\n', &mut line).await?;
+    if read == 0 {
+        return Ok(None);
+    }
+    if !line.ends_with(b"\n") {
+        bail!("request line too long");
+    }
+    Ok(Some(String::from_utf8_lossy(&line).trim_end().to_string()))
+}
+
+/// 응답 전송 (스트리밍 본문은 클라이언트가 연결을 끊으면 중단)
+pub async fn write_response<R, W>(reader: &mut R, writer: &mut W, response: Response) -> Result<()>
+where
+    R: AsyncRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    writer.write_all(response.head().as_bytes()).await?;
+
+    match response.body {
+        Body::Full(body) => writer.write_all(&body).await?,
+        Body::Stream(mut chunks) => {
+            writer.flush().await?;
+            let mut buf = [0u8; 256];
+            loop {
+                tokio::select! {
+                    chunk = chunks.recv() => match chunk {
+                        Some(chunk) => {
+                            writer.write_all(&chunk).await?;
+                            writer.flush().await?;
+                        }
+                        None => break,
+                    },
+                    // 요청 이후 들어오는 입력은 무시하고 연결 종료만 확인
+                    read = reader.read(&mut buf) => if read.unwrap_or(0) == 0 {
+                        return Ok(());
+                    },
+                }
+            }
+        }
+    }
+
+    writer.flush().await?;
+    Ok(())
+}
+
+/// 연결마다 요청 하나를 처리하는 HTTP 서버
+pub async fn serve<F, Fut>(listener: Listener, handler: F)
+where
+    F: Fn(Request) -> Fut + Clone + Send + Sync + 'static,
+    Fut: Future<Output = Response> + Send + 'static,
+{
+    loop {
+        let accepted = match &listener {
+            Listener::Tcp(listener) => listener.accept().await.map(|(stream, peer)| {
+                tokio::spawn(handle_connection(stream, peer.to_string(), handler.clone()));
+            }),
+            Listener::Unix(listener) => listener.accept().await.map(|(stream, _)| {
+                tokio::spawn(handle_connection(stream, "unix socket".to_string(), handler.clone()));
+            }),
+        };
+
+        if let Err(e) = accepted {
+            warn!("Failed to accept HTTP connection: {}", e);
+        }
+    }
+}
+
+async fn handle_connection<S, F, Fut>(stream: S, peer: String, handler: F)
+where
+    S: AsyncRead + AsyncWrite,
+    F: Fn(Request) -> Fut,
+    Fut: Future<Output = Response>,
+{
+    let (reader, mut writer) = tokio::io::split(stream);
+    let mut reader = BufReader::new(reader);
+
+    let response = match read_request(&mut reader).await {
+        Ok(Some(request)) => handler(request).await,
+        Ok(None) => return,
+        Err(e) => Response::error(400, &e.to_string()),
+    };
+
+    if let Err(e) = write_response(&mut reader, &mut writer, response).await {
+        debug!("Failed to write HTTP response to {}: {}", peer, e);
+    }
+}
+
+/// 서버 연결 (주소 규칙은 `Listener::bind` 와 같음)
+pub async fn connect(address: &str) -> Result<Box<dyn Connection>> {
+    let stream: Box<dyn Connection> = if address.contains('/') {
+        Box::new(UnixStream::connect(address).await.with_context(|| format!("failed to connect to {}", address))?)
+    } else {
+        Box::new(TcpStream::connect(address).await.with_context(|| format!("failed to connect to {}", address))?)
+    };
+    Ok(stream)
+}
+
+/// 요청을 보내고 200 응답의 헤더까지 읽음 (반환된 reader 를 drop 하면 연결 종료)
+async fn send_request(method: &str, address: &str, target: &str) -> Result<BufReader<ReadHalf<Box<dyn Connection>>>> {
+    let (reader, mut writer) = tokio::io::split(connect(address).await?);
+    writer.write_all(format!("{} {} HTTP/1.1\r\nHost: localhost\r\nContent-Length: 0\r\n\r\n", method, target).as_bytes()).await?;
+    writer.flush().await?;
+
+    let mut reader = BufReader::new(reader);
+    let status_line = read_line(&mut reader).await?.unwrap_or_default();
+    let status = status_line.split_whitespace().nth(1).unwrap_or_default().to_string();
+    while let Some(header) = read_line(&mut reader).await? {
+        if header.is_empty() {
+            break;
+        }
+    }
+
+    if status != "200" {
+        let mut body = String::new();
+        reader.read_to_string(&mut body).await?;
+        bail!("{} {} failed: {} {}", method, target, status, body.trim());
+    }
+    Ok(reader)
+}
+
+/// GET 요청 본문
+pub async fn get(address: &str, target: &str) -> Result<Vec<u8>> {
+    let mut reader = send_request("GET", address, target).await?;
+    let mut body = Vec::new();
+    reader.read_to_end(&mut body).await?;
+    Ok(body)
+}
+
+/// 본문 없는 POST 요청의 응답 본문
+pub async fn post(address: &str, target: &str) -> Result<Vec<u8>> {
+    let mut reader = send_request("POST", address, target).await?;
+    let mut body = Vec::new();
+    reader.read_to_end(&mut body).await?;
+    Ok(body)
+}
+
+/// 스트리밍 GET 요청 (본문을 줄 단위로 읽음)
+pub async fn get_lines(address: &str, target: &str) -> Result<ResponseLines> {
+    Ok(send_request("GET", address, target).await?.lines())
+}
+
+/// `%XX` 와 `+` 디코딩
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => decoded.push(b' '),
+            b'%' if i + 3 <= bytes.len() => {
+                let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).ok();
+                match hex.and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+                    Some(byte) => {
+                        decoded.push(byte);
+                        i += 2;
+                    }
+                    None => decoded.push(b'%'),
+                }
+            }
+            byte => decoded.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_request_line() {
+        let request = Request::parse("GET /events?severity=high&rule_id=kernel%5Flisten&q=bash+-i HTTP/1.1").unwrap();
+        assert_eq!(request.method, "GET");
+        assert_eq!(request.path, "/events");
+        assert_eq!(request.query("severity"), Some("high"));
+        assert_eq!(request.query("rule_id"), Some("kernel_listen"));
+        assert_eq!(request.query("q"), Some("bash -i"));
+        assert!(Request::parse("GET").is_err());
+        assert_eq!(percent_decode("100%"), "100%");
+        // 끝에 붙은 이스케이프도 디코딩
+        assert_eq!(percent_decode("kernel%5F"), "kernel_");
+        assert_eq!(percent_decode("%2F"), "/");
+        assert_eq!(percent_decode("50%2"), "50%2");
+        assert_eq!(Request::parse("GET /events?rule_id=bind%5F HTTP/1.1").unwrap().query("rule_id"), Some("bind_"));
+    }
+
+    #[tokio::test]
+    async fn test_serve_request() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(serve(listener.into(), |request: Request| async move {
+            match request.path.as_str() {
+                "/hello" => Response::ok("text/plain", "hi"),
+                _ => Response::not_found(),
+            }
+        }));
+
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream.write_all(b"GET /hello HTTP/1.1\r\nHost: localhost\r\n\r\n").await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.contains("Content-Length: 2\r\n"));
+        assert!(response.ends_with("\r\n\r\nhi"));
+
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream.write_all(b"GET /missing HTTP/1.1\r\n\r\n").await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 404 Not Found\r\n"));
+
+        let address = addr.to_string();
+        assert_eq!(get(&address, "/hello").await.unwrap(), b"hi");
+        assert!(get(&address, "/missing").await.unwrap_err().to_string().contains("404 not found"));
+    }
+
+    #[tokio::test]
+    async fn test_stream_over_unix_socket() {
+        let path = std::env::temp_dir().join(format!("http-test-{}.sock", std::process::id()));
+        let path = path.to_str().unwrap().to_string();
+        let listener = Listener::bind(&path).await.unwrap();
+        assert!(listener.is_local());
+
+        let (tx, rx) = mpsc::channel(4);
+        let rx = std::sync::Arc::new(tokio::sync::Mutex::new(Some(rx)));
+        tokio::spawn(serve(listener, move |_request: Request| {
+            let rx = std::sync::Arc::clone(&rx);
+            async move { Response::stream("application/x-ndjson", rx.lock().await.take().unwrap()) }
+        }));
+
+        let mut stream = UnixStream::connect(&path).await.unwrap();
+        stream.write_all(b"GET /stream HTTP/1.1\r\n\r\n").await.unwrap();
+        tx.send(b"{\"n\":1}\n".to_vec()).await.unwrap();
+        tx.send(b"{\"n\":2}\n".to_vec()).await.unwrap();
+        drop(tx);
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        assert!(!response.contains("Content-Length"));
+        assert!(response.ends_with("\r\n\r\n{\"n\":1}\n{\"n\":2}\n"));
+
+        // 다시 바인드하면 남아 있던 소켓 파일을 교체
+        drop(Listener::bind(&path).await.unwrap());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_client_reads_stream_lines() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        tokio::spawn(serve(listener.into(), |_request: Request| async move {
+            let (tx, rx) = mpsc::channel(2);
+            tokio::spawn(async move {
+                for line in ["first\n", "second\n"] {
+                    tx.send(line.as_bytes().to_vec()).await.unwrap();
+                }
+            });
+            Response::stream("text/plain", rx)
+        }));
+
+        let mut lines = get_lines(&address, "/stream").await.unwrap();
+        assert_eq!(lines.next_line().await.unwrap().as_deref(), Some("first"));
+        assert_eq!(lines.next_line().await.unwrap().as_deref(), Some("second"));
+        assert_eq!(lines.next_line().await.unwrap(), None);
+    }
+}
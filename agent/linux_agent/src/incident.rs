@@ -0,0 +1,334 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::net::IpAddr;
+use std::time::{Duration, Instant};
+use serde::Serialize;
+
+use crate::plugins::reverse_shell::{serialize_instant, ReverseShellEvent, Severity};
+
+/// 마지막 이벤트 이후 이 시간 안에 같은 계보나 상대 주소의 이벤트가 오면 같은 사고
+const INCIDENT_WINDOW: Duration = Duration::from_secs(600);
+
+/// 사고의 첫 이벤트 이후 이 시간 안의 이벤트는 같은 사고 (pid 와 상대 주소가 모두 없어 계보나 상대로 묶을 수 없는 이벤트만)
+const BURST_WINDOW: Duration = Duration::from_secs(60);
+
+/// 보관할 사고 수
+const MAX_INCIDENTS: usize = 256;
+
+/// 사고별로 보관할 타임라인 항목 수 (초과분은 event_count 에만 반영)
+const MAX_TIMELINE: usize = 100;
+
+/// 사고 타임라인 항목
+#[derive(Debug, Clone, Serialize)]
+pub struct TimelineEntry {
+    #[serde(serialize_with = "serialize_instant")]
+    pub timestamp: Instant,
+    pub severity: Severity,
+    pub rule_id: String,
+    /// 이벤트를 일으킨 프로세스 (알 수 없으면 0)
+    pub pid: u32,
+    pub details: String,
+}
+
+/// 관련 이벤트 묶음 (프로세스 계보, 상대 주소, 시간 근접성 기준)
+#[derive(Debug, Clone, Serialize)]
+pub struct Incident {
+    pub id: u64,
+    #[serde(serialize_with = "serialize_instant")]
+    pub first_seen: Instant,
+    #[serde(serialize_with = "serialize_instant")]
+    pub last_seen: Instant,
+    /// 포함된 이벤트 중 가장 높은 심각도
+    pub severity: Severity,
+    pub event_count: usize,
+    /// 이벤트를 일으킨 프로세스
+    pub pids: BTreeSet<u32>,
+    /// 원격 주소:포트
+    pub peers: BTreeSet<String>,
+    pub rules: BTreeSet<String>,
+    pub timeline: Vec<TimelineEntry>,
+    /// 이벤트 프로세스와 그 조상 (계보 비교용)
+    #[serde(skip)]
+    lineage: HashSet<u32>,
+    /// 원격 주소 (상대 비교용)
+    #[serde(skip)]
+    peer_addrs: HashSet<IpAddr>,
+}
+
+/// 탐지 이벤트를 사고로 묶는 추적기
+#[derive(Debug, Default)]
+pub struct IncidentTracker {
+    incidents: Vec<Incident>,
+    next_id: u64,
+    /// 합쳐져 없어진 사고 ID -> 남은 사고 ID (이미 이벤트에 붙은 ID 로도 조회할 수 있도록)
+    aliases: HashMap<u64, u64>,
+}
+
+impl Incident {
+    fn new(id: u64, event: &ReverseShellEvent) -> Self {
+        Self {
+            id,
+            first_seen: event.timestamp,
+            last_seen: event.timestamp,
+            severity: event.severity.clone(),
+            event_count: 0,
+            pids: BTreeSet::new(),
+            peers: BTreeSet::new(),
+            rules: BTreeSet::new(),
+            timeline: Vec::new(),
+            lineage: HashSet::new(),
+            peer_addrs: HashSet::new(),
+        }
+    }
+
+    /// 이벤트가 이 사고와 관련 있는지 (같은 계보, 같은 상대, 또는 첫 이벤트 직후)
+    fn is_related(&self, event: &ReverseShellEvent, pid: u32, ancestors: &[u32]) -> bool {
+        if event.timestamp.saturating_duration_since(self.last_seen) > INCIDENT_WINDOW {
+            return false;
+        }
+
+        let same_lineage = pid != 0
+            && (self.lineage.contains(&pid) || ancestors.iter().any(|ancestor| self.pids.contains(ancestor)));
+        let same_peer = peer(event).is_some_and(|(addr, _)| self.peer_addrs.contains(&addr));
+        let burst = pid == 0
+            && peer(event).is_none()
+            && event.timestamp.saturating_duration_since(self.first_seen) <= BURST_WINDOW;
+
+        same_lineage || same_peer || burst
+    }
+
+    fn add(&mut self, event: &ReverseShellEvent, pid: u32, ancestors: &[u32]) {
+        self.first_seen = self.first_seen.min(event.timestamp);
+        self.last_seen = self.last_seen.max(event.timestamp);
+        self.severity = self.severity.clone().max(event.severity.clone());
+        self.event_count += 1;
+        self.rules.insert(event.rule_id.clone());
+        if pid != 0 {
+            self.pids.insert(pid);
+            self.lineage.insert(pid);
+            self.lineage.extend(ancestors);
+        }
+        if let Some((addr, label)) = peer(event) {
+            self.peer_addrs.insert(addr);
+            self.peers.insert(label);
+        }
+        if self.timeline.len() < MAX_TIMELINE {
+            self.timeline.push(TimelineEntry {
+                timestamp: event.timestamp,
+                severity: event.severity.clone(),
+                rule_id: event.rule_id.clone(),
+                pid,
+                details: event.details.clone(),
+            });
+        }
+    }
+
+    /// 두 사고를 하나로 합침 (타임라인은 시각 순)
+    fn merge(&mut self, other: Incident) {
+        self.first_seen = self.first_seen.min(other.first_seen);
+        self.last_seen = self.last_seen.max(other.last_seen);
+        self.severity = self.severity.clone().max(other.severity);
+        self.event_count += other.event_count;
+        self.pids.extend(other.pids);
+        self.peers.extend(other.peers);
+        self.rules.extend(other.rules);
+        self.lineage.extend(other.lineage);
+        self.peer_addrs.extend(other.peer_addrs);
+        self.timeline.extend(other.timeline);
+        self.timeline.sort_by_key(|entry| entry.timestamp);
+        self.timeline.truncate(MAX_TIMELINE);
+    }
+
+    pub fn duration(&self) -> Duration {
+        self.last_seen.duration_since(self.first_seen)
+    }
+}
+
+/// 이벤트의 원격 주소 (주소, "주소:포트")
+fn peer(event: &ReverseShellEvent) -> Option<(IpAddr, String)> {
+    let connection = event.connection_info.as_ref()?;
+    Some((connection.remote()?, connection.remote_endpoint()))
+}
+
+impl IncidentTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 이벤트를 관련 사고에 추가하고 사고 ID 반환 (여러 사고와 관련되면 가장 오래된 사고로 합침)
+    ///
+    /// ancestors 는 이벤트 프로세스의 조상 pid (프로세스 정보가 없으면 비어 있음)
+    pub fn add(&mut self, event: &ReverseShellEvent, pid: u32, ancestors: &[u32]) -> u64 {
+        let mut related: Vec<usize> = self.incidents.iter()
+            .enumerate()
+            .filter(|(_, incident)| incident.is_related(event, pid, ancestors))
+            .map(|(index, _)| index)
+            .collect();
+        // pid 와 상대가 없는 이벤트는 사고끼리 잇는 근거가 될 수 없으므로 가장 최근 사고에만 추가
+        if pid == 0 && peer(event).is_none() {
+            related.drain(..related.len().saturating_sub(1));
+        }
+
+        let index = match related.split_first() {
+            Some((&first, rest)) => {
+                // 뒤쪽부터 제거해야 앞쪽 인덱스가 유지됨
+                let survivor = self.incidents[first].id;
+                for &index in rest.iter().rev() {
+                    let other = self.incidents.remove(index);
+                    for target in self.aliases.values_mut().filter(|target| **target == other.id) {
+                        *target = survivor;
+                    }
+                    self.aliases.insert(other.id, survivor);
+                    self.incidents[first].merge(other);
+                }
+                first
+            }
+            None => {
+                self.next_id += 1;
+                self.incidents.push(Incident::new(self.next_id, event));
+                if self.incidents.len() > MAX_INCIDENTS {
+                    let evicted = self.incidents.remove(0).id;
+                    self.aliases.retain(|_, target| *target != evicted);
+                }
+                self.incidents.len() - 1
+            }
+        };
+
+        self.incidents[index].add(event, pid, ancestors);
+        self.incidents[index].id
+    }
+
+    /// 사고 목록 (오래된 순)
+    pub fn incidents(&self) -> Vec<Incident> {
+        self.incidents.clone()
+    }
+
+    /// 모든 사고 닫기 (이후 이벤트는 새 사고, ID 는 이어서 부여)
+    pub fn clear(&mut self) {
+        self.incidents.clear();
+        self.aliases.clear();
+    }
+
+    /// 다른 사고에 합쳐진 ID 는 남은 사고 ID 로
+    pub fn resolve(&self, id: u64) -> u64 {
+        self.aliases.get(&id).copied().unwrap_or(id)
+    }
+
+    /// 사고 조회 (합쳐진 사고의 ID 로도 조회 가능)
+    pub fn get(&self, id: u64) -> Option<Incident> {
+        let id = self.resolve(id);
+        self.incidents.iter().find(|incident| incident.id == id).cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugins::reverse_shell::{ConnectionInfo, EventType};
+
+    fn event(at: Instant, rule_id: &str, severity: Severity, remote: Option<(u32, u16)>) -> ReverseShellEvent {
+        ReverseShellEvent {
+            timestamp: at,
+            event_type: EventType::ReverseShellDetected,
+            severity,
+            rule_id: rule_id.to_string(),
+            details: format!("{} detected", rule_id),
+            connection_info: remote.map(|(remote_addr, remote_port)| ConnectionInfo {
+                local_addr: 0x0A00000A,
+                local_addr6: None,
+                remote_addr,
+                remote_addr6: None,
+                local_port: 51234,
+                remote_port,
+                pid: 0,
+                process_name: "bash".to_string(),
+                first_seen: at,
+                last_seen: at,
+                connection_count: 1,
+                is_suspicious: true,
+            }),
+            process: None,
+            incident_id: None,
+            round_id: None,
+            techniques: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_group_by_lineage_and_peer() {
+        let mut tracker = IncidentTracker::new();
+        let start = Instant::now();
+        let at = |secs: u64| start + Duration::from_secs(secs);
+
+        // 웹 서버(1204) 아래 sh(5000) → bash(5001), 5분 뒤 같은 C2 로 다시 연결
+        let first = tracker.add(&event(at(0), "kernel_suspicious_exec", Severity::High, None), 5000, &[1204]);
+        assert_eq!(tracker.add(&event(at(90), "suspicious_connection", Severity::Medium, Some((0xCB00710A, 9001))), 5001, &[5000, 1204]), first);
+        assert_eq!(tracker.add(&event(at(390), "suspicious_connection", Severity::Medium, Some((0xCB00710A, 443))), 7000, &[]), first);
+
+        // 관련 없는 프로세스의 이벤트는 새 사고
+        let other = tracker.add(&event(at(400), "new_listen_port", Severity::Medium, None), 8000, &[1]);
+        assert_ne!(other, first);
+
+        let incident = tracker.get(first).unwrap();
+        assert_eq!(incident.event_count, 3);
+        assert_eq!(incident.severity, Severity::High);
+        assert_eq!(incident.pids, BTreeSet::from([5000, 5001, 7000]));
+        assert_eq!(incident.peers, BTreeSet::from(["203.0.113.10:443".to_string(), "203.0.113.10:9001".to_string()]));
+        assert_eq!(incident.duration(), Duration::from_secs(390));
+
+        // 창을 넘긴 같은 계보의 이벤트는 새 사고
+        assert_ne!(tracker.add(&event(at(1200), "kernel_suspicious_exec", Severity::High, None), 5000, &[1204]), first);
+        assert_eq!(tracker.incidents().len(), 3);
+    }
+
+    #[test]
+    fn test_merge_linked_incidents() {
+        let mut tracker = IncidentTracker::new();
+        let start = Instant::now();
+        let at = |secs: u64| start + Duration::from_secs(secs);
+
+        // 서로 다른 두 사고가 나중 이벤트 (계보와 상대 모두 일치) 로 합쳐짐
+        let a = tracker.add(&event(at(0), "memfd_executable", Severity::Critical, None), 31337, &[]);
+        let b = tracker.add(&event(at(120), "suspicious_connection", Severity::Medium, Some((0xCB00710A, 9001))), 31401, &[]);
+        assert_ne!(a, b);
+        let merged = tracker.add(&event(at(130), "kernel_external_connection", Severity::High, Some((0xCB00710A, 9001))), 31337, &[]);
+
+        assert_eq!(merged, a);
+        assert_eq!(tracker.incidents().len(), 1);
+        let incident = tracker.get(a).unwrap();
+        assert_eq!(incident.severity, Severity::Critical);
+        let rules: Vec<&str> = incident.timeline.iter().map(|entry| entry.rule_id.as_str()).collect();
+        assert_eq!(rules, vec!["memfd_executable", "suspicious_connection", "kernel_external_connection"]);
+
+        // 이미 이벤트에 붙은 합쳐진 사고 ID 로도 남은 사고를 조회
+        assert_eq!(tracker.resolve(b), a);
+        assert_eq!(tracker.get(b).unwrap().id, a);
+
+        // 합쳐진 사고가 다시 합쳐져도 처음 ID 가 마지막으로 남은 사고를 가리킴
+        let c = tracker.add(&event(at(140), "memfd_executable", Severity::Critical, None), 40000, &[]);
+        tracker.add(&event(at(150), "suspicious_connection", Severity::Medium, Some((0x08080808, 4444))), 40000, &[]);
+        let d = tracker.add(&event(at(160), "suspicious_connection", Severity::Medium, Some((0x08080808, 4444))), 31337, &[]);
+        assert_eq!(d, a);
+        assert_eq!(tracker.resolve(c), a);
+        assert_eq!(tracker.resolve(b), a);
+    }
+
+    #[test]
+    fn test_burst_only_without_context() {
+        let mut tracker = IncidentTracker::new();
+        let start = Instant::now();
+        let at = |secs: u64| start + Duration::from_secs(secs);
+
+        let first = tracker.add(&event(at(0), "interpreter_listen", Severity::Critical, None), 4242, &[]);
+        // pid 와 상대가 모두 없는 이벤트만 직후의 사고로 묶음
+        assert_eq!(tracker.add(&event(at(10), "kernel_hook", Severity::High, None), 0, &[]), first);
+        assert_ne!(tracker.add(&event(at(120), "kernel_hook", Severity::High, None), 0, &[]), first);
+
+        // 관련 없는 프로세스나 상대의 이벤트는 직후라도 새 사고
+        let second = tracker.add(&event(at(200), "new_listen_port", Severity::Medium, None), 8000, &[1]);
+        let third = tracker.add(&event(at(205), "suspicious_connection", Severity::Medium, Some((0x08080808, 443))), 0, &[]);
+        assert_ne!(second, third);
+        // 직후의 사고가 여럿이어도 가장 최근 사고에만 추가하고 합치지 않음
+        assert_eq!(tracker.add(&event(at(210), "kernel_hook", Severity::High, None), 0, &[]), third);
+        assert_eq!(tracker.incidents().len(), 4);
+    }
+}
@@ -1,32 +1,417 @@
+mod api;
+mod attack;
+mod baseline;
+mod event_bus;
+mod http;
+mod incident;
+mod metrics;
+mod output;
 mod plugins;
+mod process_info;
+mod response;
+mod risk;
+mod round;
+mod scoring;
+mod source;
+mod suppression;
+mod tui;
 
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::signal::unix::{signal, SignalKind};
 use anyhow::Result;
+use clap::{Args, Parser, Subcommand, ValueEnum};
 use log::{info, error};
 use tokio;
 
+use baseline::{Baseline, BaselineData, ListenEntry, OutboundEntry};
+use http::Listener;
+use output::jsonl::{FsyncPolicy, JsonlConfig, JsonlSink};
+use output::syslog::{SyslogConfig, SyslogFormat, SyslogSink, SyslogTransport};
+use output::LogSink;
+use response::ResponseConfig;
+use round::{RoundConfig, RoundReset};
+use scoring::ScoringConfig;
+use source::procfs::ProcfsSource;
+use source::replay::ReplaySource;
+use suppression::SuppressionList;
+
+/// Telemagnifier Linux 에이전트
+#[derive(Parser)]
+#[command(name = "linux_agent", version)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// 에이전트 실행 (기본 동작)
+    Run(Box<RunArgs>),
+    /// 학습된 기준선 조회 및 편집
+    Baseline {
+        /// 기준선 파일 경로
+        #[arg(long)]
+        path: PathBuf,
+        #[command(subcommand)]
+        action: BaselineAction,
+    },
+    /// 실행 중인 에이전트의 탐지 현황 대시보드 (조회 API 사용)
+    Tui {
+        /// 에이전트 조회 API 주소 (--api-listen 과 같은 값)
+        #[arg(long, default_value = "/run/telemagnifier/api.sock")]
+        api: String,
+    },
+    /// 실행 중인 에이전트의 라운드 시작/종료 (조회 API 사용)
+    Round {
+        /// 에이전트 조회 API 주소 (--api-listen 과 같은 값)
+        #[arg(long, default_value = "/run/telemagnifier/api.sock")]
+        api: String,
+        #[command(subcommand)]
+        action: RoundAction,
+    },
+}
+
+#[derive(Args, Default)]
+struct RunArgs {
+    /// 기준선 파일 경로 (지정하면 기준선에서 벗어난 동작만 탐지)
+    #[arg(long)]
+    baseline: Option<PathBuf>,
+    /// 지정한 시간(초) 동안 정상 동작을 학습한 뒤 기준선 파일로 저장
+    #[arg(long, requires = "baseline")]
+    learn: Option<u64>,
+    /// 억제 규칙 파일 경로 (JSON 배열)
+    #[arg(long)]
+    suppressions: Option<PathBuf>,
+    /// 이벤트 소스 (auto 는 eBPF 를 시도하고 실패하면 procfs 사용)
+    #[arg(long, value_enum, default_value_t = SourceKind::Auto)]
+    source: SourceKind,
+    /// 재생할 이벤트 파일 (--source replay)
+    #[arg(long, required_if_eq("source", "replay"))]
+    replay_file: Option<PathBuf>,
+    /// 기록 당시 간격대로 재생 (기본은 가상 시각으로 즉시 재생)
+    #[arg(long)]
+    replay_realtime: bool,
+    /// 분석기에 입력되는 이벤트를 파일로 기록 (--source replay 로 재생 가능)
+    #[arg(long)]
+    record: Option<PathBuf>,
+    /// 탐지 이벤트를 JSON Lines 파일로 출력
+    #[arg(long)]
+    output_jsonl: Option<PathBuf>,
+    /// 출력 파일이 이 크기(MB)를 넘으면 교체
+    #[arg(long, requires = "output_jsonl")]
+    output_max_mb: Option<u64>,
+    /// 출력 파일을 이 시간(초)마다 교체
+    #[arg(long, requires = "output_jsonl")]
+    output_rotate_secs: Option<u64>,
+    /// 교체된 출력 파일을 gzip 으로 압축
+    #[arg(long, requires = "output_jsonl")]
+    output_compress: bool,
+    /// 보관할 교체 파일 수 (0 이면 모두 보관)
+    #[arg(long, requires = "output_jsonl", default_value_t = 0)]
+    output_keep: usize,
+    /// 출력 파일 fsync 정책
+    #[arg(long, value_enum, default_value_t = FsyncPolicy::Rotate)]
+    output_fsync: FsyncPolicy,
+    /// 탐지 이벤트를 syslog 로 전송 (host:port 또는 유닉스 소켓 경로)
+    #[arg(long)]
+    syslog: Option<String>,
+    /// syslog 전송 방식
+    #[arg(long, value_enum, default_value_t = SyslogTransport::Udp)]
+    syslog_transport: SyslogTransport,
+    /// syslog 메시지 형식
+    #[arg(long, value_enum, default_value_t = SyslogFormat::Rfc5424)]
+    syslog_format: SyslogFormat,
+    /// syslog facility (0-23, 기본값 authpriv)
+    #[arg(long, default_value_t = 10, value_parser = clap::value_parser!(u8).range(0..=23))]
+    syslog_facility: u8,
+    /// Prometheus `/metrics` 엔드포인트 주소 (예: 0.0.0.0:9464, 경로를 주면 유닉스 소켓)
+    #[arg(long)]
+    metrics_listen: Option<String>,
+    /// 조회 API 주소 (유닉스 소켓 경로 또는 127.0.0.1:9465)
+    #[arg(long)]
+    api_listen: Option<String>,
+    /// 루프백이 아닌 주소에서도 인증 없는 조회 API 허용
+    #[arg(long, requires = "api_listen")]
+    api_allow_remote: bool,
+    /// 위험도 점수가 절반으로 줄어드는 시간(초)
+    #[arg(long, default_value_t = 300, value_parser = clap::value_parser!(u64).range(1..))]
+    risk_half_life: u64,
+    /// 대응 정책 파일 경로 (JSON, 지정한 규칙의 탐지에 프로세스 종료, 연결/주소 차단)
+    #[arg(long)]
+    response_policies: Option<PathBuf>,
+    /// 대응하지 않고 수행할 동작만 감사 이벤트로 기록
+    #[arg(long, requires = "response_policies")]
+    response_dry_run: bool,
+    /// CTF 팀별 감점 설정 파일 경로 (JSON, 호스트/cgroup 별 팀과 규칙/심각도별 감점)
+    #[arg(long)]
+    scoring: Option<PathBuf>,
+    /// 팀별 순위를 주기적으로 내보낼 파일 (.csv 면 CSV, 아니면 CTFd 스코어보드 JSON)
+    #[arg(long, requires = "scoring")]
+    scoreboard_out: Option<PathBuf>,
+    /// 시작할 때 이 번호의 라운드 시작
+    #[arg(long)]
+    round: Option<u64>,
+    /// 라운드 신호 파일 (`start [번호]`, `stop` 또는 라운드 번호, 내용이 바뀔 때마다 적용)
+    #[arg(long)]
+    round_file: Option<PathBuf>,
+    /// 새 라운드를 시작할 때 초기화할 상태 (쉼표 구분)
+    #[arg(long, value_enum, value_delimiter = ',')]
+    round_reset: Vec<RoundReset>,
+    /// 라운드가 끝날 때 라운드 리포트 (round-<번호>.txt) 를 쓸 디렉터리
+    #[arg(long)]
+    round_report_dir: Option<PathBuf>,
+}
+
+/// 이벤트 소스 종류
+#[derive(Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+enum SourceKind {
+    #[default]
+    Auto,
+    Ebpf,
+    Procfs,
+    Replay,
+}
+
+#[derive(Subcommand)]
+enum BaselineAction {
+    /// 기준선 출력
+    Show,
+    /// 리스닝 포트 추가 (process 에 `*` 사용 가능)
+    AddListen { process: String, port: u16 },
+    /// 리스닝 포트 제거
+    RemoveListen { process: String, port: u16 },
+    /// 외부 연결 추가 (process, destination 에 `*` 사용 가능)
+    AddOutbound { process: String, destination: String, port: u16 },
+    /// 외부 연결 제거
+    RemoveOutbound { process: String, destination: String, port: u16 },
+    /// 기준선 초기화
+    Clear,
+}
+
+#[derive(Subcommand)]
+enum RoundAction {
+    /// 라운드 시작 (번호를 생략하면 마지막 라운드 + 1, 진행 중인 라운드는 종료)
+    Start { id: Option<u64> },
+    /// 진행 중인 라운드 종료
+    Stop,
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
     // 로깅 초기화
     env_logger::init();
-    
+
+    let cli = Cli::parse();
+
+    match cli.command.unwrap_or(Command::Run(Box::default())) {
+        Command::Run(args) => run_agent(*args).await,
+        Command::Baseline { path, action } => edit_baseline(path, action),
+        Command::Tui { api } => tui::run(api).await,
+        Command::Round { api, action } => control_round(api, action).await,
+    }
+}
+
+/// 에이전트 실행
+async fn run_agent(args: RunArgs) -> Result<()> {
     info!("Starting Linux Agent with Kernel Hooking for Reverse Shell Detection");
-    
+
+    let baseline = match (args.baseline, args.learn) {
+        (Some(path), Some(secs)) => Baseline::learning(path, Duration::from_secs(secs))?,
+        (Some(path), None) => Baseline::enforcing(path)?,
+        (None, _) => Baseline::disabled(),
+    };
+
+    let suppressions = match args.suppressions {
+        Some(path) => SuppressionList::load(&path)?,
+        None => SuppressionList::default(),
+    };
+
     // 리버스 쉘 탐지 플러그인 생성 및 시작
-    let detector = plugins::reverse_shell::ReverseShellDetector::new();
-    
-    if let Err(e) = detector.start().await {
+    let detector = Arc::new(plugins::reverse_shell::ReverseShellDetector::with_baseline(baseline, suppressions));
+
+    detector.add_sink(Box::new(LogSink)).await;
+    if let Some(path) = args.output_jsonl {
+        let config = JsonlConfig {
+            max_bytes: args.output_max_mb.map(|mb| mb * 1024 * 1024),
+            max_age: args.output_rotate_secs.map(Duration::from_secs),
+            compress: args.output_compress,
+            fsync: args.output_fsync,
+            keep: args.output_keep,
+            ..JsonlConfig::new(path)
+        };
+        detector.add_sink(Box::new(JsonlSink::open(config)?)).await;
+    }
+    if let Some(address) = args.syslog {
+        let config = SyslogConfig {
+            transport: args.syslog_transport,
+            format: args.syslog_format,
+            facility: args.syslog_facility,
+            ..SyslogConfig::new(address)
+        };
+        detector.add_sink(Box::new(SyslogSink::connect(config)?)).await;
+    }
+
+    detector.set_risk_config(risk::RiskConfig {
+        half_life: Duration::from_secs(args.risk_half_life),
+        ..risk::RiskConfig::default()
+    }).await;
+
+    if let Some(path) = &args.response_policies {
+        let mut config = ResponseConfig::load(path)?;
+        config.dry_run |= args.response_dry_run;
+        detector.set_response_config(config).await;
+    }
+
+    if let Some(path) = &args.scoring {
+        detector.set_scoring_config(ScoringConfig::load(path)?).await;
+    }
+
+    detector.set_round_config(RoundConfig {
+        reset: args.round_reset.iter().copied().collect(),
+        report_dir: args.round_report_dir.clone(),
+    }).await;
+
+    if let Some(path) = &args.record {
+        detector.record_to(path).await?;
+    }
+
+    if let Some(address) = &args.metrics_listen {
+        let listener = Listener::bind(address).await?;
+        tokio::spawn(metrics::serve(listener, Arc::clone(&detector)));
+    }
+    if let Some(address) = &args.api_listen {
+        let listener = Listener::bind(address).await?;
+        api::check_exposure(&listener, args.api_allow_remote)?;
+        tokio::spawn(api::serve(listener, Arc::clone(&detector)));
+    }
+
+    // 첫 이벤트부터 라운드가 붙도록 소스 시작 전에 라운드 시작
+    if let Some(id) = args.round {
+        detector.start_round(Some(id)).await?;
+    }
+
+    let started = match args.source {
+        SourceKind::Auto => detector.start().await,
+        SourceKind::Ebpf => detector.start_with_source(Box::new(detector.ebpf_source().await)).await,
+        SourceKind::Procfs => detector.start_with_source(Box::new(ProcfsSource::new())).await,
+        SourceKind::Replay => {
+            let path = args.replay_file.unwrap_or_default();
+            detector.start_with_source(Box::new(ReplaySource::new(path, args.replay_realtime))).await
+        }
+    };
+
+    if let Err(e) = started {
         error!("Failed to start reverse shell detector: {}", e);
         return Err(e);
     }
-    
+
     info!("Reverse shell detector started successfully");
-    
+
+    if let Some(path) = args.round_file.clone() {
+        tokio::spawn(round::watch_file(path, Arc::clone(&detector)));
+    }
+
+    // 재생 소스는 파일을 모두 처리하면 리포트를 출력하고 종료
+    if args.source == SourceKind::Replay {
+        detector.wait_until_drained().await;
+        detector.shutdown().await;
+        export_scoreboard(&detector, args.scoreboard_out.as_deref()).await;
+        let report = match detector.stop_round().await {
+            Some(round) => detector.generate_round_report(round.id).await.unwrap_or_default(),
+            None => detector.generate_report().await,
+        };
+        println!("{}", report);
+        return Ok(());
+    }
+
+    let mut sigint = signal(SignalKind::interrupt())?;
+    let mut sigterm = signal(SignalKind::terminate())?;
+
     // 메인 루프 - 플러그인이 백그라운드에서 실행됨
     loop {
-        tokio::time::sleep(tokio::time::Duration::from_secs(60)).await;
-        
-        // 주기적으로 리포트 생성
-        let report = detector.generate_report();
-        info!("Periodic Report:\n{}", report);
+        tokio::select! {
+            _ = tokio::time::sleep(Duration::from_secs(60)) => {
+                // 주기적으로 리포트 생성 (라운드 진행 중이면 라운드 리포트)
+                let report = detector.current_report().await;
+                info!("Periodic Report:\n{}", report);
+                export_scoreboard(&detector, args.scoreboard_out.as_deref()).await;
+            }
+            _ = sigint.recv() => break,
+            _ = sigterm.recv() => break,
+        }
+    }
+
+    // 종료 시그널: 수집 중단, 남은 이벤트 처리, 커널 후킹 분리 후 최종 리포트 출력
+    info!("Shutdown signal received");
+    detector.shutdown().await;
+    detector.stop_round().await;
+    export_scoreboard(&detector, args.scoreboard_out.as_deref()).await;
+    info!("Final Report:\n{}", detector.generate_report().await);
+
+    Ok(())
+}
+
+/// 팀별 순위를 파일로 내보내기 (실패해도 에이전트는 계속 실행)
+async fn export_scoreboard(detector: &plugins::reverse_shell::ReverseShellDetector, path: Option<&std::path::Path>) {
+    if let Some(path) = path {
+        if let Err(e) = detector.export_scoreboard(path).await {
+            error!("Failed to export scoreboard to {}: {}", path.display(), e);
+        }
     }
 }
+
+/// 실행 중인 에이전트에 라운드 시작/종료 요청
+async fn control_round(api: String, action: RoundAction) -> Result<()> {
+    let target = match action {
+        RoundAction::Start { id: Some(id) } => format!("/rounds/start?id={}", id),
+        RoundAction::Start { id: None } => "/rounds/start".to_string(),
+        RoundAction::Stop => "/rounds/stop".to_string(),
+    };
+    let body = http::post(&api, &target).await?;
+    println!("{}", String::from_utf8_lossy(&body));
+    Ok(())
+}
+
+/// 기준선 파일 조회/편집
+fn edit_baseline(path: PathBuf, action: BaselineAction) -> Result<()> {
+    let mut data = if path.exists() {
+        BaselineData::load(&path)?
+    } else {
+        BaselineData::default()
+    };
+
+    match action {
+        BaselineAction::Show => {
+            println!("Listening ports ({}):", data.listen.len());
+            for entry in &data.listen {
+                println!("  {:<24} {}", entry.process, entry.port);
+            }
+            println!("Outbound destinations ({}):", data.outbound.len());
+            for entry in &data.outbound {
+                println!("  {:<24} {}:{}", entry.process, entry.destination, entry.port);
+            }
+            return Ok(());
+        }
+        BaselineAction::AddListen { process, port } => {
+            data.listen.insert(ListenEntry { process, port });
+        }
+        BaselineAction::RemoveListen { process, port } => {
+            data.listen.remove(&ListenEntry { process, port });
+        }
+        BaselineAction::AddOutbound { process, destination, port } => {
+            data.outbound.insert(OutboundEntry { process, destination, port });
+        }
+        BaselineAction::RemoveOutbound { process, destination, port } => {
+            data.outbound.remove(&OutboundEntry { process, destination, port });
+        }
+        BaselineAction::Clear => {
+            data = BaselineData::default();
+        }
+    }
+
+    data.save(&path)?;
+    println!("Baseline saved to {}", path.display());
+    Ok(())
+}
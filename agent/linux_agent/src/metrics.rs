@@ -0,0 +1,119 @@
+use std::fmt::Display;
+use std::sync::Arc;
+use log::info;
+use crate::http::{self, Listener, Request, Response};
+use crate::plugins::reverse_shell::ReverseShellDetector;
+
+/// Prometheus 텍스트 형식 content type
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Prometheus 텍스트 형식 작성기
+#[derive(Default)]
+pub struct Exposition {
+    out: String,
+}
+
+impl Exposition {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 지표 이름, 종류 (counter, gauge, summary), 설명
+    pub fn family(&mut self, name: &str, kind: &str, help: &str) {
+        self.out.push_str(&format!("# HELP {} {}\n# TYPE {} {}\n", name, help, name, kind));
+    }
+
+    /// 값 한 줄
+    pub fn sample(&mut self, name: &str, labels: &[(&str, &str)], value: impl Display) {
+        self.out.push_str(name);
+        if !labels.is_empty() {
+            let labels: Vec<String> = labels.iter()
+                .map(|(key, value)| format!("{}=\"{}\"", key, escape_label(value)))
+                .collect();
+            self.out.push_str(&format!("{{{}}}", labels.join(",")));
+        }
+        self.out.push_str(&format!(" {}\n", value));
+    }
+
+    pub fn finish(self) -> String {
+        self.out
+    }
+}
+
+/// 레이블 값에서 `\`, `"`, 줄바꿈 이스케이프
+fn escape_label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+/// 에이전트 프로세스 RSS (바이트)
+pub fn resident_memory_bytes() -> Option<u64> {
+    let statm = std::fs::read_to_string("/proc/self/statm").ok()?;
+    let pages: u64 = statm.split_whitespace().nth(1)?.parse().ok()?;
+    let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) };
+    Some(pages * page_size.max(0) as u64)
+}
+
+/// `/metrics` 엔드포인트 제공
+pub async fn serve(listener: Listener, detector: Arc<ReverseShellDetector>) {
+    info!("Serving Prometheus metrics on {} (GET /metrics)", listener.local_addr());
+
+    http::serve(listener, move |request: Request| {
+        let detector = Arc::clone(&detector);
+        async move {
+            match (request.method.as_str(), request.path.as_str()) {
+                ("GET", "/metrics") => Response::ok(CONTENT_TYPE, detector.render_metrics().await),
+                (_, "/metrics") => Response::error(405, "method not allowed"),
+                _ => Response::not_found(),
+            }
+        }
+    }).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::{TcpListener, TcpStream};
+    use crate::source::replay::ReplaySource;
+
+    #[test]
+    fn test_exposition_format() {
+        let mut exposition = Exposition::new();
+        exposition.family("telemagnifier_events_total", "counter", "Detection events");
+        exposition.sample("telemagnifier_events_total", &[("rule_id", "a\"b"), ("severity", "high")], 3);
+        exposition.sample("telemagnifier_up", &[], 1);
+
+        assert_eq!(exposition.finish(),
+            "# HELP telemagnifier_events_total Detection events\n\
+             # TYPE telemagnifier_events_total counter\n\
+             telemagnifier_events_total{rule_id=\"a\\\"b\",severity=\"high\"} 3\n\
+             telemagnifier_up 1\n");
+        assert!(resident_memory_bytes().unwrap() > 0);
+    }
+
+    #[tokio::test]
+    async fn test_metrics_endpoint() {
+        let detector = Arc::new(ReverseShellDetector::new());
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/replay/ctf_reverse_shell.jsonl");
+        detector.start_with_source(Box::new(ReplaySource::new(path, false))).await.unwrap();
+        detector.wait_until_drained().await;
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(serve(listener.into(), Arc::clone(&detector)));
+
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream.write_all(b"GET /metrics HTTP/1.1\r\n\r\n").await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.contains(
+            "telemagnifier_events_total{rule_id=\"interpreter_listen\",event_type=\"bind_shell_detected\",severity=\"critical\"} 1\n"));
+        assert!(response.contains("telemagnifier_listening_sockets 3\n"));
+        assert!(response.contains("# TYPE telemagnifier_scan_duration_seconds summary\n"));
+        assert!(response.contains("telemagnifier_source_info{source=\"replay\",running=\"1\"} 1\n"));
+        assert!(response.contains("telemagnifier_process_resident_memory_bytes "));
+    }
+}
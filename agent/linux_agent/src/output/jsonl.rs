@@ -0,0 +1,338 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use log::{info, warn};
+
+use crate::output::Sink;
+use crate::plugins::reverse_shell::ReverseShellEvent;
+
+/// 디스크 동기화 (fsync) 정책
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum FsyncPolicy {
+    /// 운영체제에 맡김
+    Never,
+    /// 파일 교체 및 종료 시에만
+    #[default]
+    Rotate,
+    /// 이벤트마다 (가장 안전하지만 느림)
+    Always,
+}
+
+/// JSON Lines 파일 싱크 설정
+#[derive(Debug, Clone)]
+pub struct JsonlConfig {
+    /// 현재 기록 중인 파일 경로
+    pub path: PathBuf,
+    /// 파일이 이 크기(바이트)를 넘으면 교체
+    pub max_bytes: Option<u64>,
+    /// 파일을 연 뒤 이 시간이 지나면 교체
+    pub max_age: Option<Duration>,
+    /// 교체된 파일을 gzip 으로 압축
+    pub compress: bool,
+    pub fsync: FsyncPolicy,
+    /// 보관할 교체 파일 수 (0 이면 모두 보관)
+    pub keep: usize,
+}
+
+/// 이벤트 한 건을 JSON 객체 한 줄로 기록하는 파일 싱크
+pub struct JsonlSink {
+    config: JsonlConfig,
+    /// 한 줄을 한 번의 write 로 넘기므로 버퍼 없이 기록
+    file: File,
+    /// 현재 파일 크기
+    written: u64,
+    /// 현재 파일을 연 시각
+    opened_at: Instant,
+}
+
+impl JsonlConfig {
+    /// 크기/시간 교체 없이 한 파일에 기록하는 설정
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            max_bytes: None,
+            max_age: None,
+            compress: false,
+            fsync: FsyncPolicy::default(),
+            keep: 0,
+        }
+    }
+}
+
+impl JsonlSink {
+    /// 출력 파일 열기 (기존 파일이 있으면 이어서 기록)
+    pub fn open(config: JsonlConfig) -> Result<Self> {
+        if let Some(parent) = config.path.parent() {
+            if !parent.as_os_str().is_empty() {
+                fs::create_dir_all(parent)?;
+            }
+        }
+
+        let (file, written) = Self::open_file(&config.path)?;
+        info!("Writing events to {}", config.path.display());
+
+        Ok(Self {
+            config,
+            file,
+            written,
+            opened_at: Instant::now(),
+        })
+    }
+
+    fn open_file(path: &Path) -> Result<(File, u64)> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("failed to open {}", path.display()))?;
+        let written = file.metadata()?.len();
+        Ok((file, written))
+    }
+
+    /// 지정한 시각 기준으로 교체가 필요하면 교체한 뒤 기록
+    fn write_at(&mut self, event: &ReverseShellEvent, now: Instant) -> Result<()> {
+        let mut line = serde_json::to_vec(event)?;
+        line.push(b'\n');
+
+        if self.should_rotate(line.len() as u64, now) {
+            self.rotate(now)?;
+        }
+
+        // 한 줄 단위로 커널에 넘겨 비정상 종료 시에도 잘린 줄이 남지 않도록 함
+        self.file.write_all(&line)?;
+        self.written += line.len() as u64;
+
+        if self.config.fsync == FsyncPolicy::Always {
+            self.file.sync_data()?;
+        }
+        Ok(())
+    }
+
+    /// 빈 파일은 교체하지 않음
+    fn should_rotate(&self, next_len: u64, now: Instant) -> bool {
+        if self.written == 0 {
+            return false;
+        }
+
+        let too_large = self.config.max_bytes.is_some_and(|max| self.written + next_len > max);
+        let too_old = self.config.max_age.is_some_and(|max| now.duration_since(self.opened_at) >= max);
+        too_large || too_old
+    }
+
+    /// 현재 파일을 `<파일명>.<유닉스 밀리초>` 로 옮기고 새 파일 열기
+    fn rotate(&mut self, now: Instant) -> Result<()> {
+        if self.config.fsync != FsyncPolicy::Never {
+            self.file.sync_all()?;
+        }
+
+        let rotated = self.rotated_path();
+        fs::rename(&self.config.path, &rotated)?;
+
+        let (file, written) = Self::open_file(&self.config.path)?;
+        self.file = file;
+        self.written = written;
+        self.opened_at = now;
+
+        if self.config.compress {
+            if let Err(e) = Self::compress(&rotated) {
+                warn!("Failed to compress {}: {}", rotated.display(), e);
+            }
+        }
+        self.prune()?;
+
+        info!("Rotated event output to {}", rotated.display());
+        Ok(())
+    }
+
+    /// 같은 밀리초에 여러 번 교체되면 뒤에 번호를 붙임
+    fn rotated_path(&self) -> PathBuf {
+        let millis = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_millis());
+        let base = format!("{}.{}", self.config.path.display(), millis);
+
+        let mut candidate = PathBuf::from(&base);
+        let mut suffix = 1;
+        while candidate.exists() || Path::new(&format!("{}.gz", candidate.display())).exists() {
+            candidate = PathBuf::from(format!("{}-{}", base, suffix));
+            suffix += 1;
+        }
+        candidate
+    }
+
+    /// 교체된 파일을 `.gz` 로 압축하고 원본 삭제
+    fn compress(path: &Path) -> Result<()> {
+        let compressed = PathBuf::from(format!("{}.gz", path.display()));
+        let mut encoder = GzEncoder::new(File::create(&compressed)?, Compression::default());
+        io::copy(&mut File::open(path)?, &mut encoder)?;
+        encoder.finish()?.sync_all()?;
+        fs::remove_file(path)?;
+        Ok(())
+    }
+
+    /// 보관 개수를 넘는 오래된 교체 파일 삭제
+    fn prune(&self) -> Result<()> {
+        if self.config.keep == 0 {
+            return Ok(());
+        }
+
+        let mut rotated = self.rotated_files()?;
+        if rotated.len() > self.config.keep {
+            let excess = rotated.len() - self.config.keep;
+            for path in rotated.drain(..excess) {
+                fs::remove_file(&path)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// 교체된 파일 목록 (오래된 순)
+    fn rotated_files(&self) -> Result<Vec<PathBuf>> {
+        let dir = match self.config.path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
+        let prefix = format!("{}.", self.config.path.file_name().unwrap_or_default().to_string_lossy());
+
+        let mut files: Vec<((u128, u32), PathBuf)> = fs::read_dir(&dir)?
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                let name = entry.file_name().to_string_lossy().into_owned();
+                let order = Self::rotation_order(name.strip_prefix(&prefix)?)?;
+                Some((order, entry.path()))
+            })
+            .collect();
+
+        files.sort();
+        Ok(files.into_iter().map(|(_, path)| path).collect())
+    }
+
+    /// `<밀리초>[-<번호>][.gz]` 에서 (밀리초, 번호) 추출
+    fn rotation_order(stamp: &str) -> Option<(u128, u32)> {
+        let stamp = stamp.strip_suffix(".gz").unwrap_or(stamp);
+        let (millis, suffix) = stamp.split_once('-').unwrap_or((stamp, "0"));
+        Some((millis.parse().ok()?, suffix.parse().ok()?))
+    }
+}
+
+impl Sink for JsonlSink {
+    fn name(&self) -> &'static str {
+        "jsonl"
+    }
+
+    fn write(&mut self, event: &ReverseShellEvent) -> Result<()> {
+        self.write_at(event, Instant::now())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        if self.config.fsync != FsyncPolicy::Never {
+            self.file.sync_all()?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+    use flate2::read::GzDecoder;
+    use crate::plugins::reverse_shell::{EventType, Severity};
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("jsonl-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn event(rule_id: &str) -> ReverseShellEvent {
+        ReverseShellEvent {
+            timestamp: Instant::now(),
+            event_type: EventType::FilelessExecution,
+            severity: Severity::Critical,
+            rule_id: rule_id.to_string(),
+            details: "Fileless execution: pid 510 running from /memfd:x (deleted)".to_string(),
+            connection_info: None,
+            process: None,
+            incident_id: None,
+            round_id: None,
+            techniques: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_one_json_object_per_line() {
+        let dir = temp_dir("lines");
+        let path = dir.join("events.jsonl");
+        let mut sink = JsonlSink::open(JsonlConfig::new(path.clone())).unwrap();
+
+        sink.write(&event("memfd_executable")).unwrap();
+        sink.write(&event("kernel_memfd_create")).unwrap();
+        sink.flush().unwrap();
+
+        let content = fs::read_to_string(&path).unwrap();
+        let lines: Vec<serde_json::Value> = content.lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["rule_id"], "memfd_executable");
+        assert_eq!(lines[0]["severity"], "critical");
+        assert_eq!(lines[0]["event_type"], "fileless_execution");
+        assert!(lines[1]["timestamp"].as_u64().unwrap() > 0);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_size_rotation_with_compression_and_keep() {
+        let dir = temp_dir("size");
+        let path = dir.join("events.jsonl");
+        let mut config = JsonlConfig::new(path.clone());
+        config.max_bytes = Some(1);
+        config.compress = true;
+        config.keep = 2;
+        let mut sink = JsonlSink::open(config).unwrap();
+
+        // 한 줄만 써도 최대 크기를 넘으므로 매 이벤트마다 교체
+        for i in 0..4 {
+            sink.write(&event(&format!("rule_{}", i))).unwrap();
+        }
+        sink.flush().unwrap();
+
+        let rotated = sink.rotated_files().unwrap();
+        assert_eq!(rotated.len(), 2);
+        assert!(rotated.iter().all(|p| p.extension().unwrap() == "gz"));
+
+        // 가장 최근 교체 파일은 직전 이벤트, 현재 파일은 마지막 이벤트
+        let mut decoded = String::new();
+        GzDecoder::new(File::open(&rotated[1]).unwrap()).read_to_string(&mut decoded).unwrap();
+        assert!(decoded.contains("rule_2"));
+        assert!(fs::read_to_string(&path).unwrap().contains("rule_3"));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_time_rotation() {
+        let dir = temp_dir("time");
+        let path = dir.join("events.jsonl");
+        let mut config = JsonlConfig::new(path.clone());
+        config.max_age = Some(Duration::from_secs(3600));
+        config.fsync = FsyncPolicy::Always;
+        let mut sink = JsonlSink::open(config).unwrap();
+        let opened = sink.opened_at;
+
+        sink.write_at(&event("first"), opened).unwrap();
+        sink.write_at(&event("same_file"), opened + Duration::from_secs(60)).unwrap();
+        assert!(sink.rotated_files().unwrap().is_empty());
+
+        sink.write_at(&event("next_file"), opened + Duration::from_secs(3600)).unwrap();
+        sink.flush().unwrap();
+
+        let rotated = sink.rotated_files().unwrap();
+        assert_eq!(rotated.len(), 1);
+        assert_eq!(fs::read_to_string(&rotated[0]).unwrap().lines().count(), 2);
+        assert!(fs::read_to_string(&path).unwrap().contains("next_file"));
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
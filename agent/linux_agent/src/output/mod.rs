@@ -0,0 +1,133 @@
+pub mod jsonl;
+pub mod syslog;
+
+use anyhow::Result;
+use log::{error, info, warn};
+use tokio::task::JoinHandle;
+
+use crate::event_bus::Subscription;
+use crate::plugins::reverse_shell::{ReverseShellEvent, Severity};
+
+/// 탐지 이벤트 출력 싱크 (이벤트 버스 구독자)
+pub trait Sink: Send + 'static {
+    /// 싱크 이름 (버스 지표의 구독자 이름)
+    fn name(&self) -> &'static str;
+
+    /// 이벤트 한 건 출력
+    fn write(&mut self, event: &ReverseShellEvent) -> Result<()>;
+
+    /// 버퍼에 남은 출력 반영 (종료 시 호출)
+    fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// 심각도에 따른 로그 레벨로 이벤트를 기록하는 싱크
+pub struct LogSink;
+
+impl Sink for LogSink {
+    fn name(&self) -> &'static str {
+        "log"
+    }
+
+    fn write(&mut self, event: &ReverseShellEvent) -> Result<()> {
+        match event.severity {
+            Severity::Critical | Severity::High => error!("[{}] {}", event.rule_id, event.details),
+            Severity::Medium => warn!("[{}] {}", event.rule_id, event.details),
+            Severity::Low => info!("[{}] {}", event.rule_id, event.details),
+        }
+        Ok(())
+    }
+}
+
+/// 구독한 이벤트를 싱크로 전달하는 태스크 시작 (버스가 닫히면 flush 후 종료)
+///
+/// 싱크는 파일/소켓 I/O 를 블로킹으로 수행하므로 쓰기와 flush 는 블로킹 스레드에서 실행
+pub fn spawn(mut subscription: Subscription, mut sink: Box<dyn Sink>) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        while let Some(event) = subscription.recv().await {
+            let written = tokio::task::spawn_blocking(move || {
+                let result = sink.write(&event);
+                (sink, result)
+            }).await;
+
+            match written {
+                Ok((returned, result)) => {
+                    sink = returned;
+                    if let Err(e) = result {
+                        warn!("Output sink '{}' failed to write event: {}", sink.name(), e);
+                    }
+                }
+                Err(e) => {
+                    error!("Output sink task panicked: {}", e);
+                    return;
+                }
+            }
+        }
+
+        let name = sink.name();
+        match tokio::task::spawn_blocking(move || sink.flush()).await {
+            Ok(Err(e)) => warn!("Output sink '{}' failed to flush: {}", name, e),
+            Err(e) => error!("Output sink '{}' panicked while flushing: {}", name, e),
+            Ok(Ok(())) => {}
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    use std::time::Instant;
+    use crate::event_bus::EventBus;
+    use crate::plugins::reverse_shell::EventType;
+
+    /// 출력한 규칙 ID 와 flush 여부를 기록하는 테스트 싱크
+    struct MemorySink {
+        written: Arc<Mutex<Vec<String>>>,
+        flushed: Arc<Mutex<bool>>,
+    }
+
+    impl Sink for MemorySink {
+        fn name(&self) -> &'static str {
+            "memory"
+        }
+
+        fn write(&mut self, event: &ReverseShellEvent) -> Result<()> {
+            self.written.lock().unwrap().push(event.rule_id.clone());
+            Ok(())
+        }
+
+        fn flush(&mut self) -> Result<()> {
+            *self.flushed.lock().unwrap() = true;
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sink_flushed_on_close() {
+        let bus = EventBus::default();
+        let written = Arc::new(Mutex::new(Vec::new()));
+        let flushed = Arc::new(Mutex::new(false));
+        let sink = MemorySink { written: Arc::clone(&written), flushed: Arc::clone(&flushed) };
+
+        let task = spawn(bus.subscribe(sink.name()).await, Box::new(sink));
+        bus.publish(ReverseShellEvent {
+            timestamp: Instant::now(),
+            event_type: EventType::BindShellDetected,
+            severity: Severity::Critical,
+            rule_id: "interpreter_listen".to_string(),
+            details: String::new(),
+            connection_info: None,
+            process: None,
+            incident_id: None,
+            round_id: None,
+            techniques: Vec::new(),
+        });
+        bus.close();
+        task.await.unwrap();
+
+        assert_eq!(*written.lock().unwrap(), vec!["interpreter_listen"]);
+        assert!(*flushed.lock().unwrap());
+    }
+}
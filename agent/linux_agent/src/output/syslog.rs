@@ -0,0 +1,526 @@
+use std::io::Write;
+use std::net::{TcpStream, ToSocketAddrs, UdpSocket};
+use std::os::unix::net::UnixDatagram;
+use std::time::{Duration, Instant};
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use log::{info, warn};
+
+use crate::attack;
+use crate::output::Sink;
+use crate::plugins::reverse_shell::{unix_millis, ReverseShellEvent, Severity};
+
+/// RFC 5424 구조화 데이터 ID (RFC 5612 문서용 기업 번호)
+const SD_ID: &str = "telemagnifier@32473";
+const APP_NAME: &str = "telemagnifier";
+/// TCP 연결 및 쓰기 제한 시간
+const TCP_TIMEOUT: Duration = Duration::from_secs(5);
+/// TCP 재연결 대기 시간 (실패할 때마다 두 배, 최대 RECONNECT_BACKOFF_MAX)
+const RECONNECT_BACKOFF_MIN: Duration = Duration::from_secs(1);
+const RECONNECT_BACKOFF_MAX: Duration = Duration::from_secs(60);
+
+/// syslog 전송 방식
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum SyslogTransport {
+    #[default]
+    Udp,
+    /// RFC 6587 octet counting 프레이밍
+    Tcp,
+    /// 유닉스 datagram 소켓 (예: /dev/log)
+    Unix,
+}
+
+/// 메시지 형식
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum SyslogFormat {
+    /// RFC 5424 (구조화 데이터에 탐지 정보 포함)
+    #[default]
+    Rfc5424,
+    /// RFC 5424 헤더 + ArcSight CEF 본문
+    Cef,
+}
+
+/// syslog 싱크 설정
+#[derive(Debug, Clone)]
+pub struct SyslogConfig {
+    pub transport: SyslogTransport,
+    /// host:port 또는 유닉스 소켓 경로
+    pub address: String,
+    pub format: SyslogFormat,
+    /// syslog facility (기본값 10, authpriv)
+    pub facility: u8,
+}
+
+/// syslog 연결
+enum Connection {
+    Udp(UdpSocket),
+    Tcp(TcpConnection),
+    Unix(UnixDatagram),
+}
+
+/// TCP syslog 연결 (끊어지면 대기 시간을 늘려 가며 다시 연결)
+struct TcpConnection {
+    stream: Option<TcpStream>,
+    /// 다음 재연결을 시도할 수 있는 시각
+    retry_at: Instant,
+    backoff: Duration,
+}
+
+/// 이벤트를 RFC 5424 syslog 또는 CEF 로 전송하는 싱크
+pub struct SyslogSink {
+    config: SyslogConfig,
+    hostname: String,
+    connection: Connection,
+}
+
+impl SyslogConfig {
+    /// UDP, RFC 5424, authpriv 기본 설정
+    pub fn new(address: String) -> Self {
+        Self {
+            transport: SyslogTransport::default(),
+            address,
+            format: SyslogFormat::default(),
+            facility: 10,
+        }
+    }
+}
+
+impl SyslogSink {
+    /// syslog 서버 연결
+    pub fn connect(config: SyslogConfig) -> Result<Self> {
+        let connection = match config.transport {
+            SyslogTransport::Udp => {
+                let socket = UdpSocket::bind("0.0.0.0:0")?;
+                socket.connect(&config.address)
+                    .with_context(|| format!("failed to connect to syslog {}", config.address))?;
+                Connection::Udp(socket)
+            }
+            SyslogTransport::Tcp => Connection::Tcp(TcpConnection::new(Self::connect_tcp(&config.address)?)),
+            SyslogTransport::Unix => {
+                let socket = UnixDatagram::unbound()?;
+                socket.connect(&config.address)
+                    .with_context(|| format!("failed to connect to syslog {}", config.address))?;
+                Connection::Unix(socket)
+            }
+        };
+
+        info!("Sending events to syslog {:?} {} ({:?})", config.transport, config.address, config.format);
+
+        Ok(Self {
+            config,
+            hostname: Self::hostname(),
+            connection,
+        })
+    }
+
+    /// 제한 시간 안에 연결 (주소가 여러 개로 풀리면 차례로 시도)
+    fn connect_tcp(address: &str) -> Result<TcpStream> {
+        let mut last_error = None;
+        for addr in address.to_socket_addrs().with_context(|| format!("failed to resolve syslog {}", address))? {
+            match TcpStream::connect_timeout(&addr, TCP_TIMEOUT) {
+                Ok(stream) => {
+                    stream.set_write_timeout(Some(TCP_TIMEOUT))?;
+                    return Ok(stream);
+                }
+                Err(e) => last_error = Some(e),
+            }
+        }
+
+        let error = last_error.map_or_else(|| anyhow::anyhow!("no addresses"), anyhow::Error::from);
+        Err(error.context(format!("failed to connect to syslog {}", address)))
+    }
+
+    fn hostname() -> String {
+        std::fs::read_to_string("/proc/sys/kernel/hostname")
+            .map(|name| name.trim().to_string())
+            .ok()
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| "-".to_string())
+    }
+
+    /// syslog 심각도 (crit, err, warning, notice)
+    fn syslog_severity(severity: &Severity) -> u8 {
+        match severity {
+            Severity::Critical => 2,
+            Severity::High => 3,
+            Severity::Medium => 4,
+            Severity::Low => 5,
+        }
+    }
+
+    /// CEF 심각도 (0-10)
+    fn cef_severity(severity: &Severity) -> u8 {
+        match severity {
+            Severity::Critical => 10,
+            Severity::High => 8,
+            Severity::Medium => 5,
+            Severity::Low => 3,
+        }
+    }
+
+    /// 이벤트를 syslog 메시지로 변환
+    fn format(&self, event: &ReverseShellEvent) -> String {
+        let pri = self.config.facility as u32 * 8 + Self::syslog_severity(&event.severity) as u32;
+        let header = format!("<{}>1 {} {} {} {} {}",
+            pri,
+            format_rfc3339(unix_millis(event.timestamp)),
+            self.hostname,
+            APP_NAME,
+            std::process::id(),
+            Self::msg_id(&event.rule_id));
+
+        match self.config.format {
+            SyslogFormat::Rfc5424 => format!("{} {} {}", header, Self::structured_data(event), event.details),
+            SyslogFormat::Cef => format!("{} - {}", header, Self::cef(event)),
+        }
+    }
+
+    /// MSGID 는 출력 가능한 ASCII 32자 이하
+    fn msg_id(rule_id: &str) -> String {
+        let id: String = rule_id.chars().filter(|c| c.is_ascii_graphic()).take(32).collect();
+        if id.is_empty() { "-".to_string() } else { id }
+    }
+
+    /// RFC 5424 구조화 데이터
+    fn structured_data(event: &ReverseShellEvent) -> String {
+        let mut params = vec![
+            ("rule_id", event.rule_id.clone()),
+            ("severity", event.severity.as_str().to_string()),
+            ("event_type", event.event_type.as_str().to_string()),
+        ];
+        if !event.techniques.is_empty() {
+            params.push(("technique", attack::technique_ids(&event.techniques)));
+            params.push(("tactic", attack::tactic_names(&event.techniques)));
+        }
+        if let Some(round_id) = event.round_id {
+            params.push(("round", round_id.to_string()));
+        }
+
+        if let Some(conn) = &event.connection_info {
+            params.push(("pid", conn.pid.to_string()));
+            params.push(("src", conn.local_ip()));
+            params.push(("sport", conn.local_port.to_string()));
+            params.push(("dst", conn.remote_ip()));
+            params.push(("dport", conn.remote_port.to_string()));
+        }
+        if let Some(process) = &event.process {
+            params.push(("uid", process.uid.to_string()));
+            if let Some(exe) = &process.exe {
+                params.push(("exe", exe.clone()));
+            }
+        }
+
+        let params: Vec<String> = params.into_iter()
+            .map(|(name, value)| format!("{}=\"{}\"", name, escape_sd_value(&value)))
+            .collect();
+        format!("[{} {}]", SD_ID, params.join(" "))
+    }
+
+    /// ArcSight CEF 본문
+    fn cef(event: &ReverseShellEvent) -> String {
+        let mut extensions = vec![
+            ("rt", unix_millis(event.timestamp).to_string()),
+            ("cat", event.event_type.as_str().to_string()),
+        ];
+
+        if let Some(conn) = &event.connection_info {
+            extensions.push(("spid", conn.pid.to_string()));
+            extensions.push(("sproc", conn.process_name.clone()));
+            // CEF 의 src 는 IPv4 전용이라 IPv6 로컬 주소는 c6a2 (source IPv6 address) 로 보냄
+            match conn.local_addr6 {
+                Some(addr) => {
+                    extensions.push(("c6a2", addr.to_string()));
+                    extensions.push(("c6a2Label", "Source IPv6 Address".to_string()));
+                }
+                None => extensions.push(("src", conn.local_ip())),
+            }
+            extensions.push(("spt", conn.local_port.to_string()));
+            // IPv6 원격 주소도 같은 이유로 c6a3 (destination IPv6 address) 로 보냄
+            match conn.remote_addr6 {
+                Some(addr) => {
+                    extensions.push(("c6a3", addr.to_string()));
+                    extensions.push(("c6a3Label", "Destination IPv6 Address".to_string()));
+                }
+                None => extensions.push(("dst", conn.remote_ip())),
+            }
+            extensions.push(("dpt", conn.remote_port.to_string()));
+        }
+        if let Some(process) = &event.process {
+            extensions.push(("suid", process.uid.to_string()));
+            if let Some(exe) = &process.exe {
+                extensions.push(("filePath", exe.clone()));
+            }
+            extensions.push(("cs1Label", "cmdline".to_string()));
+            extensions.push(("cs1", process.command_line()));
+        }
+        if !event.techniques.is_empty() {
+            extensions.push(("cs2Label", "mitreTechnique".to_string()));
+            extensions.push(("cs2", attack::technique_ids(&event.techniques)));
+            extensions.push(("cs3Label", "mitreTactic".to_string()));
+            extensions.push(("cs3", attack::tactic_names(&event.techniques)));
+        }
+        if let Some(round_id) = event.round_id {
+            extensions.push(("cn1Label", "round".to_string()));
+            extensions.push(("cn1", round_id.to_string()));
+        }
+
+        let extensions: Vec<String> = extensions.into_iter()
+            .map(|(key, value)| format!("{}={}", key, escape_cef_extension(&value)))
+            .collect();
+
+        format!("CEF:0|Telemagnifier|linux_agent|{}|{}|{}|{}|{}",
+            env!("CARGO_PKG_VERSION"),
+            escape_cef_header(&event.rule_id),
+            escape_cef_header(&event.details),
+            Self::cef_severity(&event.severity),
+            extensions.join(" "))
+    }
+
+    /// 메시지 전송
+    fn send(&mut self, message: &str) -> Result<()> {
+        match &mut self.connection {
+            Connection::Udp(socket) => {
+                socket.send(message.as_bytes())?;
+            }
+            Connection::Unix(socket) => {
+                socket.send(message.as_bytes())?;
+            }
+            Connection::Tcp(tcp) => {
+                let frame = format!("{} {}", message.len(), message);
+                tcp.send(&self.config.address, frame.as_bytes(), Instant::now())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl TcpConnection {
+    fn new(stream: TcpStream) -> Self {
+        Self {
+            stream: Some(stream),
+            retry_at: Instant::now(),
+            backoff: RECONNECT_BACKOFF_MIN,
+        }
+    }
+
+    /// 프레임 전송 (연결이 끊어졌으면 재연결 대기 시간이 지난 경우에만 다시 연결, 그 전에는 버림)
+    fn send(&mut self, address: &str, frame: &[u8], now: Instant) -> Result<()> {
+        if let Some(stream) = &mut self.stream {
+            match stream.write_all(frame) {
+                Ok(()) => return Ok(()),
+                Err(e) => {
+                    warn!("Lost connection to syslog {}: {}", address, e);
+                    self.stream = None;
+                }
+            }
+        }
+
+        if now < self.retry_at {
+            return Err(anyhow::anyhow!("syslog {} is disconnected, reconnecting in {:.1}s",
+                address, (self.retry_at - now).as_secs_f64()));
+        }
+
+        let connected = SyslogSink::connect_tcp(address).and_then(|mut stream| {
+            stream.write_all(frame)?;
+            Ok(stream)
+        });
+        match connected {
+            Ok(stream) => {
+                self.stream = Some(stream);
+                self.backoff = RECONNECT_BACKOFF_MIN;
+                Ok(())
+            }
+            Err(e) => {
+                self.retry_at = now + self.backoff;
+                self.backoff = (self.backoff * 2).min(RECONNECT_BACKOFF_MAX);
+                Err(e)
+            }
+        }
+    }
+}
+
+impl Sink for SyslogSink {
+    fn name(&self) -> &'static str {
+        "syslog"
+    }
+
+    fn write(&mut self, event: &ReverseShellEvent) -> Result<()> {
+        let message = self.format(event);
+        self.send(&message)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        if let Connection::Tcp(TcpConnection { stream: Some(stream), .. }) = &mut self.connection {
+            stream.flush()?;
+        }
+        Ok(())
+    }
+}
+
+/// 유닉스 밀리초를 RFC 3339 UTC 시각으로 변환 (예: 2024-01-15T10:30:45.123Z)
+fn format_rfc3339(millis: u64) -> String {
+    let secs = millis / 1000;
+    let (days, secs_of_day) = ((secs / 86400) as i64, secs % 86400);
+
+    // 1970-01-01 기준 일수를 그레고리력 날짜로 변환
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year, month, day,
+        secs_of_day / 3600, secs_of_day % 3600 / 60, secs_of_day % 60,
+        millis % 1000)
+}
+
+/// 구조화 데이터 값에서 `"`, `\`, `]` 이스케이프
+fn escape_sd_value(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '"' | '\\' | ']') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// CEF 헤더 필드에서 `\`, `|` 이스케이프
+fn escape_cef_header(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('|', "\\|").replace(['\r', '\n'], " ")
+}
+
+/// CEF 확장 값에서 `\`, `=`, 줄바꿈 이스케이프
+fn escape_cef_extension(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('=', "\\=").replace('\r', "\\r").replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+    use std::net::TcpListener;
+    use crate::plugins::reverse_shell::{ConnectionInfo, EventType};
+
+    fn connection_event() -> ReverseShellEvent {
+        ReverseShellEvent {
+            timestamp: Instant::now(),
+            event_type: EventType::KernelHookTriggered,
+            severity: Severity::Critical,
+            rule_id: "suspicious_connection".to_string(),
+            details: "Kernel hook detected suspicious connection: 10.0.0.2 -> 8.8.8.8:4444".to_string(),
+            connection_info: Some(ConnectionInfo {
+                local_addr: 0x0A000002,
+                local_addr6: None,
+                remote_addr: 0x08080808,
+                remote_addr6: None,
+                local_port: 40000,
+                remote_port: 4444,
+                pid: 4343,
+                process_name: "bash".to_string(),
+                first_seen: Instant::now(),
+                last_seen: Instant::now(),
+                connection_count: 1,
+                is_suspicious: true,
+            }),
+            process: None,
+            incident_id: None,
+            round_id: Some(3),
+            techniques: vec![attack::NON_STANDARD_PORT],
+        }
+    }
+
+    #[test]
+    fn test_rfc5424_over_udp() {
+        let server = UdpSocket::bind("127.0.0.1:0").unwrap();
+        server.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+        let config = SyslogConfig::new(server.local_addr().unwrap().to_string());
+        let mut sink = SyslogSink::connect(config).unwrap();
+
+        sink.write(&connection_event()).unwrap();
+
+        let mut buf = [0u8; 2048];
+        let len = server.recv(&mut buf).unwrap();
+        let message = std::str::from_utf8(&buf[..len]).unwrap();
+
+        // authpriv(10) * 8 + crit(2) = 82
+        assert!(message.starts_with("<82>1 "));
+        assert!(message.contains(" telemagnifier "));
+        assert!(message.contains(" suspicious_connection [telemagnifier@32473 rule_id=\"suspicious_connection\" severity=\"critical\" event_type=\"kernel_hook_triggered\" "));
+        assert!(message.contains(" technique=\"T1571\" tactic=\"command-and-control\" round=\"3\" pid=\"4343\" "));
+        assert!(message.contains("dst=\"8.8.8.8\" dport=\"4444\"]"));
+        assert!(message.ends_with("8.8.8.8:4444"));
+    }
+
+    #[test]
+    fn test_cef_over_tcp_octet_counting() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut config = SyslogConfig::new(listener.local_addr().unwrap().to_string());
+        config.transport = SyslogTransport::Tcp;
+        config.format = SyslogFormat::Cef;
+        let mut sink = SyslogSink::connect(config).unwrap();
+        let (mut stream, _) = listener.accept().unwrap();
+
+        sink.write(&connection_event()).unwrap();
+        drop(sink);
+
+        let mut received = String::new();
+        stream.read_to_string(&mut received).unwrap();
+        let (len, message) = received.split_once(' ').unwrap();
+        assert_eq!(len.parse::<usize>().unwrap(), message.len());
+        assert!(message.contains(" - CEF:0|Telemagnifier|linux_agent|"));
+        assert!(message.contains("|suspicious_connection|Kernel hook detected suspicious connection: 10.0.0.2 -> 8.8.8.8:4444|10|"));
+        assert!(message.contains("|10|rt="));
+        assert!(message.contains(" cat=kernel_hook_triggered spid=4343 "));
+        assert!(message.contains("spid=4343 sproc=bash src=10.0.0.2 spt=40000 dst=8.8.8.8 dpt=4444"));
+        assert!(message.contains("cs2Label=mitreTechnique cs2=T1571 cs3Label=mitreTactic cs3=command-and-control cn1Label=round cn1=3"));
+    }
+
+    #[test]
+    fn test_tcp_reconnect_backoff() {
+        // 닫힌 포트
+        let address = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().to_string();
+        let now = Instant::now();
+        let mut tcp = TcpConnection { stream: None, retry_at: now, backoff: RECONNECT_BACKOFF_MIN };
+
+        // 연결 실패마다 대기 시간이 두 배로 늘고, 대기 중에는 연결을 시도하지 않음
+        assert!(tcp.send(&address, b"1 a", now).is_err());
+        assert_eq!((tcp.retry_at, tcp.backoff), (now + Duration::from_secs(1), Duration::from_secs(2)));
+        let waiting = tcp.send(&address, b"1 a", now + Duration::from_millis(500)).unwrap_err();
+        assert!(waiting.to_string().contains("reconnecting in 0.5s"));
+        assert_eq!(tcp.backoff, Duration::from_secs(2));
+
+        for _ in 0..10 {
+            let _ = tcp.send(&address, b"1 a", tcp.retry_at);
+        }
+        assert_eq!(tcp.backoff, RECONNECT_BACKOFF_MAX);
+
+        // 서버가 다시 열리면 연결하고 대기 시간 초기화
+        let listener = TcpListener::bind(&address).unwrap();
+        tcp.send(&address, b"1 a", tcp.retry_at).unwrap();
+        assert_eq!(tcp.backoff, RECONNECT_BACKOFF_MIN);
+        let (mut stream, _) = listener.accept().unwrap();
+        drop(tcp);
+        let mut received = String::new();
+        stream.read_to_string(&mut received).unwrap();
+        assert_eq!(received, "1 a");
+    }
+
+    #[test]
+    fn test_escaping_and_timestamp() {
+        assert_eq!(format_rfc3339(0), "1970-01-01T00:00:00.000Z");
+        assert_eq!(format_rfc3339(1705314645123), "2024-01-15T10:30:45.123Z");
+        assert_eq!(format_rfc3339(951782400000), "2000-02-29T00:00:00.000Z");
+
+        assert_eq!(escape_sd_value(r#"a"b]c\d"#), r#"a\"b\]c\\d"#);
+        assert_eq!(escape_cef_header("a|b\\c"), "a\\|b\\\\c");
+        assert_eq!(escape_cef_extension("x=1\nbash -i"), "x\\=1\\nbash -i");
+        assert_eq!(SyslogSink::msg_id("kernel memfd_create"), "kernelmemfd_create");
+    }
+}
@@ -1,67 +1,391 @@
-use std::collections::HashMap;
-use std::sync::{Arc, Mutex};
-use std::cell::RefCell;
-use std::time::{Duration, Instant};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::path::Path;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use anyhow::Result;
 use log::{info, warn, error, debug};
-use tokio::time::sleep;
-
-// eBPF 관련 구조체들
-use aya::{
-    include_bytes_aligned,
-    maps::{HashMap as AyaHashMap, MapData},
-    programs::{Xdp, XdpFlags, TracePoint},
-    Bpf, BpfLoader,
+use serde::{Serialize, Serializer};
+use tokio::sync::mpsc::{self, Receiver};
+use tokio::sync::{oneshot, Mutex, RwLock};
+use tokio::task::JoinHandle;
+
+use crate::attack::{self, TacticCoverage, Technique};
+use crate::baseline::{Baseline, BaselineData, BaselineVerdict};
+use crate::event_bus::{BusMetrics, EventBus, Subscription};
+use crate::incident::{Incident, IncidentTracker};
+use crate::metrics::{self, Exposition};
+use crate::output::{self, Sink};
+use crate::process_info::{self, ProcessCache, ProcessInfo, ProcessNode};
+use crate::response::{self, ResponseConfig, ResponseEngine};
+use crate::scoring::{Scoreboard, ScoreboardFeed, ScoringConfig};
+use crate::round::{self, Round, RoundConfig, RoundReset, RoundSignal, RoundSummary, RoundTracker};
+use crate::risk::{self, Escalation, EscalationKind, RiskConfig, RiskSummary, RiskTracker, RuleWeight, Subject};
+use crate::source::{
+    EventSender, EventSource, HookStatus, KernelEvent, KernelEventKind, ProcessSnapshot,
+    SocketSnapshot, SourceEvent, SourceHandle, SourceMetrics, TimedEvent, SOURCE_CHANNEL_SIZE,
 };
-use aya_log::BpfLogger;
+use crate::source::ebpf::EbpfSource;
+use crate::source::procfs::ProcfsSource;
+use crate::source::record::StreamRecorder;
+use crate::suppression::{Suppression, SuppressionList};
 
-/// 리버스 쉘 탐지 플러그인 (커널 기반)
+/// 리포트에 표시할 위험도 상위 프로세스 트리 수
+const REPORT_RISK_TREES: usize = 5;
+
+/// 리포트에 표시할 최근 사고 수
+const REPORT_INCIDENTS: usize = 5;
+
+/// 리포트에 표시할 사고별 타임라인 항목 수
+const REPORT_TIMELINE: usize = 10;
+
+/// 리버스 쉘 탐지 플러그인 (커널 기반, Arc 로 감싸 여러 태스크에서 동시에 조회 가능)
 pub struct ReverseShellDetector {
-    /// eBPF 프로그램
-    bpf: RefCell<Option<Bpf>>,
-    /// 탐지된 이벤트
-    detected_events: Arc<Mutex<Vec<ReverseShellEvent>>>,
+    /// 실행 중인 이벤트 소스 제어 (eBPF 프로그램은 소스 태스크가 소유)
+    source: Mutex<Option<SourceControl>>,
+    /// 이벤트 소스 상태 (리포트용)
+    source_status: RwLock<Option<SourceStatus>>,
+    /// 수집 지표 (스캔 소요 시간, 커널 이벤트 유실)
+    source_metrics: Arc<SourceMetrics>,
+    /// 이벤트 분석 태스크
+    analysis_task: Mutex<Option<JoinHandle<()>>>,
+    /// 분석기에 입력되는 이벤트 기록 (재생 회귀 테스트용)
+    stream_recorder: Mutex<Option<StreamRecorder>>,
+    /// 탐지된 이벤트 (리포트 및 조회용 기록)
+    detected_events: Arc<RwLock<Vec<ReverseShellEvent>>>,
+    /// 탐지 이벤트 버스 (출력 싱크는 여기에 구독)
+    event_bus: EventBus,
+    /// 출력 싱크 태스크
+    sinks: Mutex<Vec<JoinHandle<()>>>,
     /// 네트워크 연결 추적
-    connection_tracker: Arc<Mutex<HashMap<String, ConnectionInfo>>>,
+    connection_tracker: Arc<RwLock<HashMap<String, ConnectionInfo>>>,
+    /// 리스닝 소켓 추적 (바인드 쉘 탐지용)
+    listen_tracker: Arc<RwLock<HashMap<String, ConnectionInfo>>>,
+    /// 정상 동작 기준선
+    baseline: Arc<RwLock<Baseline>>,
+    /// 억제 규칙
+    suppressions: Arc<RwLock<SuppressionList>>,
+    /// 프로세스 식별 정보 캐시
+    process_cache: Arc<Mutex<ProcessCache>>,
+    /// 프로세스 트리, 호스트별 위험도 점수
+    risk: Arc<Mutex<RiskTracker>>,
+    /// 관련 이벤트를 묶은 사고
+    incidents: Arc<Mutex<IncidentTracker>>,
+    /// 대응 정책 (프로세스 종료, 연결/주소 차단)
+    response: Arc<Mutex<ResponseEngine>>,
+    /// 팀별 감점 (CTF 스코어보드)
+    scoreboard: Arc<Mutex<Scoreboard>>,
+    /// 라운드 시작/종료 기록
+    rounds: Arc<Mutex<RoundTracker>>,
+    /// 탐지기 생성 시각 (가동 시간)
+    created_at: Instant,
 }
 
-/// 네트워크 연결 정보
+/// 실행 중인 이벤트 소스 제어
+struct SourceControl {
+    /// 소스 태스크 종료 요청
+    stop: oneshot::Sender<()>,
+    /// 소스 핸들을 소유하는 태스크 (종료 시 수집 태스크 중단, 커널 후킹 분리)
+    supervisor: JoinHandle<()>,
+}
+
+/// 이벤트 소스 상태
 #[derive(Debug, Clone)]
+struct SourceStatus {
+    name: &'static str,
+    /// 커널 후킹 부착 결과 요약 (eBPF 소스만)
+    kernel_hooks: Option<String>,
+    /// 프로그램별 부착 결과 (eBPF 소스만)
+    hooks: Vec<HookStatus>,
+    running: bool,
+}
+
+/// 이벤트 기록기 (억제 규칙 적용 및 프로세스 정보 보강)
+#[derive(Clone)]
+struct EventRecorder {
+    events: Arc<RwLock<Vec<ReverseShellEvent>>>,
+    bus: EventBus,
+    suppressions: Arc<RwLock<SuppressionList>>,
+    process_cache: Arc<Mutex<ProcessCache>>,
+    risk: Arc<Mutex<RiskTracker>>,
+    incidents: Arc<Mutex<IncidentTracker>>,
+    response: Arc<Mutex<ResponseEngine>>,
+    scoreboard: Arc<Mutex<Scoreboard>>,
+    rounds: Arc<Mutex<RoundTracker>>,
+    /// 현재 호스트의 /proc 으로 프로세스 정보를 보강할지 (재생 시에는 false)
+    enrich_processes: bool,
+    /// 대응 정책을 실행할지 (재생 시에는 false)
+    respond: bool,
+}
+
+/// 이벤트 소스에서 들어온 이벤트를 분석하는 탐지 로직
+struct Analyzer {
+    recorder: EventRecorder,
+    connections: Arc<RwLock<HashMap<String, ConnectionInfo>>>,
+    listeners: Arc<RwLock<HashMap<String, ConnectionInfo>>>,
+    baseline: Arc<RwLock<Baseline>>,
+    /// 학습된 기준선이 없을 때 첫 스캔 시점에 열려 있던 리스닝 포트
+    initial_listen_ports: Option<HashSet<u16>>,
+    /// 이미 보고한 파일리스 프로세스 (pid, exe 링크 대상)
+    reported_fileless: HashSet<(u32, String)>,
+    /// 이미 보고한 표준 입출력이 소켓인 쉘
+    reported_stdio_sockets: HashSet<u32>,
+    /// 이미 보고한 의심스러운 프로세스 (pid, 시작 시각, 이름)
+    reported_suspicious: HashSet<(u32, u64, String)>,
+    /// 마지막으로 확인한 시작된 라운드 수 (바뀌면 설정에 따라 상태 초기화)
+    rounds_seen: usize,
+    /// 가상 시각의 기준 (이벤트 소스 시작 시점)
+    clock_base: Instant,
+    /// 처리 중인 이벤트의 가상 시각
+    now: Instant,
+    /// 입력 이벤트 기록
+    stream_recorder: Option<StreamRecorder>,
+}
+
+/// 네트워크 연결 정보
+#[derive(Debug, Clone, Serialize)]
 pub struct ConnectionInfo {
+    #[serde(serialize_with = "serialize_ip")]
     pub local_addr: u32,
+    /// IPv4 로 나타낼 수 없는 IPv6 로컬 주소 (리스닝 소켓)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub local_addr6: Option<Ipv6Addr>,
+    #[serde(serialize_with = "serialize_ip")]
     pub remote_addr: u32,
+    /// IPv4 로 나타낼 수 없는 IPv6 원격 주소
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remote_addr6: Option<Ipv6Addr>,
     pub local_port: u16,
     pub remote_port: u16,
     pub pid: u32,
     pub process_name: String,
+    #[serde(serialize_with = "serialize_instant")]
     pub first_seen: Instant,
+    #[serde(serialize_with = "serialize_instant")]
     pub last_seen: Instant,
     pub connection_count: u32,
     pub is_suspicious: bool,
 }
 
-/// 리버스 쉘 이벤트
-#[derive(Debug, Clone)]
+/// 리버스 쉘 이벤트 (JSON 으로 직렬화하면 시각은 유닉스 밀리초, 주소는 점 표기)
+#[derive(Debug, Clone, Serialize)]
 pub struct ReverseShellEvent {
+    #[serde(serialize_with = "serialize_instant")]
     pub timestamp: Instant,
     pub event_type: EventType,
     pub severity: Severity,
+    /// 이벤트를 생성한 탐지 규칙 ID
+    pub rule_id: String,
     pub details: String,
     pub connection_info: Option<ConnectionInfo>,
+    /// 관련 프로세스 식별 정보
+    pub process: Option<ProcessInfo>,
+    /// 이벤트가 속한 사고 (기록 시 부여)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub incident_id: Option<u64>,
+    /// 이벤트가 탐지된 라운드 (라운드 진행 중이면 기록 시 부여)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub round_id: Option<u64>,
+    /// MITRE ATT&CK 기법과 전술 (기록 시 규칙에 따라 부여)
+    pub techniques: Vec<Technique>,
 }
 
 /// 이벤트 타입
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum EventType {
     SuspiciousConnection,
     ReverseShellDetected,
     ProcessInjection,
     NetworkAnomaly,
     KernelHookTriggered,
+    BindShellDetected,
+    FilelessExecution,
+    /// 누적 위험도 점수가 경보 기준을 넘음
+    RiskEscalation,
+    /// 대응 엔진이 수행한 동작 (감사 기록)
+    ResponseAction,
 }
 
-/// 심각도 레벨
-#[derive(Debug, Clone)]
+/// 탐지 규칙 설명
+#[derive(Debug, Clone, Serialize)]
+pub struct DetectionRule {
+    pub rule_id: &'static str,
+    pub event_type: EventType,
+    pub description: &'static str,
+    pub techniques: &'static [Technique],
+}
+
+/// 분석기가 사용하는 탐지 규칙
+pub const DETECTION_RULES: &[DetectionRule] = &[
+    DetectionRule {
+        rule_id: "suspicious_connection",
+        event_type: EventType::KernelHookTriggered,
+        description: "New outbound connection to a reverse shell port or external address, or outside the baseline",
+        techniques: &[attack::APPLICATION_LAYER_PROTOCOL, attack::NON_STANDARD_PORT],
+    },
+    DetectionRule {
+        rule_id: "interpreter_listen",
+        event_type: EventType::BindShellDetected,
+        description: "Shell or interpreter listening on a socket",
+        techniques: &[attack::UNIX_SHELL, attack::NON_STANDARD_PORT],
+    },
+    DetectionRule {
+        rule_id: "new_listen_port",
+        event_type: EventType::BindShellDetected,
+        description: "Listening port not open at startup or outside the baseline",
+        techniques: &[attack::NON_STANDARD_PORT],
+    },
+    DetectionRule {
+        rule_id: "suspicious_process",
+        event_type: EventType::ProcessInjection,
+        description: "Process name matching a reverse shell tool pattern",
+        techniques: &[attack::UNIX_SHELL, attack::INGRESS_TOOL_TRANSFER],
+    },
+    DetectionRule {
+        rule_id: "memfd_executable",
+        event_type: EventType::FilelessExecution,
+        description: "Process running from a memfd",
+        techniques: &[attack::REFLECTIVE_CODE_LOADING],
+    },
+    DetectionRule {
+        rule_id: "deleted_executable",
+        event_type: EventType::FilelessExecution,
+        description: "Process running from a deleted file",
+        techniques: &[attack::FILE_DELETION],
+    },
+    DetectionRule {
+        rule_id: "kernel_suspicious_port",
+        event_type: EventType::SuspiciousConnection,
+        description: "Kernel hook: connection to a watched port",
+        techniques: &[attack::NON_STANDARD_PORT, attack::NON_APPLICATION_LAYER_PROTOCOL],
+    },
+    DetectionRule {
+        rule_id: "kernel_external_connection",
+        event_type: EventType::NetworkAnomaly,
+        description: "Kernel hook: connection from a Nova instance to an external address",
+        techniques: &[attack::APPLICATION_LAYER_PROTOCOL],
+    },
+    DetectionRule {
+        rule_id: "kernel_suspicious_exec",
+        event_type: EventType::ProcessInjection,
+        description: "Kernel hook: shell or interpreter executed",
+        techniques: &[attack::UNIX_SHELL],
+    },
+    DetectionRule {
+        rule_id: "kernel_listen",
+        event_type: EventType::BindShellDetected,
+        description: "Kernel hook: socket put into listening state",
+        techniques: &[attack::NON_STANDARD_PORT],
+    },
+    DetectionRule {
+        rule_id: "kernel_memfd_create",
+        event_type: EventType::FilelessExecution,
+        description: "Kernel hook: memfd_create called",
+        techniques: &[attack::REFLECTIVE_CODE_LOADING],
+    },
+    DetectionRule {
+        rule_id: "kernel_execveat",
+        event_type: EventType::FilelessExecution,
+        description: "Kernel hook: execveat on a file descriptor",
+        techniques: &[attack::REFLECTIVE_CODE_LOADING],
+    },
+    DetectionRule {
+        rule_id: "kernel_stdio_redirect",
+        event_type: EventType::ReverseShellDetected,
+        description: "Kernel hook: socket duplicated onto stdin, stdout or stderr",
+        techniques: &[attack::UNIX_SHELL, attack::NON_APPLICATION_LAYER_PROTOCOL],
+    },
+    DetectionRule {
+        rule_id: "stdio_socket",
+        event_type: EventType::ReverseShellDetected,
+        description: "Shell or interpreter whose stdin and stdout are sockets",
+        techniques: &[attack::UNIX_SHELL, attack::NON_APPLICATION_LAYER_PROTOCOL],
+    },
+    DetectionRule {
+        rule_id: "reverse_shell_chain",
+        event_type: EventType::ReverseShellDetected,
+        description: "Shell exec, outbound connect and stdio redirected to a socket in one process tree",
+        techniques: &[attack::UNIX_SHELL, attack::NON_APPLICATION_LAYER_PROTOCOL],
+    },
+    DetectionRule {
+        rule_id: "risk_escalation",
+        event_type: EventType::RiskEscalation,
+        description: "Decayed risk score of a process tree or the host crossed an alert threshold",
+        techniques: &[],
+    },
+    DetectionRule {
+        rule_id: "lsm_connect_denied",
+        event_type: EventType::ResponseAction,
+        description: "BPF-LSM denied a connection to a blocked port or CIDR from an enforced cgroup",
+        techniques: &[],
+    },
+    DetectionRule {
+        rule_id: "lsm_exec_denied",
+        event_type: EventType::ResponseAction,
+        description: "BPF-LSM denied execution of a blocked binary from an enforced cgroup",
+        techniques: &[],
+    },
+    DetectionRule {
+        rule_id: response::AUDIT_RULE_ID,
+        event_type: EventType::ResponseAction,
+        description: "Audit record of an active response action (kill, drop flow, block destination) or its dry run",
+        techniques: &[],
+    },
+    DetectionRule {
+        rule_id: "kernel_hook",
+        event_type: EventType::KernelHookTriggered,
+        description: "Kernel hook: other high severity event",
+        techniques: &[],
+    },
+];
+
+impl DetectionRule {
+    /// 규칙의 ATT&CK 기법 (탐지 규칙 목록에 없으면 이벤트 타입 기준)
+    pub fn techniques_for(rule_id: &str, event_type: &EventType) -> &'static [Technique] {
+        DETECTION_RULES.iter()
+            .find(|rule| rule.rule_id == rule_id)
+            .map_or(event_type.techniques(), |rule| rule.techniques)
+    }
+}
+
+/// 불러온 규칙 (탐지 규칙, 억제 규칙, 기준선)
+#[derive(Debug, Clone, Serialize)]
+pub struct LoadedRules {
+    pub detection: &'static [DetectionRule],
+    pub suppressions: Vec<Suppression>,
+    pub baseline_mode: &'static str,
+    pub baseline: BaselineData,
+    pub risk_weights: &'static [RuleWeight],
+    pub response: ResponseConfig,
+}
+
+/// 에이전트 상태
+#[derive(Debug, Clone, Serialize)]
+pub struct Health {
+    /// 이벤트 소스 이름 (시작 전이면 None)
+    pub source: Option<&'static str>,
+    pub running: bool,
+    pub uptime_secs: u64,
+    /// 커널 후킹 부착 결과 (eBPF 소스만)
+    pub hooks: Vec<HookStatus>,
+    pub kernel_events_lost: u64,
+    pub events: usize,
+    pub connections: usize,
+    pub listeners: usize,
+    pub tracked_processes: usize,
+    pub baseline_mode: &'static str,
+    pub suppressions: usize,
+    pub event_bus: BusMetrics,
+    pub host_risk_score: f64,
+    /// 진행 중인 라운드
+    pub round: Option<u64>,
+}
+
+/// 심각도 레벨 (Low < Medium < High < Critical)
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Severity {
     Low,
     Medium,
@@ -69,132 +393,384 @@ pub enum Severity {
     Critical,
 }
 
+/// Instant 를 유닉스 시각 (밀리초) 으로 변환 (재생 시 가상 시각은 현재 기준으로 환산)
+pub fn unix_millis(instant: Instant) -> u64 {
+    let now = Instant::now();
+    let wall = if instant <= now {
+        SystemTime::now() - (now - instant)
+    } else {
+        SystemTime::now() + (instant - now)
+    };
+    wall.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_millis() as u64)
+}
+
+pub fn serialize_instant<S: Serializer>(instant: &Instant, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+    serializer.serialize_u64(unix_millis(*instant))
+}
+
+fn serialize_ip<S: Serializer>(ip: &u32, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+    serializer.serialize_str(&ReverseShellDetector::u32_to_ip(*ip))
+}
+
+impl ConnectionInfo {
+    fn from_snapshot(snapshot: &SocketSnapshot, now: Instant) -> Self {
+        Self {
+            local_addr: snapshot.local_addr,
+            local_addr6: snapshot.local_addr6,
+            remote_addr: snapshot.remote_addr,
+            remote_addr6: snapshot.remote_addr6,
+            local_port: snapshot.local_port,
+            remote_port: snapshot.remote_port,
+            pid: snapshot.pid,
+            process_name: snapshot.process_name.clone(),
+            first_seen: now,
+            last_seen: now,
+            connection_count: 1,
+            is_suspicious: false,
+        }
+    }
+
+    /// 로컬 주소 (IPv6 주소가 있으면 IPv6)
+    pub fn local_ip(&self) -> String {
+        match self.local_addr6 {
+            Some(addr) => addr.to_string(),
+            None => ReverseShellDetector::u32_to_ip(self.local_addr),
+        }
+    }
+
+    /// 로컬 주소:포트 (IPv6 는 [주소]:포트)
+    pub fn local_endpoint(&self) -> String {
+        match self.local_addr6 {
+            Some(addr) => format!("[{}]:{}", addr, self.local_port),
+            None => format!("{}:{}", self.local_ip(), self.local_port),
+        }
+    }
+
+    /// 원격 주소 (원격 주소가 없는 리스닝 소켓이면 None)
+    pub fn remote(&self) -> Option<IpAddr> {
+        match self.remote_addr6 {
+            Some(addr) => Some(IpAddr::V6(addr)),
+            None => (self.remote_addr != 0).then(|| IpAddr::V4(Ipv4Addr::from(self.remote_addr))),
+        }
+    }
+
+    /// 원격 주소 (IPv6 주소가 있으면 IPv6)
+    pub fn remote_ip(&self) -> String {
+        match self.remote_addr6 {
+            Some(addr) => addr.to_string(),
+            None => ReverseShellDetector::u32_to_ip(self.remote_addr),
+        }
+    }
+
+    /// 원격 주소:포트 (IPv6 는 [주소]:포트)
+    pub fn remote_endpoint(&self) -> String {
+        match self.remote_addr6 {
+            Some(addr) => format!("[{}]:{}", addr, self.remote_port),
+            None => format!("{}:{}", self.remote_ip(), self.remote_port),
+        }
+    }
+}
+
+impl EventType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EventType::SuspiciousConnection => "suspicious_connection",
+            EventType::ReverseShellDetected => "reverse_shell_detected",
+            EventType::ProcessInjection => "process_injection",
+            EventType::NetworkAnomaly => "network_anomaly",
+            EventType::KernelHookTriggered => "kernel_hook_triggered",
+            EventType::BindShellDetected => "bind_shell_detected",
+            EventType::FilelessExecution => "fileless_execution",
+            EventType::RiskEscalation => "risk_escalation",
+            EventType::ResponseAction => "response_action",
+        }
+    }
+}
+
+impl EventType {
+    /// 규칙에 ATT&CK 기법이 지정되지 않았을 때 사용할 기법
+    pub fn techniques(&self) -> &'static [Technique] {
+        match self {
+            EventType::SuspiciousConnection => &[attack::NON_STANDARD_PORT],
+            EventType::ReverseShellDetected => &[attack::UNIX_SHELL, attack::NON_APPLICATION_LAYER_PROTOCOL],
+            EventType::ProcessInjection => &[attack::UNIX_SHELL],
+            EventType::NetworkAnomaly => &[attack::APPLICATION_LAYER_PROTOCOL],
+            EventType::BindShellDetected => &[attack::UNIX_SHELL, attack::NON_STANDARD_PORT],
+            EventType::FilelessExecution => &[attack::REFLECTIVE_CODE_LOADING],
+            EventType::KernelHookTriggered | EventType::RiskEscalation | EventType::ResponseAction => &[],
+        }
+    }
+
+    /// `as_str` 이름으로 변환
+    pub fn parse(name: &str) -> Option<Self> {
+        [
+            EventType::SuspiciousConnection,
+            EventType::ReverseShellDetected,
+            EventType::ProcessInjection,
+            EventType::NetworkAnomaly,
+            EventType::KernelHookTriggered,
+            EventType::BindShellDetected,
+            EventType::FilelessExecution,
+            EventType::RiskEscalation,
+            EventType::ResponseAction,
+        ].into_iter().find(|event_type| event_type.as_str() == name)
+    }
+}
+
+impl Severity {
+    /// `as_str` 이름으로 변환
+    pub fn parse(name: &str) -> Option<Self> {
+        [Severity::Low, Severity::Medium, Severity::High, Severity::Critical]
+            .into_iter()
+            .find(|severity| severity.as_str() == name)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Severity::Low => "low",
+            Severity::Medium => "medium",
+            Severity::High => "high",
+            Severity::Critical => "critical",
+        }
+    }
+
+    /// eBPF 이벤트의 심각도 값 변환
+    fn from_raw(severity: u8) -> Self {
+        match severity {
+            0 => Severity::Low,
+            1 => Severity::Medium,
+            2 => Severity::High,
+            _ => Severity::Critical,
+        }
+    }
+}
+
 impl ReverseShellDetector {
     /// 새로운 리버스 쉘 탐지기 생성
     pub fn new() -> Self {
+        Self::with_baseline(Baseline::disabled(), SuppressionList::default())
+    }
+
+    /// 기준선과 억제 규칙을 사용하는 리버스 쉘 탐지기 생성
+    pub fn with_baseline(baseline: Baseline, suppressions: SuppressionList) -> Self {
         Self {
-            bpf: RefCell::new(None),
-            detected_events: Arc::new(Mutex::new(Vec::new())),
-            connection_tracker: Arc::new(Mutex::new(HashMap::new())),
+            source: Mutex::new(None),
+            source_status: RwLock::new(None),
+            source_metrics: Arc::new(SourceMetrics::default()),
+            analysis_task: Mutex::new(None),
+            stream_recorder: Mutex::new(None),
+            detected_events: Arc::new(RwLock::new(Vec::new())),
+            event_bus: EventBus::default(),
+            sinks: Mutex::new(Vec::new()),
+            connection_tracker: Arc::new(RwLock::new(HashMap::new())),
+            listen_tracker: Arc::new(RwLock::new(HashMap::new())),
+            baseline: Arc::new(RwLock::new(baseline)),
+            suppressions: Arc::new(RwLock::new(suppressions)),
+            process_cache: Arc::new(Mutex::new(ProcessCache::new())),
+            risk: Arc::new(Mutex::new(RiskTracker::default())),
+            incidents: Arc::new(Mutex::new(IncidentTracker::new())),
+            response: Arc::new(Mutex::new(ResponseEngine::default())),
+            scoreboard: Arc::new(Mutex::new(Scoreboard::default())),
+            rounds: Arc::new(Mutex::new(RoundTracker::new(RoundConfig::default()))),
+            created_at: Instant::now(),
         }
     }
 
-    /// 플러그인 시작 (커널 후킹 포함)
+    /// 플러그인 시작 (eBPF 를 사용할 수 없으면 procfs 소스로 실행)
     pub async fn start(&self) -> Result<()> {
-        info!("Starting Kernel-based Reverse Shell Detector");
-        
-        // eBPF 프로그램 로드
-        self.load_ebpf_program().await?;
-        
-        // 여러 모니터링 태스크를 동시에 실행
-        let events_clone = Arc::clone(&self.detected_events);
-        let connections_clone = Arc::clone(&self.connection_tracker);
-        
-        // 커널 이벤트 모니터링
-        tokio::spawn(async move {
-            Self::monitor_kernel_events(events_clone, connections_clone).await;
-        });
+        match self.start_with_source(Box::new(self.ebpf_source().await)).await {
+            Ok(()) => Ok(()),
+            Err(e) => {
+                warn!("eBPF source unavailable ({}), falling back to procfs source", e);
+                self.start_with_source(Box::new(ProcfsSource::new())).await
+            }
+        }
+    }
 
-        // 네트워크 연결 분석
-        let events_clone = Arc::clone(&self.detected_events);
-        let connections_clone = Arc::clone(&self.connection_tracker);
-        
-        tokio::spawn(async move {
-            Self::analyze_network_connections(events_clone, connections_clone).await;
+    /// 억제 규칙은 커널 allow 맵, 대응 설정의 LSM 차단 목록은 LSM 맵에 넣는 eBPF 소스
+    pub async fn ebpf_source(&self) -> EbpfSource {
+        let config = self.response.lock().await.config().clone();
+        EbpfSource {
+            deny_entries: config.lsm.kernel_deny_entries(),
+            lsm_audit_only: config.dry_run,
+            ..EbpfSource::new(self.suppressions.read().await.kernel_allow_entries())
+        }
+    }
+
+    /// 위험도 점수 모델 설정 (start 전에 호출)
+    pub async fn set_risk_config(&self, config: RiskConfig) {
+        *self.risk.lock().await = RiskTracker::new(config);
+    }
+
+    /// 대응 정책 설정 (start 전에 호출)
+    pub async fn set_response_config(&self, config: ResponseConfig) {
+        *self.response.lock().await = ResponseEngine::new(config);
+    }
+
+    /// 팀별 감점 설정 (start 전에 호출)
+    pub async fn set_scoring_config(&self, config: ScoringConfig) {
+        *self.scoreboard.lock().await = Scoreboard::new(config);
+    }
+
+    /// 라운드 설정 (start 전에 호출)
+    pub async fn set_round_config(&self, config: RoundConfig) {
+        *self.rounds.lock().await = RoundTracker::new(config);
+    }
+
+    /// 분석기에 입력되는 이벤트를 파일로 기록 (start 전에 호출)
+    pub async fn record_to(&self, path: &Path) -> Result<()> {
+        *self.stream_recorder.lock().await = Some(StreamRecorder::create(path)?);
+        Ok(())
+    }
+
+    /// 지정한 이벤트 소스로 플러그인 시작
+    pub async fn start_with_source(&self, source: Box<dyn EventSource>) -> Result<()> {
+        info!("Starting Kernel-based Reverse Shell Detector ({} source)", source.name());
+
+        let (tx, rx) = mpsc::channel(SOURCE_CHANNEL_SIZE);
+        // 재생한 이벤트의 pid 와 주소는 현재 호스트의 것이 아니므로 보강과 대응은 실시간 소스에서만
+        let proc = source.proc_root();
+        let live = proc.is_some();
+        if let Some(proc) = proc {
+            *self.process_cache.lock().await = ProcessCache::with_root(proc);
+        }
+        let mut analyzer = self.analyzer(live, live);
+
+        // 이벤트 소스 시작 (eBPF 프로그램은 소스 태스크가 핸들을 소유하는 동안 유지됨)
+        let mut handle = source.start(EventSender::new(tx, Arc::clone(&self.source_metrics)))?;
+        if let Some(verdicts) = handle.verdicts.take() {
+            self.response.lock().await.enforcer_mut().set_verdicts(verdicts);
+        }
+        *self.source_status.write().await = Some(SourceStatus {
+            name: handle.name,
+            kernel_hooks: handle.bpf.as_ref().and(handle.hook_summary()),
+            hooks: if handle.bpf.is_some() { handle.hooks.clone() } else { Vec::new() },
+            running: true,
         });
 
-        // 프로세스 생성 모니터링
-        let events_clone = Arc::clone(&self.detected_events);
-        
-        tokio::spawn(async move {
-            Self::monitor_process_creation(events_clone).await;
+        let (stop, stopped) = oneshot::channel();
+        let supervisor = tokio::spawn(Self::supervise(handle, stopped));
+        *self.source.lock().await = Some(SourceControl { stop, supervisor });
+        analyzer.stream_recorder = self.stream_recorder.lock().await.take();
+
+        // 이벤트 분석
+        let task = tokio::spawn(async move {
+            analyzer.run(rx).await;
         });
+        *self.analysis_task.lock().await = Some(task);
 
         Ok(())
     }
 
-    /// eBPF 프로그램 로드
-    async fn load_ebpf_program(&self) -> Result<()> {
-        info!("Loading eBPF program for kernel-level monitoring");
-        
-        // ARM64에서는 시뮬레이션 모드로 실행
-        if cfg!(target_arch = "aarch64") {
-            // 시뮬레이션 모드
-            info!("eBPF loading skipped for ARM64 compatibility");
-            *self.bpf.borrow_mut() = None;
-            info!("eBPF program simulation completed");
-            Ok(())
-        } else {
-            // x86_64에서는 실제 eBPF 로딩
-            info!("Loading actual eBPF program on x86_64");
-            
-            // eBPF 바이트코드 로드
-            let mut bpf = BpfLoader::new()
-                .load(include_bytes_aligned!(
-                    "../../ebpf/reverse_shell_detector.o"
-                ))?;
-            
-            // 로그 설정
-            BpfLogger::init(&mut bpf)?;
-            
-            // XDP 프로그램 로드
-            let program: &mut Xdp = bpf.program_mut("reverse_shell_detector")?.try_into()?;
-            program.load()?;
-            program.attach("eth0", XdpFlags::default())?;
-            
-            // Tracepoint 프로그램들 로드
-            let execve_program: &mut TracePoint = bpf.program_mut("trace_execve")?.try_into()?;
-            execve_program.load()?;
-            execve_program.attach("syscalls", "sys_enter_execve")?;
-            
-            let socket_program: &mut TracePoint = bpf.program_mut("trace_socket")?.try_into()?;
-            socket_program.load()?;
-            socket_program.attach("syscalls", "sys_enter_socket")?;
-            
-            let connect_program: &mut TracePoint = bpf.program_mut("trace_connect")?.try_into()?;
-            connect_program.load()?;
-            connect_program.attach("syscalls", "sys_enter_connect")?;
-            
-            // 맵 초기화
-            self.initialize_ebpf_maps(&mut bpf).await?;
-            
-            // eBPF 인스턴스 저장
-            *self.bpf.borrow_mut() = Some(bpf);
-            
-            info!("eBPF program loaded and attached successfully");
-            Ok(())
-        }
-    }
-
-    /// eBPF 맵 초기화
-    async fn initialize_ebpf_maps(&self, bpf: &mut Bpf) -> Result<()> {
-        // ARM64에서는 시뮬레이션 모드로 실행
-        if cfg!(target_arch = "aarch64") {
-            // 의심스러운 포트 맵 초기화 (시뮬레이션)
-            info!("eBPF maps initialization simulated");
-            Ok(())
-        } else {
-            // x86_64에서는 실제 eBPF 맵 초기화
-            let mut suspicious_ports: AyaHashMap<_, u16, u8> = AyaHashMap::try_from(
-                bpf.map_mut("suspicious_ports")?
-            )?;
-            
-            let suspicious_port_list = vec![4444, 8080, 9001, 9002, 1337, 31337, 54321, 12345, 6667, 6668, 6669];
-            for port in suspicious_port_list {
-                suspicious_ports.insert(port, 1, 0)?;
+    /// 출력 싱크 추가 (이벤트 버스를 구독하여 이후 탐지되는 이벤트를 전달)
+    pub async fn add_sink(&self, sink: Box<dyn Sink>) {
+        let subscription = self.event_bus.subscribe(sink.name()).await;
+        self.sinks.lock().await.push(output::spawn(subscription, sink));
+    }
+
+    /// 이벤트 소스 핸들을 소유하다가 종료 요청 시 수집 태스크를 중단하고 커널 후킹 분리
+    async fn supervise(mut handle: SourceHandle, stopped: oneshot::Receiver<()>) {
+        // 탐지기가 drop 되어 요청 채널이 닫혀도 종료
+        let _ = stopped.await;
+
+        // 수집 태스크가 끝나면 채널이 닫히고, 분석기는 남은 이벤트를 모두 처리한 뒤 종료됨
+        for task in &handle.tasks {
+            task.abort();
+        }
+        for task in std::mem::take(&mut handle.tasks) {
+            let _ = task.await;
+        }
+
+        // Bpf 를 drop 하면 XDP, tracepoint, kprobe 링크가 모두 분리됨
+        if handle.bpf.take().is_some() {
+            info!("Kernel hooks detached");
+        }
+    }
+
+    /// 이벤트 소스가 끝날 때까지 대기 (재생 소스용)
+    pub async fn wait_until_drained(&self) {
+        let task = self.analysis_task.lock().await.take();
+        if let Some(task) = task {
+            if let Err(e) = task.await {
+                error!("Analysis task failed: {}", e);
             }
-            
-            info!("eBPF maps initialized successfully");
-            Ok(())
+        }
+    }
+
+    /// 플러그인 종료 (수집 중단, 남은 이벤트 처리, 커널 후킹 분리)
+    pub async fn shutdown(&self) {
+        info!("Shutting down reverse shell detector");
+
+        let control = self.source.lock().await.take();
+        if let Some(control) = control {
+            let _ = control.stop.send(());
+            if let Err(e) = control.supervisor.await {
+                error!("Source task failed: {}", e);
+            }
+        }
+        self.wait_until_drained().await;
+
+        // 버스를 닫으면 싱크는 남은 이벤트를 모두 쓰고 flush 한 뒤 종료됨
+        self.event_bus.close();
+        let sinks = std::mem::take(&mut *self.sinks.lock().await);
+        for sink in sinks {
+            if let Err(e) = sink.await {
+                error!("Output sink task failed: {}", e);
+            }
+        }
+
+        if let Some(status) = self.source_status.write().await.as_mut() {
+            status.running = false;
+        }
+
+        // 학습 중이던 기준선 저장
+        self.baseline.write().await.flush();
+
+        info!("Reverse shell detector stopped");
+    }
+
+    /// 이벤트 기록기
+    fn recorder(&self, enrich_processes: bool, respond: bool) -> EventRecorder {
+        EventRecorder {
+            events: Arc::clone(&self.detected_events),
+            bus: self.event_bus.clone(),
+            suppressions: Arc::clone(&self.suppressions),
+            process_cache: Arc::clone(&self.process_cache),
+            risk: Arc::clone(&self.risk),
+            incidents: Arc::clone(&self.incidents),
+            response: Arc::clone(&self.response),
+            scoreboard: Arc::clone(&self.scoreboard),
+            rounds: Arc::clone(&self.rounds),
+            enrich_processes,
+            respond,
+        }
+    }
+
+    /// 탐지 로직
+    fn analyzer(&self, enrich_processes: bool, respond: bool) -> Analyzer {
+        Analyzer {
+            recorder: self.recorder(enrich_processes, respond),
+            connections: Arc::clone(&self.connection_tracker),
+            listeners: Arc::clone(&self.listen_tracker),
+            baseline: Arc::clone(&self.baseline),
+            initial_listen_ports: None,
+            reported_fileless: HashSet::new(),
+            reported_stdio_sockets: HashSet::new(),
+            reported_suspicious: HashSet::new(),
+            rounds_seen: 0,
+            clock_base: Instant::now(),
+            now: Instant::now(),
+            stream_recorder: None,
         }
     }
 
     /// IP 주소를 u32로 변환
+    #[cfg(test)]
     fn ip_to_u32(ip: &str) -> u32 {
         let parts: Vec<u8> = ip.split('.')
             .map(|p| p.parse::<u8>().unwrap_or(0))
             .collect();
-        
+
         if parts.len() == 4 {
             ((parts[0] as u32) << 24) |
             ((parts[1] as u32) << 16) |
@@ -206,7 +782,7 @@ impl ReverseShellDetector {
     }
 
     /// u32를 IP 주소로 변환
-    fn u32_to_ip(ip_u32: u32) -> String {
+    pub fn u32_to_ip(ip_u32: u32) -> String {
         format!("{}.{}.{}.{}",
             (ip_u32 >> 24) & 0xFF,
             (ip_u32 >> 16) & 0xFF,
@@ -215,57 +791,6 @@ impl ReverseShellDetector {
         )
     }
 
-    /// 커널 이벤트 모니터링
-    async fn monitor_kernel_events(
-        events: Arc<Mutex<Vec<ReverseShellEvent>>>,
-        connections: Arc<Mutex<HashMap<String, ConnectionInfo>>>,
-    ) {
-        loop {
-            if let Err(e) = Self::process_kernel_events(&events, &connections).await {
-                error!("Error processing kernel events: {}", e);
-            }
-            
-            sleep(Duration::from_millis(100)).await; // 고빈도 모니터링
-        }
-    }
-
-    /// 커널 이벤트 처리
-    async fn process_kernel_events(
-        events: &Arc<Mutex<Vec<ReverseShellEvent>>>,
-        connections: &Arc<Mutex<HashMap<String, ConnectionInfo>>>,
-    ) -> Result<()> {
-        // 커널에서 전달된 이벤트 처리 (실제로는 eBPF 맵에서 읽어옴)
-        // 여기서는 시뮬레이션
-        
-        // 의심스러운 연결 패턴 감지
-        let connections_guard = connections.lock().unwrap();
-        
-        for (key, conn) in connections_guard.iter() {
-            if Self::is_suspicious_connection_pattern(conn) {
-                let event = ReverseShellEvent {
-                    timestamp: Instant::now(),
-                    event_type: EventType::KernelHookTriggered,
-                    severity: Severity::Critical,
-                    details: format!("Kernel hook detected suspicious connection: {} -> {}:{}", 
-                                   Self::u32_to_ip(conn.local_addr), 
-                                   Self::u32_to_ip(conn.remote_addr), 
-                                   conn.remote_port),
-                    connection_info: Some(conn.clone()),
-                };
-
-                let mut events_guard = events.lock().unwrap();
-                events_guard.push(event);
-                
-                error!("KERNEL HOOK: Suspicious connection detected: {} -> {}:{}", 
-                      Self::u32_to_ip(conn.local_addr), 
-                      Self::u32_to_ip(conn.remote_addr), 
-                      conn.remote_port);
-            }
-        }
-
-        Ok(())
-    }
-
     /// 의심스러운 연결 패턴 감지
     fn is_suspicious_connection_pattern(conn: &ConnectionInfo) -> bool {
         // 1. 일반적인 리버스 쉘 포트 확인
@@ -278,7 +803,11 @@ impl ReverseShellDetector {
         }
 
         // 2. 외부 IP로의 연결 확인
-        if !Self::is_private_ip_u32(conn.remote_addr) {
+        let is_private = match conn.remote_addr6 {
+            Some(addr) => Self::is_private_ipv6(&addr),
+            None => Self::is_private_ip_u32(conn.remote_addr),
+        };
+        if !is_private {
             return true;
         }
 
@@ -296,317 +825,1032 @@ impl ReverseShellDetector {
         if (ip_u32 & 0xFF000000) == 0x7F000000 {
             return true;
         }
-        
+
         // 10.0.0.0/8 (10.0.0.0 - 10.255.255.255)
         if (ip_u32 & 0xFF000000) == 0x0A000000 {
             return true;
         }
-        
+
         // 172.16.0.0/12 (172.16.0.0 - 172.31.255.255)
         if (ip_u32 & 0xFFF00000) == 0xAC100000 {
             return true;
         }
-        
+
         // 192.168.0.0/16 (192.168.0.0 - 192.168.255.255)
         if (ip_u32 & 0xFFFF0000) == 0xC0A80000 {
             return true;
         }
-        
+
         false
     }
 
-    /// 네트워크 연결 분석
-    async fn analyze_network_connections(
-        events: Arc<Mutex<Vec<ReverseShellEvent>>>,
-        connections: Arc<Mutex<HashMap<String, ConnectionInfo>>>,
-    ) {
-        loop {
-            if let Err(e) = Self::scan_network_connections(&events, &connections).await {
-                error!("Error scanning network connections: {}", e);
-            }
-            
-            sleep(Duration::from_secs(5)).await;
-        }
+    /// 프라이빗 IPv6 주소인지 확인 (루프백, fc00::/7 고유 로컬, fe80::/10 링크 로컬)
+    fn is_private_ipv6(addr: &Ipv6Addr) -> bool {
+        let first = addr.segments()[0];
+        addr.is_loopback() || (first & 0xFE00) == 0xFC00 || (first & 0xFFC0) == 0xFE80
     }
 
-    /// 네트워크 연결 스캔
-    async fn scan_network_connections(
-        events: &Arc<Mutex<Vec<ReverseShellEvent>>>,
-        connections: &Arc<Mutex<HashMap<String, ConnectionInfo>>>,
-    ) -> Result<()> {
-        // /proc/net/tcp 파일을 읽어서 활성 연결 확인
-        let tcp_content = std::fs::read_to_string("/proc/net/tcp")?;
-        let tcp6_content = std::fs::read_to_string("/proc/net/tcp6")?;
+    /// 바인드 쉘로 사용되는 쉘/인터프리터인지 확인
+    fn is_bind_shell_process(process_name: &str) -> bool {
+        let interpreters = vec![
+            "sh", "bash", "dash", "zsh", "ksh", "ash", "busybox",
+            "nc", "netcat", "ncat", "nc.traditional", "nc.openbsd", "socat",
+            "python", "python2", "python3", "perl", "ruby", "php", "lua", "node"
+        ];
 
-        let mut new_connections = Vec::new();
+        let base_name = process_name.rsplit('/').next().unwrap_or(process_name);
+        interpreters.contains(&base_name)
+    }
 
-        // TCP 연결 파싱
-        for line in tcp_content.lines().skip(1) {
-            if let Some(conn_info) = Self::parse_tcp_line(line)? {
-                new_connections.push(conn_info);
-            }
+    /// exe 링크 대상 분류 (memfd 는 Critical, 삭제된 파일은 High)
+    fn classify_executable(target: &str) -> Option<(Severity, &'static str)> {
+        if target.starts_with("/memfd:") {
+            Some((Severity::Critical, "memfd_executable"))
+        } else if target.ends_with(" (deleted)") {
+            Some((Severity::High, "deleted_executable"))
+        } else {
+            None
         }
+    }
 
-        // TCP6 연결 파싱
-        for line in tcp6_content.lines().skip(1) {
-            if let Some(conn_info) = Self::parse_tcp6_line(line)? {
-                new_connections.push(conn_info);
-            }
+    /// 의심스러운 프로세스인지 확인
+    fn is_suspicious_process(process_name: &str) -> bool {
+        let suspicious_patterns = vec![
+            "nc", "netcat", "bash -i", "sh -i", "python -c", "perl -e",
+            "ruby -rsocket", "php -r", "wget", "curl", "ftp", "telnet",
+            "ssh", "scp", "rsync", "nc.traditional", "ncat"
+        ];
+
+        suspicious_patterns.iter().any(|&pattern| process_name.contains(pattern))
+    }
+
+    /// 탐지된 이벤트 가져오기
+    pub async fn get_detected_events(&self) -> Vec<ReverseShellEvent> {
+        let events_guard = self.detected_events.read().await;
+        events_guard.clone()
+    }
+
+    /// 의심스러운 연결 목록 가져오기
+    pub async fn get_suspicious_connections(&self) -> Vec<ConnectionInfo> {
+        let connections_guard = self.connection_tracker.read().await;
+        connections_guard.values().cloned().collect()
+    }
+
+    /// 리스닝 소켓 목록 가져오기
+    pub async fn get_listening_sockets(&self) -> Vec<ConnectionInfo> {
+        let listeners_guard = self.listen_tracker.read().await;
+        listeners_guard.values().cloned().collect()
+    }
+
+    /// 이벤트 버스 구독 (이후 탐지되는 이벤트부터 전달됨)
+    pub async fn subscribe(&self, name: &str) -> Subscription {
+        self.event_bus.subscribe(name).await
+    }
+
+    /// 에이전트 상태
+    pub async fn health(&self) -> Health {
+        let status = self.source_status.read().await.clone();
+        let baseline_mode = self.baseline.read().await.mode().as_str();
+
+        Health {
+            source: status.as_ref().map(|status| status.name),
+            running: status.as_ref().is_some_and(|status| status.running),
+            uptime_secs: self.created_at.elapsed().as_secs(),
+            hooks: status.map(|status| status.hooks).unwrap_or_default(),
+            kernel_events_lost: self.source_metrics.kernel_events_lost.load(Ordering::Relaxed),
+            events: self.detected_events.read().await.len(),
+            connections: self.connection_tracker.read().await.len(),
+            listeners: self.listen_tracker.read().await.len(),
+            tracked_processes: self.process_cache.lock().await.len(),
+            baseline_mode,
+            suppressions: self.suppressions.read().await.rules().len(),
+            event_bus: self.event_bus.metrics().await,
+            host_risk_score: self.risk_summary(0).await.host.score,
+            round: self.rounds.lock().await.current_id(),
         }
+    }
 
-        // 연결 정보 업데이트
-        let mut connections_guard = connections.lock().unwrap();
-        for conn in new_connections {
-            let key = format!("{}:{}->{}:{}", 
-                Self::u32_to_ip(conn.local_addr), conn.local_port,
-                Self::u32_to_ip(conn.remote_addr), conn.remote_port);
-            
-            connections_guard.insert(key, conn);
+    /// 탐지 이벤트와 관련된 프로세스 트리 (조상 프로세스 포함)
+    pub async fn process_tree(&self) -> Vec<ProcessNode> {
+        let processes = self.process_cache.lock().await.with_ancestors();
+        process_info::build_tree(processes)
+    }
+
+    /// 불러온 탐지 규칙, 억제 규칙, 기준선
+    pub async fn rules(&self) -> LoadedRules {
+        let baseline = self.baseline.read().await;
+        LoadedRules {
+            detection: DETECTION_RULES,
+            suppressions: self.suppressions.read().await.rules().to_vec(),
+            baseline_mode: baseline.mode().as_str(),
+            baseline: baseline.data().clone(),
+            risk_weights: risk::RULE_WEIGHTS,
+            response: self.response.lock().await.config().clone(),
         }
+    }
 
-        Ok(())
+    /// 전술별 ATT&CK 탐지 현황 (규칙이 다루는 기법과 실제 탐지된 기법)
+    pub async fn attack_coverage(&self) -> Vec<TacticCoverage> {
+        let events = self.detected_events.read().await;
+        attack::coverage(
+            DETECTION_RULES.iter().map(|rule| rule.techniques),
+            events.iter().map(|event| event.techniques.as_slice()),
+        )
+    }
+
+    /// 관련 이벤트를 묶은 사고 (오래된 순)
+    pub async fn get_incidents(&self) -> Vec<Incident> {
+        self.incidents.lock().await.incidents()
+    }
+
+    /// 사고 조회
+    pub async fn get_incident(&self, id: u64) -> Option<Incident> {
+        self.incidents.lock().await.get(id)
+    }
+
+    /// 팀별 감점 순위 (CTFd 스코어보드 형식)
+    pub async fn scoreboard(&self) -> ScoreboardFeed {
+        self.scoreboard.lock().await.feed()
+    }
+
+    /// 팀별 감점 순위 (CSV)
+    pub async fn scoreboard_csv(&self) -> String {
+        self.scoreboard.lock().await.to_csv()
+    }
+
+    /// 팀별 감점 순위를 파일로 내보내기 (.csv 면 CSV, 아니면 JSON)
+    pub async fn export_scoreboard(&self, path: &Path) -> Result<()> {
+        self.scoreboard.lock().await.export(path)
     }
 
-    /// TCP 라인 파싱
-    fn parse_tcp_line(line: &str) -> Result<Option<ConnectionInfo>> {
-        let parts: Vec<&str> = line.split_whitespace().collect();
-        if parts.len() < 4 {
-            return Ok(None);
+    /// 라운드 시작 (진행 중인 라운드는 끝내고 리포트 출력) 후 설정에 따라 이전 라운드의 상태 초기화
+    pub async fn start_round(&self, id: Option<u64>) -> Result<Round> {
+        let (ended, round) = self.rounds.lock().await.start(id, Instant::now())?;
+        if let Some(ended) = ended {
+            self.finish_round(&ended).await;
         }
 
-        let local_addr_port = parts[1];
-        let remote_addr_port = parts[2];
-        let state = parts[3];
+        let resets = self.rounds.lock().await.config().reset.clone();
+        for reset in &resets {
+            match reset {
+                RoundReset::Dedup => {
+                    self.connection_tracker.write().await.clear();
+                    self.listen_tracker.write().await.clear();
+                    self.response.lock().await.forget_applied();
+                }
+                // 분석기 상태와 기준선은 분석기가 다음 이벤트를 처리할 때 (가상 시각으로) 초기화
+                RoundReset::Baseline => {}
+                RoundReset::Risk => self.risk.lock().await.reset(),
+                RoundReset::Incidents => self.incidents.lock().await.clear(),
+                RoundReset::Scoreboard => self.scoreboard.lock().await.reset(),
+            }
+        }
+
+        info!("Round {} started (reset: {})", round.id,
+            if resets.is_empty() { "none".to_string() } else { resets.iter().map(RoundReset::as_str).collect::<Vec<_>>().join(", ") });
+        Ok(round)
+    }
+
+    /// 진행 중인 라운드 종료 (리포트 출력)
+    pub async fn stop_round(&self) -> Option<Round> {
+        let round = self.rounds.lock().await.stop(Instant::now())?;
+        self.finish_round(&round).await;
+        Some(round)
+    }
 
-        // 연결이 ESTABLISHED 상태인지 확인
-        if state != "01" {
-            return Ok(None);
+    pub async fn apply_round_signal(&self, signal: RoundSignal) -> Result<()> {
+        match signal {
+            RoundSignal::Start(id) => self.start_round(id).await.map(|_| ()),
+            RoundSignal::Stop => {
+                self.stop_round().await;
+                Ok(())
+            }
         }
+    }
 
-        let (local_addr, local_port) = Self::parse_addr_port(local_addr_port)?;
-        let (remote_addr, remote_port) = Self::parse_addr_port(remote_addr_port)?;
+    /// 끝난 라운드의 리포트를 로그와 리포트 디렉터리에 출력
+    async fn finish_round(&self, round: &Round) {
+        let Some(report) = self.generate_round_report(round.id).await else { return };
+        info!("Round {} ended:\n{}", round.id, report);
 
-        // 프로세스 정보 가져오기
-        let (pid, process_name) = Self::get_process_info_for_connection(&Self::u32_to_ip(local_addr), local_port)?;
+        let report_dir = self.rounds.lock().await.config().report_dir.clone();
+        if let Some(dir) = report_dir {
+            let path = round::report_path(&dir, round.id);
+            if let Err(e) = std::fs::create_dir_all(&dir).and_then(|_| std::fs::write(&path, report)) {
+                warn!("Failed to write round report to {}: {}", path.display(), e);
+            }
+        }
+    }
 
-        Ok(Some(ConnectionInfo {
-            local_addr,
-            remote_addr,
-            local_port,
-            remote_port,
-            pid,
-            process_name,
-            first_seen: Instant::now(),
-            last_seen: Instant::now(),
-            connection_count: 1,
-            is_suspicious: false,
-        }))
+    /// 라운드별 요약 (시작 순)
+    pub async fn round_summaries(&self) -> Vec<RoundSummary> {
+        let rounds = self.rounds.lock().await.rounds().to_vec();
+        let events = self.detected_events.read().await;
+        rounds.into_iter().map(|round| RoundSummary::new(round, &events)).collect()
     }
 
-    /// TCP6 라인 파싱
-    fn parse_tcp6_line(line: &str) -> Result<Option<ConnectionInfo>> {
-        // TCP6는 IPv6 주소를 사용하므로 별도 처리
-        Self::parse_tcp_line(line)
+    pub async fn round_summary(&self, id: u64) -> Option<RoundSummary> {
+        let round = self.rounds.lock().await.get(id).cloned()?;
+        Some(RoundSummary::new(round, &self.detected_events.read().await))
     }
 
-    /// 주소:포트 파싱
-    fn parse_addr_port(addr_port: &str) -> Result<(u32, u16)> {
-        let parts: Vec<&str> = addr_port.split(':').collect();
-        if parts.len() != 2 {
-            return Err(anyhow::anyhow!("Invalid address:port format"));
+    /// 팀별 순위 한 줄 요약 (리포트용)
+    async fn scoreboard_line(&self) -> String {
+        let scoreboard = self.scoreboard.lock().await;
+        if !scoreboard.is_enabled() {
+            return "not configured".to_string();
         }
+        scoreboard.standings().iter()
+            .map(|team| format!("{}. {} {} ({} detections)", team.pos, team.name, team.score, team.detections))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
 
-        let addr_hex = parts[0];
-        let port_hex = parts[1];
+    /// 호스트 위험도 점수와 점수가 높은 프로세스 트리 상위 limit 개
+    pub async fn risk_summary(&self, limit: usize) -> RiskSummary {
+        self.risk.lock().await.summary(limit, Instant::now())
+    }
 
-        // 16진수 주소를 IP 주소로 변환
-        let addr = Self::hex_to_ip_u32(addr_hex)?;
-        let port = u16::from_str_radix(port_hex, 16)?;
+    /// 플러그인 상태 리포트
+    pub async fn generate_report(&self) -> String {
+        let events = self.get_detected_events().await;
+        let connections = self.get_suspicious_connections().await;
+        let listeners = self.get_listening_sockets().await;
+        let source = self.source_status.read().await.as_ref().map_or("not started".to_string(), |status| {
+            match (&status.kernel_hooks, status.running) {
+                (Some(hooks), true) => format!("{} (kernel hooks {})", status.name, hooks),
+                (_, true) => status.name.to_string(),
+                (_, false) => format!("{} (stopped)", status.name),
+            }
+        });
+        let baseline = {
+            let baseline_guard = self.baseline.read().await;
+            format!("{} ({} listen, {} outbound)", baseline_guard.mode().as_str(),
+                    baseline_guard.data().listen.len(),
+                    baseline_guard.data().outbound.len())
+        };
+        let suppression_count = self.suppressions.read().await.rules().len();
+        let tracked_processes = self.process_cache.lock().await.len();
+        let risk = {
+            let summary = self.risk_summary(REPORT_RISK_TREES).await;
+            let trees = summary.processes.iter()
+                .map(|tree| format!("pid {} {:.1}{}", tree.pid.unwrap_or_default(), tree.score,
+                    tree.level.as_ref().map(|level| format!(" ({})", level.as_str())).unwrap_or_default()))
+                .collect::<Vec<_>>();
+            format!("host {:.1}, process trees: {}", summary.host.score,
+                if trees.is_empty() { "none".to_string() } else { trees.join(", ") })
+        };
+        let response = {
+            let engine = self.response.lock().await;
+            let actions = engine.counts().iter()
+                .map(|((action, outcome), count)| format!("{} {} {}", action.as_str(), outcome.as_str(), count))
+                .collect::<Vec<_>>();
+            format!("{} ({} policies), actions: {}", engine.config().mode(), engine.config().policies.len(),
+                if actions.is_empty() { "none".to_string() } else { actions.join(", ") })
+        };
+        let scoreboard = self.scoreboard_line().await;
+        let incidents = self.get_incidents().await;
+        let coverage = self.attack_coverage().await.iter()
+            .map(|tactic| format!("  {} ({}): {} detections, observed [{}] of covered [{}]",
+                tactic.tactic.as_str(), tactic.tactic_id, tactic.detections,
+                tactic.observed.iter().copied().collect::<Vec<_>>().join(", "),
+                tactic.covered.iter().copied().collect::<Vec<_>>().join(", ")))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let bus_metrics = self.event_bus.metrics().await;
+        let subscribers = bus_metrics.subscribers.iter()
+            .map(|m| format!("{} ({} received, {} lagged, {} pending)", m.name, m.received, m.lagged, m.pending))
+            .collect::<Vec<_>>();
 
-        Ok((addr, port))
+        format!(
+            "Kernel-based Reverse Shell Detection Report\n\
+             ===========================================\n\
+             Event Source: {}\n\
+             Total Events Detected: {}\n\
+             Suspicious Connections: {}\n\
+             Listening Sockets: {}\n\
+             Baseline: {}\n\
+             Suppression Rules: {}\n\
+             Tracked Processes: {}\n\
+             Risk Score: {}\n\
+             Active Response: {}\n\
+             Scoreboard: {}\n\
+             Event Bus: {} published, subscribers: {}\n\
+             ATT&CK Coverage:\n\
+             {}\n\
+             Incidents: {}\n\
+             {}\n\
+             \n\
+             Recent Events:\n\
+             {}",
+            source,
+            events.len(),
+            connections.len(),
+            listeners.len(),
+            baseline,
+            suppression_count,
+            tracked_processes,
+            risk,
+            response,
+            scoreboard,
+            bus_metrics.published,
+            if subscribers.is_empty() { "none".to_string() } else { subscribers.join(", ") },
+            coverage,
+            incidents.len(),
+            incidents.iter().rev().take(REPORT_INCIDENTS).map(format_incident).collect::<Vec<_>>().join("\n"),
+            events.iter()
+                .take(10)
+                .map(format_event)
+                .collect::<Vec<_>>()
+                .join("\n")
+        )
     }
 
-    /// 16진수 주소를 IP 주소로 변환 (u32)
-    fn hex_to_ip_u32(hex: &str) -> Result<u32> {
-        if hex.len() != 8 {
-            return Err(anyhow::anyhow!("Invalid hex address length"));
+    /// 라운드 리포트 (라운드에 탐지된 이벤트와 그 사고만 포함)
+    pub async fn generate_round_report(&self, id: u64) -> Option<String> {
+        let summary = self.round_summary(id).await?;
+        let round = &summary.round;
+        let status = match round.ended_at {
+            Some(_) => format!("ended after {}s", round.duration(Instant::now()).as_secs()),
+            None => format!("running for {}s", round.duration(Instant::now()).as_secs()),
+        };
+        let counts = |counts: Vec<(String, usize)>| if counts.is_empty() {
+            "none".to_string()
+        } else {
+            counts.iter().map(|(key, count)| format!("{} {}", key, count)).collect::<Vec<_>>().join(", ")
+        };
+        let severities = counts([Severity::Critical, Severity::High, Severity::Medium, Severity::Low].iter()
+            .filter_map(|severity| summary.severities.get(severity.as_str()).map(|count| (severity.as_str().to_string(), *count)))
+            .collect());
+        let rules = counts(summary.rules.iter().map(|(rule_id, count)| (rule_id.clone(), *count)).collect());
+        let techniques = if summary.techniques.is_empty() {
+            "none".to_string()
+        } else {
+            summary.techniques.iter().copied().collect::<Vec<_>>().join(", ")
+        };
+        let incidents: Vec<Incident> = {
+            let tracker = self.incidents.lock().await;
+            let ids: HashSet<u64> = summary.incidents.iter().map(|&id| tracker.resolve(id)).collect();
+            tracker.incidents().into_iter().filter(|incident| ids.contains(&incident.id)).collect()
+        };
+        let events: Vec<ReverseShellEvent> = self.detected_events.read().await.iter()
+            .filter(|event| event.round_id == Some(id))
+            .cloned()
+            .collect();
+        let title = format!("Round {} Report", id);
+
+        Some(format!(
+            "{}\n\
+             {}\n\
+             Status: {}\n\
+             Total Events Detected: {}\n\
+             Severities: {}\n\
+             Rules: {}\n\
+             ATT&CK Techniques: {}\n\
+             Scoreboard: {}\n\
+             Incidents: {}\n\
+             {}\n\
+             \n\
+             Recent Events:\n\
+             {}",
+            title,
+            "=".repeat(title.len()),
+            status,
+            summary.events,
+            severities,
+            rules,
+            techniques,
+            self.scoreboard_line().await,
+            incidents.len(),
+            incidents.iter().rev().take(REPORT_INCIDENTS).map(format_incident).collect::<Vec<_>>().join("\n"),
+            events.iter().rev().take(10).map(format_event).collect::<Vec<_>>().join("\n"),
+        ))
+    }
+
+    /// 진행 중인 라운드가 있으면 라운드 리포트, 없으면 전체 리포트
+    pub async fn current_report(&self) -> String {
+        let current = self.rounds.lock().await.current_id();
+        match current {
+            Some(id) => self.generate_round_report(id).await.unwrap_or_default(),
+            None => self.generate_report().await,
         }
+    }
+}
 
-        let bytes: Vec<u8> = (0..8)
-            .step_by(2)
-            .map(|i| u8::from_str_radix(&hex[i..i+2], 16))
-            .collect::<Result<Vec<u8>, _>>()?;
+/// 리포트용 이벤트 한 줄 (탐지 후 경과 시간, 프로세스 정보)
+fn format_event(e: &ReverseShellEvent) -> String {
+    format!("[{}] {:?} - {}{}",
+        e.timestamp.elapsed().as_secs(),
+        e.severity,
+        e.details,
+        e.process.as_ref()
+            .map(|p| format!(" [uid={} exe={} cmd={}]",
+                p.uid,
+                p.exe.as_deref().unwrap_or("?"),
+                p.command_line()))
+            .unwrap_or_default())
+}
 
-        // 리틀 엔디안으로 저장된 주소를 변환
-        Ok(((bytes[3] as u32) << 24) |
-           ((bytes[2] as u32) << 16) |
-           ((bytes[1] as u32) << 8) |
-           (bytes[0] as u32))
+/// 리포트용 사고 요약과 타임라인 (첫 이벤트 기준 경과 시간)
+fn format_incident(incident: &Incident) -> String {
+    let mut lines = vec![format!(
+        "#{} {:?} - {} events over {}s, pids: {}, peers: {}, rules: {}",
+        incident.id,
+        incident.severity,
+        incident.event_count,
+        incident.duration().as_secs(),
+        incident.pids.iter().map(|pid| pid.to_string()).collect::<Vec<_>>().join(", "),
+        if incident.peers.is_empty() { "none".to_string() } else { incident.peers.iter().cloned().collect::<Vec<_>>().join(", ") },
+        incident.rules.iter().cloned().collect::<Vec<_>>().join(", "),
+    )];
+    for entry in incident.timeline.iter().take(REPORT_TIMELINE) {
+        lines.push(format!("  +{}s {:?} [{}] {}",
+            entry.timestamp.duration_since(incident.first_seen).as_secs(), entry.severity, entry.rule_id, entry.details));
+    }
+    if incident.event_count > REPORT_TIMELINE {
+        lines.push(format!("  ... {} more events", incident.event_count - REPORT_TIMELINE));
     }
+    lines.join("\n")
+}
 
-    /// 연결에 대한 프로세스 정보 가져오기
-    fn get_process_info_for_connection(local_addr: &str, local_port: u16) -> Result<(u32, String)> {
-        // /proc/net/tcp에서 해당 연결의 inode 찾기
-        let tcp_content = std::fs::read_to_string("/proc/net/tcp")?;
-        
-        for line in tcp_content.lines().skip(1) {
-            let parts: Vec<&str> = line.split_whitespace().collect();
-            if parts.len() < 10 {
-                continue;
+impl ReverseShellDetector {
+    /// Prometheus 텍스트 형식 지표
+    pub async fn render_metrics(&self) -> String {
+        let mut exposition = Exposition::new();
+
+        // 규칙, 종류, 심각도별 탐지 수
+        let mut counts: BTreeMap<(String, &'static str, &'static str), u64> = BTreeMap::new();
+        for event in self.detected_events.read().await.iter() {
+            *counts.entry((event.rule_id.clone(), event.event_type.as_str(), event.severity.as_str())).or_default() += 1;
+        }
+        exposition.family("telemagnifier_events_total", "counter", "Detection events by rule, type and severity");
+        for ((rule_id, event_type, severity), count) in &counts {
+            exposition.sample("telemagnifier_events_total",
+                &[("rule_id", rule_id), ("event_type", event_type), ("severity", severity)], count);
+        }
+
+        exposition.family("telemagnifier_tracked_connections", "gauge", "Connections in the connection tracker");
+        exposition.sample("telemagnifier_tracked_connections", &[], self.connection_tracker.read().await.len());
+        exposition.family("telemagnifier_listening_sockets", "gauge", "Listening sockets in the listen tracker");
+        exposition.sample("telemagnifier_listening_sockets", &[], self.listen_tracker.read().await.len());
+        exposition.family("telemagnifier_tracked_processes", "gauge", "Processes in the process identity cache");
+        exposition.sample("telemagnifier_tracked_processes", &[], self.process_cache.lock().await.len());
+
+        let risk = self.risk_summary(usize::MAX).await;
+        exposition.family("telemagnifier_host_risk_score", "gauge", "Decayed risk score of the host");
+        exposition.sample("telemagnifier_host_risk_score", &[], risk.host.score);
+        exposition.family("telemagnifier_elevated_process_trees", "gauge", "Process trees at or above the high risk threshold");
+        exposition.sample("telemagnifier_elevated_process_trees", &[],
+            risk.processes.iter().filter(|tree| tree.level.is_some()).count());
+
+        exposition.family("telemagnifier_response_actions_total", "counter", "Active response actions by action and outcome");
+        for ((action, outcome), count) in self.response.lock().await.counts() {
+            exposition.sample("telemagnifier_response_actions_total",
+                &[("action", action.as_str()), ("outcome", outcome.as_str())], count);
+        }
+
+        exposition.family("telemagnifier_team_penalty_points", "gauge", "CTF penalty points by team");
+        for team in self.scoreboard.lock().await.standings() {
+            exposition.sample("telemagnifier_team_penalty_points", &[("team", team.name.as_str())], team.penalty);
+        }
+
+        let source = &self.source_metrics;
+        exposition.family("telemagnifier_kernel_events_total", "counter", "Kernel events read from perf buffers");
+        exposition.sample("telemagnifier_kernel_events_total", &[], source.kernel_events_read.load(Ordering::Relaxed));
+        exposition.family("telemagnifier_kernel_events_lost_total", "counter", "Kernel events lost because perf buffers were full");
+        exposition.sample("telemagnifier_kernel_events_lost_total", &[], source.kernel_events_lost.load(Ordering::Relaxed));
+
+        exposition.family("telemagnifier_scan_duration_seconds", "summary", "Time spent scanning procfs");
+        for (scan, timer) in [
+            ("network_connections", &source.network_connection_scans),
+            ("listen_sockets", &source.listen_socket_scans),
+            ("process_creation", &source.process_creation_scans),
+        ] {
+            exposition.sample("telemagnifier_scan_duration_seconds_sum", &[("scan", scan)], timer.total_seconds());
+            exposition.sample("telemagnifier_scan_duration_seconds_count", &[("scan", scan)], timer.count());
+        }
+
+        if let Some(status) = self.source_status.read().await.as_ref() {
+            exposition.family("telemagnifier_source_info", "gauge", "Active event source");
+            exposition.sample("telemagnifier_source_info",
+                &[("source", status.name), ("running", if status.running { "1" } else { "0" })], 1);
+
+            if !status.hooks.is_empty() {
+                exposition.family("telemagnifier_bpf_program_attached", "gauge", "Whether each BPF program is attached");
+                for hook in &status.hooks {
+                    exposition.sample("telemagnifier_bpf_program_attached",
+                        &[("program", hook.program), ("kind", hook.kind.as_str()), ("target", &hook.target)],
+                        u8::from(hook.is_attached() && status.running));
+                }
             }
+        }
+
+        let bus = self.event_bus.metrics().await;
+        exposition.family("telemagnifier_event_bus_published_total", "counter", "Events published on the event bus");
+        exposition.sample("telemagnifier_event_bus_published_total", &[], bus.published);
+        exposition.family("telemagnifier_event_bus_lagged_total", "counter", "Events dropped by slow subscribers");
+        for subscriber in &bus.subscribers {
+            exposition.sample("telemagnifier_event_bus_lagged_total", &[("subscriber", &subscriber.name)], subscriber.lagged);
+        }
 
-            let (addr, port) = Self::parse_addr_port(parts[1])?;
-            if Self::u32_to_ip(addr) == local_addr && port == local_port {
-                let inode = parts[9];
-                return Self::get_process_by_inode(inode);
+        if let Some(rss) = metrics::resident_memory_bytes() {
+            exposition.family("telemagnifier_process_resident_memory_bytes", "gauge", "Resident memory of the agent");
+            exposition.sample("telemagnifier_process_resident_memory_bytes", &[], rss);
+        }
+
+        exposition.finish()
+    }
+}
+
+impl Analyzer {
+    /// 이벤트 소스가 닫힐 때까지 이벤트 처리
+    async fn run(&mut self, mut rx: Receiver<TimedEvent>) {
+        while let Some(event) = rx.recv().await {
+            self.handle(event).await;
+        }
+
+        if let Some(recorder) = &self.stream_recorder {
+            info!("Recorded {} source events", recorder.count());
+        }
+        debug!("Event source closed");
+    }
+
+    /// 이벤트 한 건 처리 (이벤트의 수집 시각을 분석 시각으로 사용)
+    async fn handle(&mut self, timed: TimedEvent) {
+        if let Some(recorder) = &mut self.stream_recorder {
+            if let Err(e) = recorder.write(&timed) {
+                warn!("Failed to record source event, recording stopped: {}", e);
+                self.stream_recorder = None;
             }
         }
 
-        Ok((0, "unknown".to_string()))
-    }
-
-    /// inode로 프로세스 정보 가져오기
-    fn get_process_by_inode(inode: &str) -> Result<(u32, String)> {
-        // /proc 디렉토리를 스캔하여 해당 inode를 사용하는 프로세스 찾기
-        for entry in std::fs::read_dir("/proc")? {
-            let entry = entry?;
-            let path = entry.path();
-            
-            if let Some(pid_str) = path.file_name().and_then(|s| s.to_str()) {
-                if let Ok(pid) = pid_str.parse::<u32>() {
-                    if let Ok(links) = std::fs::read_dir(path.join("fd")) {
-                        for link in links {
-                            if let Ok(link) = link {
-                                if let Ok(target) = std::fs::read_link(link.path()) {
-                                    if target.to_string_lossy().contains(inode) {
-                                        // 프로세스 이름 가져오기
-                                        if let Ok(cmdline) = std::fs::read_to_string(path.join("cmdline")) {
-                                            let process_name = cmdline.split('\0').next().unwrap_or("unknown");
-                                            return Ok((pid, process_name.to_string()));
-                                        }
-                                    }
-                                }
-                            }
-                        }
+        self.now = self.clock_base + Duration::from_millis(timed.offset_ms);
+        self.sync_round().await;
+
+        match timed.event {
+            SourceEvent::ConnectionScan(connections) => self.handle_connection_scan(connections).await,
+            SourceEvent::ListenScan(listeners) => self.handle_listen_scan(listeners).await,
+            SourceEvent::ProcessScan(processes) => self.handle_process_scan(processes).await,
+            SourceEvent::Kernel(event) => self.handle_kernel_event(event).await,
+        }
+    }
+
+    /// 새 라운드가 시작되었으면 설정에 따라 분석기 상태 초기화
+    async fn sync_round(&mut self) {
+        let rounds = self.recorder.rounds.lock().await;
+        if rounds.started() == self.rounds_seen {
+            return;
+        }
+        self.rounds_seen = rounds.started();
+
+        if rounds.resets(RoundReset::Dedup) {
+            self.reported_fileless.clear();
+            self.reported_stdio_sockets.clear();
+            self.reported_suspicious.clear();
+        }
+        if rounds.resets(RoundReset::Baseline) {
+            self.initial_listen_ports = None;
+            self.baseline.write().await.relearn(self.now);
+        }
+    }
+
+    /// 연결 스캔 결과 처리 (새로 보인 연결만 분석)
+    async fn handle_connection_scan(&mut self, connections: Vec<SocketSnapshot>) {
+        let mut new_connections = Vec::new();
+        {
+            let mut connections_guard = self.connections.write().await;
+            for snapshot in &connections {
+                let conn = ConnectionInfo::from_snapshot(snapshot, self.now);
+                let key = format!("{}->{}", conn.local_endpoint(), conn.remote_endpoint());
+
+                match connections_guard.get_mut(&key) {
+                    Some(existing) => {
+                        existing.last_seen = self.now;
+                        existing.connection_count += 1;
+                    }
+                    None => {
+                        new_connections.push(conn.clone());
+                        connections_guard.insert(key, conn);
                     }
                 }
             }
         }
 
-        Ok((0, "unknown".to_string()))
+        let mut baseline_guard = self.baseline.write().await;
+        baseline_guard.tick(self.now);
+
+        // 의심스러운 연결 패턴 감지
+        for conn in new_connections {
+            let verdict = baseline_guard.observe_outbound(
+                &conn.process_name,
+                &conn.remote_ip(),
+                conn.remote_port,
+                self.now,
+            );
+
+            // 기준선이 있으면 기준선에서 벗어난 연결만 탐지
+            let is_suspicious = match verdict {
+                BaselineVerdict::NotConfigured => ReverseShellDetector::is_suspicious_connection_pattern(&conn),
+                BaselineVerdict::Learning | BaselineVerdict::Known => false,
+                BaselineVerdict::Deviation => true,
+            };
+
+            if is_suspicious {
+                let event = ReverseShellEvent {
+                    timestamp: self.now,
+                    event_type: EventType::KernelHookTriggered,
+                    severity: Severity::Critical,
+                    rule_id: "suspicious_connection".to_string(),
+                    details: format!("Kernel hook detected suspicious connection: {} -> {}",
+                                   conn.local_ip(),
+                                   conn.remote_endpoint()),
+                    connection_info: Some(ConnectionInfo { is_suspicious: true, ..conn.clone() }),
+                    process: None,
+                    incident_id: None,
+                    round_id: None,
+                    techniques: Vec::new(),
+                };
+
+                self.recorder.record(event, conn.pid).await;
+            }
+        }
     }
 
-    /// 프로세스 생성 모니터링
-    async fn monitor_process_creation(
-        events: Arc<Mutex<Vec<ReverseShellEvent>>>,
-    ) {
-        loop {
-            if let Err(e) = Self::scan_process_creation(&events).await {
-                error!("Error scanning process creation: {}", e);
+    /// 리스닝 소켓 스캔 결과 처리
+    async fn handle_listen_scan(&mut self, listeners: Vec<SocketSnapshot>) {
+        // 주소 체계까지 포함한 키 (듀얼 스택의 0.0.0.0:22 와 [::]:22 는 서로 다른 소켓)
+        let current: HashMap<String, ConnectionInfo> = listeners.iter()
+            .map(|snapshot| {
+                let conn = ConnectionInfo::from_snapshot(snapshot, self.now);
+                (conn.local_endpoint(), conn)
+            })
+            .collect();
+
+        // 학습된 기준선이 없으면 첫 스캔 시점에 열려 있던 포트를 기준선으로 사용
+        let is_first_scan = self.initial_listen_ports.is_none();
+        let initial_ports = self.initial_listen_ports.get_or_insert_with(|| {
+            listeners.iter().map(|snapshot| snapshot.local_port).collect()
+        });
+
+        let mut listeners_guard = self.listeners.write().await;
+        listeners_guard.retain(|key, _| current.contains_key(key));
+
+        for (key, conn) in current {
+            if listeners_guard.contains_key(&key) {
+                continue;
+            }
+
+            let conn = ConnectionInfo { connection_count: 0, ..conn };
+            let process_name = &conn.process_name;
+            let pid = conn.pid;
+
+            let verdict = self.baseline.write().await.observe_listen(process_name, conn.local_port, self.now);
+            let is_new_port = match verdict {
+                BaselineVerdict::NotConfigured => !is_first_scan && !initial_ports.contains(&conn.local_port),
+                BaselineVerdict::Learning | BaselineVerdict::Known => false,
+                BaselineVerdict::Deviation => true,
+            };
+
+            // 학습된 기준선에 포함된 리스닝 소켓은 인터프리터라도 허용
+            let detection = if matches!(verdict, BaselineVerdict::Learning | BaselineVerdict::Known) {
+                None
+            } else if ReverseShellDetector::is_bind_shell_process(process_name) {
+                Some((Severity::Critical, "interpreter_listen"))
+            } else if is_new_port {
+                Some((Severity::Medium, "new_listen_port"))
+            } else {
+                None
+            };
+
+            if let Some((severity, rule_id)) = detection {
+                let event = ReverseShellEvent {
+                    timestamp: self.now,
+                    event_type: EventType::BindShellDetected,
+                    severity,
+                    rule_id: rule_id.to_string(),
+                    details: format!("Possible bind shell: {} (pid {}) listening on {}",
+                                   process_name, pid, key),
+                    connection_info: Some(ConnectionInfo { is_suspicious: true, ..conn.clone() }),
+                    process: None,
+                    incident_id: None,
+                    round_id: None,
+                    techniques: Vec::new(),
+                };
+
+                self.recorder.record(event, pid).await;
             }
-            
-            sleep(Duration::from_secs(10)).await;
+
+            listeners_guard.insert(key, conn);
         }
     }
 
-    /// 프로세스 생성 스캔
-    async fn scan_process_creation(
-        events: &Arc<Mutex<Vec<ReverseShellEvent>>>,
-    ) -> Result<()> {
-        // 현재 실행 중인 프로세스 목록 가져오기
-        let current_processes = Self::get_current_processes()?;
-        
-        // 의심스러운 프로세스 패턴 확인
-        for process in current_processes {
-            if Self::is_suspicious_process(&process) {
+    /// 프로세스 스캔 결과 처리 (의심스러운 프로세스, 파일리스 실행)
+    async fn handle_process_scan(&mut self, processes: Vec<ProcessSnapshot>) {
+        let mut current_fileless = HashSet::new();
+        let mut current_stdio_sockets = HashSet::new();
+        let mut current_suspicious = HashSet::new();
+
+        for process in &processes {
+            // 의심스러운 프로세스 패턴 확인 (프로세스가 살아 있는 동안 한 번만 보고)
+            let key = (process.pid, process.start_time, process.name.clone());
+            if !process.name.is_empty()
+                && ReverseShellDetector::is_suspicious_process(&process.name)
+                && current_suspicious.insert(key.clone())
+                && self.reported_suspicious.insert(key)
+            {
                 let event = ReverseShellEvent {
-                    timestamp: Instant::now(),
+                    timestamp: self.now,
                     event_type: EventType::ProcessInjection,
                     severity: Severity::High,
-                    details: format!("Suspicious process detected: {}", process),
+                    rule_id: "suspicious_process".to_string(),
+                    details: format!("Suspicious process detected: {} (pid {})", process.name, process.pid),
                     connection_info: None,
+                    process: None,
+                    incident_id: None,
+                    round_id: None,
+                    techniques: Vec::new(),
                 };
 
-                let mut events_guard = events.lock().unwrap();
-                events_guard.push(event);
-                
-                warn!("Suspicious process detected: {}", process);
+                self.recorder.record(event, process.pid).await;
             }
+
+            // dup2 로 소켓을 표준 입출력에 연결한 쉘 (프로세스가 살아 있는 동안 한 번만 보고)
+            if process.stdio_socket && ReverseShellDetector::is_bind_shell_process(&process.name) {
+                current_stdio_sockets.insert(process.pid);
+                if self.reported_stdio_sockets.insert(process.pid) {
+                    let event = ReverseShellEvent {
+                        timestamp: self.now,
+                        event_type: EventType::ReverseShellDetected,
+                        severity: Severity::High,
+                        rule_id: "stdio_socket".to_string(),
+                        details: format!("Shell with socket stdio: {} (pid {})", process.name, process.pid),
+                        connection_info: None,
+                        process: None,
+                        incident_id: None,
+                        round_id: None,
+                        techniques: Vec::new(),
+                    };
+
+                    self.recorder.record(event, process.pid).await;
+                }
+            }
+
+            // /proc/<pid>/exe 가 memfd 또는 삭제된 파일을 가리키는지 확인
+            let Some(target) = &process.exe else { continue };
+            let Some((severity, rule_id)) = ReverseShellDetector::classify_executable(target) else { continue };
+
+            let key = (process.pid, target.clone());
+            current_fileless.insert(key.clone());
+            if self.reported_fileless.contains(&key) {
+                continue;
+            }
+
+            let event = ReverseShellEvent {
+                timestamp: self.now,
+                event_type: EventType::FilelessExecution,
+                severity,
+                rule_id: rule_id.to_string(),
+                details: format!("Fileless execution: pid {} running from {}", process.pid, target),
+                connection_info: None,
+                process: None,
+                incident_id: None,
+                round_id: None,
+                techniques: Vec::new(),
+            };
+
+            self.recorder.record(event, process.pid).await;
+            self.reported_fileless.insert(key);
         }
 
-        Ok(())
+        // 종료된 프로세스는 다시 보고할 수 있도록 제거
+        self.reported_fileless.retain(|key| current_fileless.contains(key));
+        self.reported_stdio_sockets.retain(|pid| current_stdio_sockets.contains(pid));
+        self.reported_suspicious.retain(|key| current_suspicious.contains(key));
+
+        // 종료된 프로세스 정보와 점수가 사라진 프로세스 트리 정리
+        if self.recorder.enrich_processes {
+            self.recorder.process_cache.lock().await.prune();
+        }
+        self.recorder.risk.lock().await.prune(self.now);
     }
 
-    /// 현재 프로세스 목록 가져오기
-    fn get_current_processes() -> Result<Vec<String>> {
-        let mut processes = Vec::new();
-        
-        for entry in std::fs::read_dir("/proc")? {
-            let entry = entry?;
-            let path = entry.path();
-            
-            if let Some(pid_str) = path.file_name().and_then(|s| s.to_str()) {
-                if let Ok(_pid) = pid_str.parse::<u32>() {
-                    if let Ok(cmdline) = std::fs::read_to_string(path.join("cmdline")) {
-                        let process_name = cmdline.split('\0').next().unwrap_or("unknown");
-                        if !process_name.is_empty() && process_name != "unknown" {
-                            processes.push(process_name.to_string());
-                        }
-                    }
-                }
+    /// 커널 이벤트 처리 (High 이상과 파일리스 실행 단계만 이벤트로 기록)
+    async fn handle_kernel_event(&mut self, kernel_event: KernelEvent) {
+        let (event_type, rule_id) = match kernel_event.kind {
+            KernelEventKind::SuspiciousPort => (EventType::SuspiciousConnection, "kernel_suspicious_port"),
+            KernelEventKind::ExternalConnection => (EventType::NetworkAnomaly, "kernel_external_connection"),
+            KernelEventKind::SuspiciousExec => (EventType::ProcessInjection, "kernel_suspicious_exec"),
+            KernelEventKind::Listen => (EventType::BindShellDetected, "kernel_listen"),
+            KernelEventKind::MemfdCreate => (EventType::FilelessExecution, "kernel_memfd_create"),
+            KernelEventKind::Execveat => (EventType::FilelessExecution, "kernel_execveat"),
+            KernelEventKind::StdioRedirect => (EventType::ReverseShellDetected, "kernel_stdio_redirect"),
+            KernelEventKind::ConnectDenied => (EventType::ResponseAction, "lsm_connect_denied"),
+            KernelEventKind::ExecDenied => (EventType::ResponseAction, "lsm_exec_denied"),
+            KernelEventKind::Socket | KernelEventKind::Connect | KernelEventKind::Unknown(_) => {
+                (EventType::KernelHookTriggered, "kernel_hook")
+            }
+        };
+
+        // memfd_create 와 평범한 execveat 는 커널이 Medium 으로 보내지만 파일리스 실행 단계이므로 기록
+        let fileless = matches!(kernel_event.kind, KernelEventKind::MemfdCreate | KernelEventKind::Execveat);
+        if kernel_event.severity < 2 && !fileless {
+            debug!("Kernel event {:?} from pid {}", kernel_event.kind, kernel_event.pid);
+            // connect 호출은 이벤트로 기록하지 않고 리버스 쉘 연쇄 판단에만 사용
+            if kernel_event.kind == KernelEventKind::Connect {
+                self.recorder.observe("kernel_connect", &Severity::from_raw(kernel_event.severity), kernel_event.pid, self.now).await;
             }
+            return;
         }
 
-        Ok(processes)
+        let connection_info = (kernel_event.remote_addr != 0).then(|| ConnectionInfo {
+            is_suspicious: true,
+            ..ConnectionInfo::from_snapshot(&SocketSnapshot {
+                local_addr: kernel_event.local_addr,
+                local_addr6: None,
+                local_port: kernel_event.local_port,
+                remote_addr: kernel_event.remote_addr,
+                remote_addr6: None,
+                remote_port: kernel_event.remote_port,
+                pid: kernel_event.pid,
+                process_name: "unknown".to_string(),
+            }, self.now)
+        });
+
+        let details = match kernel_event.kind {
+            KernelEventKind::ConnectDenied | KernelEventKind::ExecDenied => {
+                // dry-run 이면 LSM 프로그램은 거부하지 않고 이벤트만 보냄
+                let verdict = if self.recorder.response.lock().await.config().dry_run { "would deny (dry-run)" } else { "denied" };
+                let target = match kernel_event.kind {
+                    KernelEventKind::ConnectDenied => format!("connect to {}:{}",
+                        ReverseShellDetector::u32_to_ip(kernel_event.remote_addr), kernel_event.remote_port),
+                    _ => "exec of blocked binary".to_string(),
+                };
+                format!("LSM {} {} by pid {}", verdict, target, kernel_event.pid)
+            }
+            _ => format!("Kernel hook {:?} triggered by pid {}", kernel_event.kind, kernel_event.pid),
+        };
+
+        let event = ReverseShellEvent {
+            timestamp: self.now,
+            event_type,
+            severity: Severity::from_raw(kernel_event.severity),
+            rule_id: rule_id.to_string(),
+            details,
+            connection_info,
+            process: None,
+            incident_id: None,
+            round_id: None,
+            techniques: Vec::new(),
+        };
+
+        self.recorder.record(event, kernel_event.pid).await;
     }
+}
 
-    /// 의심스러운 프로세스인지 확인
-    fn is_suspicious_process(process_name: &str) -> bool {
-        let suspicious_patterns = vec![
-            "nc", "netcat", "bash -i", "sh -i", "python -c", "perl -e",
-            "ruby -rsocket", "php -r", "wget", "curl", "ftp", "telnet",
-            "ssh", "scp", "rsync", "nc.traditional", "ncat"
-        ];
+impl EventRecorder {
+    /// 프로세스 정보를 보강하여 이벤트 기록 후 버스에 발행하고 위험도 점수에 반영 (억제 규칙에 해당하면 무시)
+    async fn record(&self, mut event: ReverseShellEvent, pid: u32) {
+        if event.process.is_none() && self.enrich_processes {
+            event.process = self.process_info(pid).await;
+        }
 
-        suspicious_patterns.iter().any(|&pattern| process_name.contains(pattern))
+        let ancestors = self.ancestors(pid, event.process.as_ref()).await;
+        let Some(event) = self.publish(event, pid, &ancestors).await else { return };
+        self.score(&event.rule_id, &event.severity, pid, &ancestors, event.timestamp).await;
     }
 
-    /// 탐지된 이벤트 가져오기
-    pub fn get_detected_events(&self) -> Vec<ReverseShellEvent> {
-        let events_guard = self.detected_events.lock().unwrap();
-        events_guard.clone()
+    /// 프로세스 정보 (실행 파일 해시는 캐시 잠금 밖의 블로킹 스레드에서 계산)
+    async fn process_info(&self, pid: u32) -> Option<ProcessInfo> {
+        let (mut info, proc) = {
+            let mut cache = self.process_cache.lock().await;
+            (cache.get(pid)?, cache.proc().clone())
+        };
+        let Some(exe) = self.process_cache.lock().await.unhashed(&info) else { return Some(info) };
+
+        let hash = tokio::task::spawn_blocking(move || process_info::hash_executable(&proc, pid, exe))
+            .await
+            .unwrap_or_default();
+        info.exe_sha256 = self.process_cache.lock().await.insert_hash(exe, hash);
+        Some(info)
     }
 
-    /// 의심스러운 연결 목록 가져오기
-    pub fn get_suspicious_connections(&self) -> Vec<ConnectionInfo> {
-        let connections_guard = self.connection_tracker.lock().unwrap();
-        connections_guard.values().cloned().collect()
+    /// 이벤트로 기록하지 않는 신호 (커널 connect 등) 를 위험도 점수에 반영
+    async fn observe(&self, rule_id: &str, severity: &Severity, pid: u32, now: Instant) {
+        let ancestors = self.ancestors(pid, None).await;
+        self.score(rule_id, severity, pid, &ancestors, now).await;
     }
 
-    /// 플러그인 상태 리포트
-    pub fn generate_report(&self) -> String {
-        let events = self.get_detected_events();
-        let connections = self.get_suspicious_connections();
-        
-        format!(
-            "Kernel-based Reverse Shell Detection Report\n\
-             ===========================================\n\
-             Total Events Detected: {}\n\
-             Suspicious Connections: {}\n\
-             \n\
-             Recent Events:\n\
-             {}",
-            events.len(),
-            connections.len(),
-            events.iter()
-                .take(10)
-                .map(|e| format!("[{}] {:?} - {}", 
-                    e.timestamp.elapsed().as_secs(), 
-                    e.severity, 
-                    e.details))
-                .collect::<Vec<_>>()
-                .join("\n")
-        )
+    /// 조상 pid (부모부터, 현재 호스트의 /proc 을 읽을 수 없으면 이벤트의 부모 pid 만)
+    async fn ancestors(&self, pid: u32, process: Option<&ProcessInfo>) -> Vec<u32> {
+        if self.enrich_processes {
+            self.process_cache.lock().await.ancestors(pid)
+        } else {
+            process.map(|process| process.ppid).filter(|&ppid| ppid > 1).into_iter().collect()
+        }
+    }
+
+    /// 위험도 점수에 반영하고 새로 발생한 경보를 원인 이벤트와 같은 사고로 기록
+    async fn score(&self, rule_id: &str, severity: &Severity, pid: u32, ancestors: &[u32], now: Instant) {
+        let escalations = self.risk.lock().await.observe(rule_id, severity, pid, ancestors, now);
+        for escalation in escalations {
+            let event = self.escalation_event(escalation, now).await;
+            self.publish(event, pid, ancestors).await;
+        }
+    }
+
+    /// 경보를 탐지 이벤트로 변환 (프로세스 트리 경보는 트리 루트 프로세스 정보 포함)
+    async fn escalation_event(&self, escalation: Escalation, now: Instant) -> ReverseShellEvent {
+        let (subject, pid) = match escalation.subject {
+            Subject::Host => ("host".to_string(), 0),
+            Subject::ProcessTree(pid) => (format!("process tree of pid {}", pid), pid),
+        };
+        let (event_type, rule_id, details) = match escalation.kind {
+            EscalationKind::ReverseShellChain => (
+                EventType::ReverseShellDetected,
+                "reverse_shell_chain",
+                format!("Reverse shell sequence in {}: shell exec, outbound connect and stdio redirected to a socket (score {:.1}, rules: {})",
+                    subject, escalation.score, escalation.rules.join(", ")),
+            ),
+            EscalationKind::Threshold => (
+                EventType::RiskEscalation,
+                "risk_escalation",
+                format!("Risk score of {} reached {:.1} (rules: {})", subject, escalation.score, escalation.rules.join(", ")),
+            ),
+        };
+        let process = if self.enrich_processes {
+            self.process_info(pid).await
+        } else {
+            None
+        };
+        // 경보는 점수에 기여한 규칙의 기법을 모두 가짐
+        let mut techniques: Vec<Technique> = Vec::new();
+        for rule_id in &escalation.rules {
+            for technique in DetectionRule::techniques_for(rule_id, &EventType::RiskEscalation) {
+                if !techniques.contains(technique) {
+                    techniques.push(*technique);
+                }
+            }
+        }
+
+        ReverseShellEvent {
+            timestamp: now,
+            event_type,
+            severity: escalation.severity,
+            rule_id: rule_id.to_string(),
+            details,
+            connection_info: None,
+            process,
+            incident_id: None,
+            round_id: None,
+            techniques,
+        }
+    }
+
+    /// 억제 규칙에 해당하지 않으면 기록 후 버스에 발행하고 대응 정책 적용
+    async fn publish(&self, mut event: ReverseShellEvent, pid: u32, ancestors: &[u32]) -> Option<ReverseShellEvent> {
+        if let Some(suppression) = self.suppressions.read().await.find_match(&event) {
+            debug!("Event suppressed by '{}': {}", suppression.id, event.details);
+            return None;
+        }
+
+        if event.techniques.is_empty() {
+            event.techniques = DetectionRule::techniques_for(&event.rule_id, &event.event_type).to_vec();
+        }
+        let event = self.emit(event, pid, ancestors).await;
+        if self.respond {
+            self.respond(&event, pid, ancestors).await;
+        }
+        Some(event)
+    }
+
+    /// 사고에 추가하고 기록 후 버스에 발행
+    async fn emit(&self, mut event: ReverseShellEvent, pid: u32, ancestors: &[u32]) -> ReverseShellEvent {
+        event.incident_id = Some(self.incidents.lock().await.add(&event, pid, ancestors));
+        event.round_id = self.rounds.lock().await.current_id();
+        self.scoreboard.lock().await.record(&event);
+        self.events.write().await.push(event.clone());
+        self.bus.publish(event.clone());
+        event
+    }
+
+    /// 대응 정책에 따라 대응하고 결과를 원인 이벤트와 같은 사고의 감사 이벤트로 기록 (억제 규칙 적용 안 함)
+    async fn respond(&self, event: &ReverseShellEvent, pid: u32, ancestors: &[u32]) {
+        let records = self.response.lock().await.respond(event, pid);
+        for record in records {
+            let audit = ReverseShellEvent {
+                timestamp: event.timestamp,
+                event_type: EventType::ResponseAction,
+                severity: event.severity.clone(),
+                rule_id: response::AUDIT_RULE_ID.to_string(),
+                details: record.describe(),
+                connection_info: event.connection_info.clone(),
+                process: event.process.clone(),
+                incident_id: None,
+                round_id: None,
+                techniques: Vec::new(),
+            };
+            self.emit(audit, pid, ancestors).await;
+        }
     }
 }
 
@@ -622,10 +1866,11 @@ mod tests {
 
     #[test]
     fn test_ip_conversion() {
-        assert_eq!(ReverseShellDetector::ip_to_u32("127.0.0.1"), 0x0100007F);
-        assert_eq!(ReverseShellDetector::ip_to_u32("192.168.1.1"), 0x0101A8C0);
-        assert_eq!(ReverseShellDetector::u32_to_ip(0x0100007F), "127.0.0.1");
-        assert_eq!(ReverseShellDetector::u32_to_ip(0x0101A8C0), "192.168.1.1");
+        // /proc/net 파싱 결과와 같은 호스트 바이트 순서 (첫 옥텟이 최상위 바이트)
+        assert_eq!(ReverseShellDetector::ip_to_u32("127.0.0.1"), 0x7F000001);
+        assert_eq!(ReverseShellDetector::ip_to_u32("192.168.1.1"), 0xC0A80101);
+        assert_eq!(ReverseShellDetector::u32_to_ip(0x7F000001), "127.0.0.1");
+        assert_eq!(ReverseShellDetector::u32_to_ip(0xC0A80101), "192.168.1.1");
     }
 
     #[test]
@@ -642,4 +1887,443 @@ mod tests {
         assert!(ReverseShellDetector::is_suspicious_process("bash -i"));
         assert!(!ReverseShellDetector::is_suspicious_process("nginx"));
     }
+
+    #[test]
+    fn test_classify_executable() {
+        assert!(matches!(
+            ReverseShellDetector::classify_executable("/memfd:payload (deleted)"),
+            Some((Severity::Critical, "memfd_executable"))
+        ));
+        assert!(matches!(
+            ReverseShellDetector::classify_executable("/tmp/.x/implant (deleted)"),
+            Some((Severity::High, "deleted_executable"))
+        ));
+        assert!(ReverseShellDetector::classify_executable("/usr/sbin/sshd").is_none());
+    }
+
+    #[test]
+    fn test_bind_shell_process() {
+        assert!(ReverseShellDetector::is_bind_shell_process("nc"));
+        assert!(ReverseShellDetector::is_bind_shell_process("/bin/sh"));
+        assert!(ReverseShellDetector::is_bind_shell_process("/usr/bin/python3"));
+        assert!(!ReverseShellDetector::is_bind_shell_process("/usr/sbin/sshd"));
+        assert!(!ReverseShellDetector::is_bind_shell_process("nginx"));
+    }
+
+    #[tokio::test]
+    async fn test_analyzer_without_privileges() {
+        let detector = ReverseShellDetector::new();
+        let mut analyzer = detector.analyzer(false, true);
+
+        let listener = |pid: u32, process_name: &str, port: u16| SocketSnapshot {
+            local_addr: 0,
+            local_addr6: None,
+            local_port: port,
+            remote_addr: 0,
+            remote_addr6: None,
+            remote_port: 0,
+            pid,
+            process_name: process_name.to_string(),
+        };
+
+        let at = |offset_ms: u64, event: SourceEvent| TimedEvent { offset_ms, event };
+
+        // 첫 스캔의 sshd 는 기준 포트, 이후 nc 리스너는 바인드 쉘
+        analyzer.handle(at(0, SourceEvent::ListenScan(vec![listener(1, "/usr/sbin/sshd", 22)]))).await;
+        analyzer.handle(at(5000, SourceEvent::ListenScan(vec![
+            listener(1, "/usr/sbin/sshd", 22),
+            listener(4242, "nc", 4444),
+        ]))).await;
+        // 같은 연결이 다시 보여도 한 번만 탐지
+        let outbound = SocketSnapshot {
+            local_addr: 0x0A000002,
+            local_addr6: None,
+            local_port: 40000,
+            remote_addr: 0x08080808,
+            remote_addr6: None,
+            remote_port: 4444,
+            pid: 4343,
+            process_name: "bash".to_string(),
+        };
+        analyzer.handle(at(10000, SourceEvent::ConnectionScan(vec![outbound.clone()]))).await;
+        analyzer.handle(at(15000, SourceEvent::ConnectionScan(vec![outbound]))).await;
+
+        let events = detector.get_detected_events().await;
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].rule_id, "interpreter_listen");
+        assert_eq!(events[1].rule_id, "suspicious_connection");
+        assert!(events.iter().all(|e| e.process.is_none()));
+        assert_eq!(detector.get_suspicious_connections().await[0].connection_count, 2);
+    }
+
+    #[tokio::test]
+    async fn test_ipv6_connection_detected() {
+        let detector = ReverseShellDetector::new();
+        let mut analyzer = detector.analyzer(false, true);
+
+        let outbound = |remote_addr6: &str, remote_port: u16| SocketSnapshot {
+            local_addr: 0,
+            local_addr6: Some("2001:db8::1".parse().unwrap()),
+            local_port: 40000,
+            remote_addr: 0,
+            remote_addr6: Some(remote_addr6.parse().unwrap()),
+            remote_port,
+            pid: 4343,
+            process_name: "bash".to_string(),
+        };
+
+        // 외부 IPv6 주소로의 연결만 탐지 (fd00::/8 은 고유 로컬)
+        analyzer.handle(TimedEvent {
+            offset_ms: 0,
+            event: SourceEvent::ConnectionScan(vec![outbound("2001:db8::2", 9001), outbound("fd00::1", 443)]),
+        }).await;
+
+        let events = detector.get_detected_events().await;
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].details, "Kernel hook detected suspicious connection: 2001:db8::1 -> [2001:db8::2]:9001");
+        let json = serde_json::to_value(&events[0]).unwrap();
+        assert_eq!(json["connection_info"]["remote_addr6"], "2001:db8::2");
+    }
+
+    #[tokio::test]
+    async fn test_suspicious_process_reported_once() {
+        let detector = ReverseShellDetector::new();
+        let mut analyzer = detector.analyzer(false, true);
+        let scan = |offset_ms: u64, processes: Vec<(u32, u64)>| TimedEvent {
+            offset_ms,
+            event: SourceEvent::ProcessScan(processes.into_iter().map(|(pid, start_time)| ProcessSnapshot {
+                pid,
+                name: "nc".to_string(),
+                exe: None,
+                stdio_socket: false,
+                start_time,
+            }).collect()),
+        };
+
+        // 살아 있는 동안에는 스캔마다 다시 보고하지 않음
+        analyzer.handle(scan(0, vec![(4242, 100)])).await;
+        analyzer.handle(scan(10000, vec![(4242, 100)])).await;
+        assert_eq!(detector.get_detected_events().await.len(), 1);
+
+        // pid 가 재사용되거나 종료 후 다시 실행되면 새로 보고
+        analyzer.handle(scan(20000, vec![(4242, 200)])).await;
+        analyzer.handle(scan(30000, vec![])).await;
+        analyzer.handle(scan(40000, vec![(4242, 200)])).await;
+        let events = detector.get_detected_events().await;
+        assert_eq!(events.len(), 3);
+        assert!(events.iter().all(|e| e.rule_id == "suspicious_process"));
+    }
+
+    #[tokio::test]
+    async fn test_dual_stack_listeners() {
+        let detector = ReverseShellDetector::new();
+        let mut analyzer = detector.analyzer(false, true);
+        let listener = |local_addr6: Option<Ipv6Addr>| SocketSnapshot {
+            local_addr: 0,
+            local_addr6,
+            local_port: 4444,
+            remote_addr: 0,
+            remote_addr6: None,
+            remote_port: 0,
+            pid: 4242,
+            process_name: "nc".to_string(),
+        };
+
+        // 같은 포트의 IPv4 와일드카드, IPv6 와일드카드, IPv6 루프백은 서로 다른 리스너
+        analyzer.handle(TimedEvent {
+            offset_ms: 0,
+            event: SourceEvent::ListenScan(vec![
+                listener(None),
+                listener(Some(Ipv6Addr::UNSPECIFIED)),
+                listener(Some(Ipv6Addr::LOCALHOST)),
+            ]),
+        }).await;
+
+        let mut endpoints: Vec<String> = detector.listen_tracker.read().await.keys().cloned().collect();
+        endpoints.sort();
+        assert_eq!(endpoints, vec!["0.0.0.0:4444", "[::1]:4444", "[::]:4444"]);
+
+        let events = detector.get_detected_events().await;
+        assert_eq!(events.len(), 3);
+        assert!(events.iter().any(|e| e.details.ends_with("listening on [::1]:4444")));
+        let json = serde_json::to_value(events.iter().find(|e| e.details.ends_with("[::1]:4444")).unwrap()).unwrap();
+        assert_eq!(json["connection_info"]["local_addr6"], "::1");
+    }
+
+    #[tokio::test]
+    async fn test_round_tagging_and_reset() {
+        let detector = ReverseShellDetector::new();
+        detector.set_round_config(RoundConfig {
+            reset: [RoundReset::Dedup, RoundReset::Incidents].into(),
+            report_dir: None,
+        }).await;
+        let mut analyzer = detector.analyzer(false, true);
+        let bind_shell = || TimedEvent {
+            offset_ms: 0,
+            event: SourceEvent::ListenScan(vec![SocketSnapshot {
+                local_addr: 0,
+                local_addr6: None,
+                local_port: 4444,
+                remote_addr: 0,
+                remote_addr6: None,
+                remote_port: 0,
+                pid: 4242,
+                process_name: "nc".to_string(),
+            }]),
+        };
+
+        // 라운드 밖의 이벤트에는 라운드가 없고, 같은 리스너는 라운드 안에서 한 번만 탐지
+        analyzer.handle(bind_shell()).await;
+        detector.start_round(Some(1)).await.unwrap();
+        analyzer.handle(bind_shell()).await;
+        analyzer.handle(bind_shell()).await;
+        detector.start_round(None).await.unwrap();
+        analyzer.handle(bind_shell()).await;
+        assert!(detector.start_round(Some(2)).await.is_err());
+
+        let events = detector.get_detected_events().await;
+        assert_eq!(events.iter().map(|e| e.round_id).collect::<Vec<_>>(), vec![None, Some(1), Some(2)]);
+        assert_ne!(events[1].incident_id, events[2].incident_id);
+        assert_eq!(detector.get_incidents().await.len(), 1);
+
+        let summaries = detector.round_summaries().await;
+        assert_eq!(summaries.iter().map(|summary| (summary.round.id, summary.events)).collect::<Vec<_>>(), vec![(1, 1), (2, 1)]);
+        assert!(summaries[0].round.ended_at.is_some());
+
+        assert_eq!(detector.stop_round().await.map(|round| round.id), Some(2));
+        assert!(detector.stop_round().await.is_none());
+        let report = detector.generate_round_report(2).await.unwrap();
+        assert!(report.starts_with("Round 2 Report\n=============="));
+        assert!(report.contains("Total Events Detected: 1\nSeverities: critical 1\nRules: interpreter_listen 1\n"));
+        assert!(detector.current_report().await.starts_with("Kernel-based Reverse Shell Detection Report"));
+    }
+
+    #[tokio::test]
+    async fn test_reverse_shell_chain_escalates() {
+        let detector = ReverseShellDetector::new();
+        let mut analyzer = detector.analyzer(false, true);
+
+        let kernel = |offset_ms: u64, kind: KernelEventKind, severity: u8| TimedEvent {
+            offset_ms,
+            event: SourceEvent::Kernel(KernelEvent {
+                kind,
+                severity,
+                pid: 5150,
+                local_addr: 0,
+                remote_addr: 0,
+                local_port: 0,
+                remote_port: 0,
+                timestamp_ns: offset_ms * 1_000_000,
+            }),
+        };
+
+        // 쉘 실행 → connect (이벤트 없음) → dup2(sock, 0) 순서로 같은 프로세스에서 발생
+        analyzer.handle(kernel(0, KernelEventKind::SuspiciousExec, 2)).await;
+        analyzer.handle(kernel(500, KernelEventKind::Connect, 1)).await;
+        analyzer.handle(kernel(600, KernelEventKind::StdioRedirect, 2)).await;
+
+        let events = detector.get_detected_events().await;
+        let rule_ids: Vec<&str> = events.iter().map(|e| e.rule_id.as_str()).collect();
+        assert_eq!(rule_ids, vec!["kernel_suspicious_exec", "kernel_stdio_redirect", "reverse_shell_chain"]);
+        assert_eq!(events[2].severity, Severity::Critical);
+        assert_eq!(events[2].event_type, EventType::ReverseShellDetected);
+        assert_eq!(attack::technique_ids(&events[2].techniques), "T1059.004,T1095");
+        assert!(events[2].details.contains("process tree of pid 5150"));
+
+        let risk = detector.risk_summary(10).await;
+        assert_eq!(risk.processes[0].pid, Some(5150));
+        assert_eq!(risk.processes[0].level, Some(Severity::Critical));
+        assert!(detector.generate_report().await.contains("Risk Score: host 55.0, process trees: pid 5150 55.0 (critical)"));
+    }
+
+    #[tokio::test]
+    async fn test_response_audit_events() {
+        let kernel = |kind: KernelEventKind, severity: u8, remote_addr: u32| TimedEvent {
+            offset_ms: 0,
+            event: SourceEvent::Kernel(KernelEvent {
+                kind,
+                severity,
+                pid: 5150,
+                local_addr: 0x0A00000A,
+                remote_addr,
+                local_port: 51234,
+                remote_port: 4444,
+                timestamp_ns: 0,
+            }),
+        };
+        let policies = |dry_run: bool| serde_json::from_value::<ResponseConfig>(serde_json::json!({
+            "dry_run": dry_run,
+            "policies": [{"rule_id": "kernel_suspicious_port", "actions": ["kill", "drop_flow"]}],
+        })).unwrap();
+
+        // dry-run 은 수행할 동작만 원인 이벤트와 같은 사고로 기록
+        let detector = ReverseShellDetector::new();
+        detector.set_response_config(policies(true)).await;
+        let mut analyzer = detector.analyzer(false, true);
+        analyzer.handle(kernel(KernelEventKind::SuspiciousPort, 3, 0xCB00710A)).await;
+        analyzer.handle(kernel(KernelEventKind::SuspiciousPort, 3, 0xCB00710A)).await;
+
+        let events = detector.get_detected_events().await;
+        let details: Vec<&str> = events.iter().filter(|e| e.event_type == EventType::ResponseAction).map(|e| e.details.as_str()).collect();
+        assert_eq!(details, vec![
+            "Response kill pid 5150 for kernel_suspicious_port: dry_run",
+            "Response drop_flow flow 10.0.0.10:51234 -> 203.0.113.10:4444 for kernel_suspicious_port: dry_run",
+        ]);
+        assert_eq!(events.len(), 4);
+        assert!(events.iter().all(|e| e.incident_id == events[0].incident_id));
+        assert!(detector.generate_report().await.contains("Active Response: dry-run (1 policies), actions: kill dry_run 1, drop_flow dry_run 1"));
+
+        // dry-run 이면 LSM 프로그램은 거부하지 않고 이벤트만 보냄
+        analyzer.handle(kernel(KernelEventKind::ConnectDenied, 2, 0xCB00710A)).await;
+        let denied = detector.get_detected_events().await.pop().unwrap();
+        assert_eq!((denied.rule_id.as_str(), denied.event_type), ("lsm_connect_denied", EventType::ResponseAction));
+        assert_eq!(denied.details, "LSM would deny (dry-run) connect to 203.0.113.10:4444 by pid 5150");
+
+        // XDP 차단 맵이 없는 소스에서는 연결 차단이 실패로 기록됨 (테스트 프로세스가 아닌 pid 를 종료하지 않도록 종료는 제외)
+        let detector = ReverseShellDetector::new();
+        let mut config = policies(false);
+        config.policies[0].actions = vec![response::ResponseAction::DropFlow];
+        detector.set_response_config(config).await;
+        let mut analyzer = detector.analyzer(false, true);
+        analyzer.handle(kernel(KernelEventKind::SuspiciousPort, 3, 0xCB00710A)).await;
+
+        let events = detector.get_detected_events().await;
+        assert_eq!(events[1].rule_id, "response_action");
+        assert!(events[1].details.ends_with("failed (XDP verdict maps not available (eBPF source with XDP required))"));
+        analyzer.handle(kernel(KernelEventKind::ExecDenied, 2, 0)).await;
+        assert_eq!(detector.get_detected_events().await[2].details, "LSM denied exec of blocked binary by pid 5150");
+        assert!(detector.render_metrics().await
+            .contains("telemagnifier_response_actions_total{action=\"drop_flow\",outcome=\"failed\"} 1"));
+    }
+
+    #[tokio::test]
+    async fn test_replay_ctf_incident() {
+        // tests/replay 의 기록 파일은 실제 CTF 사고를 재현한 회귀 테스트
+        let path = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/replay/ctf_reverse_shell.jsonl");
+        let detector = ReverseShellDetector::new();
+
+        detector.start_with_source(Box::new(crate::source::replay::ReplaySource::new(path, false))).await.unwrap();
+        detector.wait_until_drained().await;
+
+        let events = detector.get_detected_events().await;
+        let rule_ids: Vec<&str> = events.iter().map(|e| e.rule_id.as_str()).collect();
+        assert_eq!(rule_ids, vec![
+            "kernel_memfd_create",
+            "memfd_executable",
+            "interpreter_listen",
+            "suspicious_connection",
+        ]);
+
+        // 기록 당시의 간격이 가상 시각으로 재현됨
+        let elapsed = events[3].timestamp.duration_since(events[0].timestamp);
+        assert_eq!(elapsed, Duration::from_secs(40));
+
+        // 기록에는 조상 정보가 없으므로 같은 pid 의 이벤트만 같은 사고 (pid 가 있으면 시간만으로 묶지 않음)
+        let incident_ids: Vec<Option<u64>> = events.iter().map(|e| e.incident_id).collect();
+        assert_eq!(incident_ids, vec![Some(1), Some(1), Some(2), Some(3)]);
+        let incidents = detector.get_incidents().await;
+        assert_eq!(incidents.len(), 3);
+        assert_eq!(incidents[0].pids.iter().copied().collect::<Vec<_>>(), vec![31337]);
+        assert_eq!(incidents[2].peers.iter().cloned().collect::<Vec<_>>(), vec!["203.0.113.10:9001"]);
+
+        let report = detector.generate_report().await;
+        assert!(report.contains("Incidents: 3\n#3 Critical - 1 events over 0s, pids: 31401, peers: 203.0.113.10:9001"));
+        assert!(report.contains("#1 Critical - 2 events over 0s, pids: 31337, peers: none, rules: kernel_memfd_create, memfd_executable"));
+        assert!(report.contains("  +0s Critical [interpreter_listen] Possible bind shell"));
+
+        // 모든 탐지에 ATT&CK 기법이 부여되고 리포트에 전술별로 요약됨
+        assert!(events.iter().all(|e| !e.techniques.is_empty()));
+        assert_eq!(events[2].techniques.iter().map(|t| t.id).collect::<Vec<_>>(), vec!["T1059.004", "T1571"]);
+        assert!(report.contains("  execution (TA0002): 1 detections, observed [T1059.004] of covered [T1059.004]"));
+        assert!(report.contains("  defense-evasion (TA0005): 2 detections, observed [T1620] of covered [T1070.004, T1620]"));
+        assert!(report.contains("  command-and-control (TA0011): 2 detections, observed [T1071, T1571]"));
+    }
+
+    #[tokio::test]
+    async fn test_replay_takes_no_response() {
+        // 기록된 pid 와 주소는 현재 호스트의 다른 프로세스일 수 있으므로 dry-run 이 아니어도 대응하지 않음
+        let path = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/replay/ctf_reverse_shell.jsonl");
+        let detector = ReverseShellDetector::new();
+        detector.set_response_config(serde_json::from_value(serde_json::json!({
+            "dry_run": false,
+            "policies": [{"rule_id": "suspicious_connection", "actions": ["kill", "drop_flow", "block_destination"]}],
+        })).unwrap()).await;
+
+        detector.start_with_source(Box::new(crate::source::replay::ReplaySource::new(path, false))).await.unwrap();
+        detector.wait_until_drained().await;
+
+        let events = detector.get_detected_events().await;
+        assert_eq!(events.len(), 4);
+        assert!(events.iter().all(|e| e.event_type != EventType::ResponseAction));
+        assert!(!detector.render_metrics().await.contains("telemagnifier_response_actions_total{"));
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_drains_and_stops() {
+        let root = crate::source::procfs::ProcRoot::new(
+            std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/proc"));
+        let detector = ReverseShellDetector::new();
+
+        detector.start_with_source(Box::new(ProcfsSource::with_root(root))).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        detector.shutdown().await;
+
+        // 종료 전에 들어온 스캔 결과는 모두 분석됨
+        let detected = detector.get_detected_events().await.len();
+        assert!(detected > 0);
+
+        // 종료 후에는 더 이상 이벤트가 수집되지 않고, 다시 호출해도 안전함
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        detector.shutdown().await;
+        assert_eq!(detector.get_detected_events().await.len(), detected);
+    }
+
+    #[tokio::test]
+    async fn test_shared_detector_queried_concurrently() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<ReverseShellDetector>();
+
+        let path = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/replay/ctf_reverse_shell.jsonl");
+        let detector = Arc::new(ReverseShellDetector::new());
+        detector.start_with_source(Box::new(crate::source::replay::ReplaySource::new(path, false))).await.unwrap();
+
+        // 분석 중에도 다른 태스크에서 리포트와 이벤트를 조회할 수 있음
+        let queries: Vec<_> = (0..4).map(|_| {
+            let detector = Arc::clone(&detector);
+            tokio::spawn(async move {
+                let report = detector.generate_report().await;
+                assert!(report.contains("Event Source: replay"));
+                detector.get_detected_events().await.len()
+            })
+        }).collect();
+        for query in queries {
+            assert!(query.await.unwrap() <= 4);
+        }
+
+        detector.shutdown().await;
+        assert_eq!(detector.get_detected_events().await.len(), 4);
+        assert!(detector.generate_report().await.contains("Event Source: replay (stopped)"));
+    }
+
+    #[tokio::test]
+    async fn test_event_bus_subscriber() {
+        let path = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/replay/ctf_reverse_shell.jsonl");
+        let detector = ReverseShellDetector::new();
+        let mut subscription = detector.event_bus.subscribe("test").await;
+
+        detector.start_with_source(Box::new(crate::source::replay::ReplaySource::new(path, false))).await.unwrap();
+        detector.shutdown().await;
+
+        // 기록된 이벤트가 발행 순서대로 구독자에게 전달됨
+        let mut rule_ids = Vec::new();
+        for _ in 0..4 {
+            rule_ids.push(subscription.recv().await.unwrap().rule_id.clone());
+        }
+        assert_eq!(rule_ids, vec!["kernel_memfd_create", "memfd_executable", "interpreter_listen", "suspicious_connection"]);
+        assert!(detector.generate_report().await.contains("Event Bus: 4 published, subscribers: test (4 received, 0 lagged, 0 pending)"));
+    }
 }
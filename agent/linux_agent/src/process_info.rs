@@ -0,0 +1,521 @@
+use std::collections::{HashMap, HashSet};
+use std::fs::Metadata;
+use std::io::Read;
+use std::os::unix::fs::MetadataExt;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::source::procfs::ProcRoot;
+
+/// 해시를 계산할 실행 파일 최대 크기
+const MAX_HASH_SIZE: u64 = 64 * 1024 * 1024;
+
+/// 위험도 점수를 누적할 조상 프로세스 최대 깊이
+const MAX_ANCESTORS: usize = 32;
+
+/// 이벤트에 포함할 환경 변수 목록
+const ENV_KEYS: &[&str] = &[
+    "USER", "HOME", "PWD", "SHELL", "TERM", "SSH_CONNECTION", "SSH_CLIENT",
+    "SUDO_USER", "LD_PRELOAD", "LD_LIBRARY_PATH",
+];
+
+/// 프로세스 식별 정보
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProcessInfo {
+    pub pid: u32,
+    pub ppid: u32,
+    /// 부팅 후 프로세스 시작 시각 (clock ticks, /proc/<pid>/stat 22번째 필드)
+    pub start_time: u64,
+    pub comm: String,
+    /// /proc/<pid>/exe 링크 대상
+    pub exe: Option<String>,
+    pub exe_sha256: Option<String>,
+    pub uid: u32,
+    pub euid: u32,
+    pub gid: u32,
+    pub cwd: Option<String>,
+    pub tty: Option<String>,
+    /// cgroup 경로 (cgroup v2 기준)
+    pub cgroup: Option<String>,
+    pub argv: Vec<String>,
+    /// 주요 환경 변수 (ENV_KEYS)
+    pub environ: Vec<(String, String)>,
+}
+
+/// /proc/<pid>/stat 에서 필요한 필드
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ProcStat {
+    pub comm: String,
+    pub ppid: u32,
+    pub tty_nr: u32,
+    pub start_time: u64,
+}
+
+/// 프로세스 트리 노드
+#[derive(Debug, Clone, Serialize)]
+pub struct ProcessNode {
+    #[serde(flatten)]
+    pub process: ProcessInfo,
+    pub children: Vec<ProcessNode>,
+}
+
+/// 실행 파일 식별자 (같은 파일이면 해시를 다시 계산하지 않음)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ExeId {
+    dev: u64,
+    ino: u64,
+    mtime: i64,
+    mtime_nsec: i64,
+    size: u64,
+}
+
+/// 캐시된 프로세스 정보와 실행 파일 식별자
+#[derive(Debug)]
+struct CachedProcess {
+    info: ProcessInfo,
+    exe: Option<ExeId>,
+}
+
+/// (pid, 시작 시각) 기준 프로세스 정보 캐시
+///
+/// 실행 파일 해시는 계산 비용이 커서 조회 시 계산하지 않고, 호출자가 잠금 밖에서
+/// 계산해 insert_hash 로 넣으면 실행 파일 식별자 기준으로 공유함
+#[derive(Debug, Default)]
+pub struct ProcessCache {
+    /// 읽을 /proc 경로
+    proc: ProcRoot,
+    entries: HashMap<(u32, u64), CachedProcess>,
+    /// 실행 파일별 SHA-256 (계산할 수 없는 파일은 None)
+    hashes: HashMap<ExeId, Option<String>>,
+}
+
+impl ProcessInfo {
+    /// 전체 명령줄
+    pub fn command_line(&self) -> String {
+        if self.argv.is_empty() {
+            self.comm.clone()
+        } else {
+            self.argv.join(" ")
+        }
+    }
+
+    fn read(proc: &ProcRoot, pid: u32, stat: ProcStat) -> CachedProcess {
+        let proc_dir = proc.pid_path(pid);
+
+        let exe = std::fs::read_link(proc_dir.join("exe"))
+            .ok()
+            .map(|p| p.to_string_lossy().to_string());
+
+        let cwd = std::fs::read_link(proc_dir.join("cwd"))
+            .ok()
+            .map(|p| p.to_string_lossy().to_string());
+
+        let (uid, euid, gid) = std::fs::read_to_string(proc_dir.join("status"))
+            .map(|status| parse_status_ids(&status))
+            .unwrap_or((0, 0, 0));
+
+        let argv = std::fs::read(proc_dir.join("cmdline"))
+            .map(|bytes| split_nul(&bytes))
+            .unwrap_or_default();
+
+        let environ = std::fs::read(proc_dir.join("environ"))
+            .map(|bytes| {
+                split_nul(&bytes)
+                    .into_iter()
+                    .filter_map(|var| {
+                        let (key, value) = var.split_once('=')?;
+                        ENV_KEYS.contains(&key).then(|| (key.to_string(), value.to_string()))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let cgroup = std::fs::read_to_string(proc_dir.join("cgroup"))
+            .ok()
+            .and_then(|content| parse_cgroup(&content));
+
+        let info = Self {
+            pid,
+            ppid: stat.ppid,
+            start_time: stat.start_time,
+            comm: stat.comm,
+            exe_sha256: None,
+            exe,
+            uid,
+            euid,
+            gid,
+            cwd,
+            tty: tty_name(stat.tty_nr),
+            cgroup,
+            argv,
+            environ,
+        };
+        let exe = std::fs::metadata(proc_dir.join("exe")).ok().map(|metadata| ExeId::from(&metadata));
+        CachedProcess { info, exe }
+    }
+}
+
+impl From<&Metadata> for ExeId {
+    fn from(metadata: &Metadata) -> Self {
+        Self {
+            dev: metadata.dev(),
+            ino: metadata.ino(),
+            mtime: metadata.mtime(),
+            mtime_nsec: metadata.mtime_nsec(),
+            size: metadata.size(),
+        }
+    }
+}
+
+impl ProcessCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 지정한 /proc 경로를 읽는 캐시 생성
+    pub fn with_root(proc: ProcRoot) -> Self {
+        Self { proc, ..Self::default() }
+    }
+
+    /// 읽는 /proc 경로
+    pub fn proc(&self) -> &ProcRoot {
+        &self.proc
+    }
+
+    /// 프로세스 정보 조회 (pid 가 재사용된 경우 새로 읽음, 해시는 계산된 경우에만 포함)
+    pub fn get(&mut self, pid: u32) -> Option<ProcessInfo> {
+        if pid == 0 {
+            return None;
+        }
+
+        let stat = self.proc.stat(pid)?;
+        let key = (pid, stat.start_time);
+
+        let entry = self.entries
+            .entry(key)
+            .or_insert_with(|| ProcessInfo::read(&self.proc, pid, stat));
+        Some(Self::resolve(&self.hashes, entry))
+    }
+
+    /// 해시를 아직 계산하지 않은 프로세스의 실행 파일 식별자
+    pub fn unhashed(&self, info: &ProcessInfo) -> Option<ExeId> {
+        let exe = self.entries.get(&(info.pid, info.start_time))?.exe?;
+        (!self.hashes.contains_key(&exe)).then_some(exe)
+    }
+
+    /// 계산한 해시를 저장하고 반환
+    pub fn insert_hash(&mut self, exe: ExeId, hash: Option<String>) -> Option<String> {
+        self.hashes.entry(exe).or_insert(hash).clone()
+    }
+
+    fn resolve(hashes: &HashMap<ExeId, Option<String>>, entry: &CachedProcess) -> ProcessInfo {
+        ProcessInfo {
+            exe_sha256: entry.exe.and_then(|exe| hashes.get(&exe).cloned().flatten()),
+            ..entry.info.clone()
+        }
+    }
+
+    /// 종료되었거나 pid 가 재사용된 프로세스와 더 이상 실행 중이 아닌 실행 파일의 해시 제거
+    pub fn prune(&mut self) {
+        let proc = &self.proc;
+        self.entries.retain(|(pid, start_time), _| {
+            proc.stat(*pid).is_some_and(|stat| stat.start_time == *start_time)
+        });
+        let running: HashSet<ExeId> = self.entries.values().filter_map(|entry| entry.exe).collect();
+        self.hashes.retain(|exe, _| running.contains(exe));
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// 부모부터 순서대로 조상 pid (init 과 커널 스레드 제외)
+    pub fn ancestors(&mut self, pid: u32) -> Vec<u32> {
+        let mut ancestors = Vec::new();
+        let mut current = pid;
+        while let Some(info) = self.get(current) {
+            if info.ppid <= 1 || ancestors.contains(&info.ppid) || ancestors.len() >= MAX_ANCESTORS {
+                break;
+            }
+            ancestors.push(info.ppid);
+            current = info.ppid;
+        }
+        ancestors
+    }
+
+    /// 캐시된 프로세스와 그 조상 프로세스 (조상은 /proc 에서 읽어 캐시에 추가)
+    pub fn with_ancestors(&mut self) -> Vec<ProcessInfo> {
+        let mut processes: HashMap<u32, ProcessInfo> = self.entries.values()
+            .map(|entry| (entry.info.pid, Self::resolve(&self.hashes, entry)))
+            .collect();
+
+        let mut pending: Vec<u32> = processes.values().map(|info| info.ppid).collect();
+        while let Some(ppid) = pending.pop() {
+            if processes.contains_key(&ppid) {
+                continue;
+            }
+            if let Some(parent) = self.get(ppid) {
+                pending.push(parent.ppid);
+                processes.insert(ppid, parent);
+            }
+        }
+
+        processes.into_values().collect()
+    }
+}
+
+/// 부모 pid 기준으로 트리 구성 (부모가 목록에 없는 프로세스가 루트, pid 순 정렬)
+pub fn build_tree(processes: Vec<ProcessInfo>) -> Vec<ProcessNode> {
+    let pids: HashSet<u32> = processes.iter().map(|info| info.pid).collect();
+    let mut children: HashMap<u32, Vec<ProcessInfo>> = HashMap::new();
+    let mut roots = Vec::new();
+    for info in processes {
+        if info.ppid != info.pid && pids.contains(&info.ppid) {
+            children.entry(info.ppid).or_default().push(info);
+        } else {
+            roots.push(info);
+        }
+    }
+
+    fn attach(info: ProcessInfo, children: &mut HashMap<u32, Vec<ProcessInfo>>) -> ProcessNode {
+        let mut kids = children.remove(&info.pid).unwrap_or_default();
+        kids.sort_by_key(|child| child.pid);
+        ProcessNode {
+            children: kids.into_iter().map(|child| attach(child, children)).collect(),
+            process: info,
+        }
+    }
+
+    roots.sort_by_key(|root| root.pid);
+    roots.into_iter().map(|root| attach(root, &mut children)).collect()
+}
+
+/// /proc/<pid>/stat 파싱 (comm 에 공백/괄호가 들어갈 수 있으므로 마지막 ')' 기준)
+pub(crate) fn parse_stat(content: &str) -> Result<ProcStat> {
+    let open = content.find('(').ok_or_else(|| anyhow::anyhow!("Invalid stat format"))?;
+    let close = content.rfind(')').ok_or_else(|| anyhow::anyhow!("Invalid stat format"))?;
+    if close < open {
+        return Err(anyhow::anyhow!("Invalid stat format"));
+    }
+
+    let comm = content[open + 1..close].to_string();
+    let fields: Vec<&str> = content[close + 1..].split_whitespace().collect();
+    if fields.len() < 20 {
+        return Err(anyhow::anyhow!("Truncated stat line"));
+    }
+
+    Ok(ProcStat {
+        comm,
+        ppid: fields[1].parse()?,
+        tty_nr: fields[4].parse::<i64>()? as u32,
+        start_time: fields[19].parse()?,
+    })
+}
+
+/// status 의 Uid/Gid 라인에서 (uid, euid, gid) 추출
+fn parse_status_ids(status: &str) -> (u32, u32, u32) {
+    let ids = |prefix: &str| -> Vec<u32> {
+        status.lines()
+            .find(|line| line.starts_with(prefix))
+            .map(|line| line.split_whitespace().skip(1).filter_map(|v| v.parse().ok()).collect())
+            .unwrap_or_default()
+    };
+
+    let uids = ids("Uid:");
+    let gids = ids("Gid:");
+    (
+        uids.first().copied().unwrap_or(0),
+        uids.get(1).copied().unwrap_or(0),
+        gids.first().copied().unwrap_or(0),
+    )
+}
+
+/// cgroup v2 (0::path) 경로, 없으면 첫 번째 계층 경로
+fn parse_cgroup(content: &str) -> Option<String> {
+    let paths: Vec<(&str, &str)> = content.lines()
+        .filter_map(|line| {
+            let mut parts = line.splitn(3, ':');
+            let id = parts.next()?;
+            parts.next()?;
+            Some((id, parts.next()?))
+        })
+        .collect();
+
+    paths.iter()
+        .find(|(id, _)| *id == "0")
+        .or_else(|| paths.first())
+        .map(|(_, path)| path.to_string())
+}
+
+/// tty_nr 을 장치 이름으로 변환
+fn tty_name(tty_nr: u32) -> Option<String> {
+    let major = (tty_nr >> 8) & 0xfff;
+    let minor = (tty_nr & 0xff) | ((tty_nr >> 12) & 0xfff00);
+
+    match major {
+        0 => None,
+        4 => Some(format!("tty{}", minor)),
+        136..=143 => Some(format!("pts/{}", (major - 136) * 256 + minor)),
+        _ => Some(format!("{}:{}", major, minor)),
+    }
+}
+
+fn split_nul(bytes: &[u8]) -> Vec<String> {
+    bytes.split(|b| *b == 0)
+        .filter(|part| !part.is_empty())
+        .map(|part| String::from_utf8_lossy(part).to_string())
+        .collect()
+}
+
+/// /proc/<pid>/exe 의 SHA-256 (삭제된 실행 파일도 읽을 수 있음)
+///
+/// 파일 전체를 읽으므로 블로킹 스레드에서 호출. 그 사이에 실행 파일이 바뀌었으면 None
+pub fn hash_executable(proc: &ProcRoot, pid: u32, exe: ExeId) -> Option<String> {
+    let file = std::fs::File::open(proc.pid_path(pid).join("exe")).ok()?;
+    let metadata = file.metadata().ok()?;
+    if ExeId::from(&metadata) != exe || metadata.len() > MAX_HASH_SIZE {
+        return None;
+    }
+
+    let mut hasher = Sha256::new();
+    let mut reader = std::io::BufReader::new(file);
+    let mut buffer = [0u8; 64 * 1024];
+    loop {
+        let read = reader.read(&mut buffer).ok()?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+
+    Some(hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_stat() {
+        let line = "4242 (evil (sh) x) S 1 4242 4242 34816 4242 4194560 100 0 0 0 0 0 0 0 20 0 1 0 987654 1000 100 18446744073709551615 0 0 0 0 0 0 0 0 0 0 0 0 17 0 0 0 0 0 0";
+        let stat = parse_stat(line).unwrap();
+        assert_eq!(stat.comm, "evil (sh) x");
+        assert_eq!(stat.ppid, 1);
+        assert_eq!(stat.start_time, 987654);
+        assert_eq!(tty_name(stat.tty_nr), Some("pts/0".to_string()));
+        assert!(parse_stat("4242 (truncated) S 1").is_err());
+    }
+
+    #[test]
+    fn test_parse_status_and_cgroup() {
+        let status = "Name:\tbash\nUid:\t1000\t0\t0\t0\nGid:\t1000\t1000\t1000\t1000\n";
+        assert_eq!(parse_status_ids(status), (1000, 0, 1000));
+
+        let cgroup = "12:cpu,cpuacct:/docker/abc\n0::/system.slice/sshd.service\n";
+        assert_eq!(parse_cgroup(cgroup), Some("/system.slice/sshd.service".to_string()));
+        assert_eq!(tty_name(0), None);
+    }
+
+    #[test]
+    fn test_cache_reads_current_process() {
+        let mut cache = ProcessCache::new();
+        let pid = std::process::id();
+
+        let info = cache.get(pid).unwrap();
+        assert_eq!(info.pid, pid);
+        assert!(info.exe.is_some());
+        assert!(!info.argv.is_empty());
+        assert_eq!(cache.len(), 1);
+
+        cache.prune();
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn test_cache_reads_proc_root() {
+        let root = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/proc");
+        let mut cache = ProcessCache::with_root(ProcRoot::new(root));
+
+        // 호스트의 /proc 이 아니라 픽스처를 읽음
+        let info = cache.get(200).unwrap();
+        assert_eq!(info.comm, "nc");
+        assert_eq!(info.start_time, 987654);
+        assert_eq!(info.exe.as_deref(), Some("/usr/bin/nc.openbsd"));
+        assert_eq!(cache.get(std::process::id()).map(|info| info.pid), None);
+
+        cache.prune();
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn test_exe_hash_cache() {
+        // 테스트 바이너리는 MAX_HASH_SIZE 보다 클 수 있으므로 작은 실행 파일을 사용
+        let mut child = std::process::Command::new("sleep").arg("30").spawn().unwrap();
+        let mut cache = ProcessCache::new();
+        let pid = child.id();
+
+        // 조회만으로는 해시를 계산하지 않음
+        let info = cache.get(pid).unwrap();
+        assert_eq!(info.exe_sha256, None);
+        let exe = cache.unhashed(&info).unwrap();
+
+        let hash = hash_executable(cache.proc(), pid, exe);
+        assert_eq!(hash.as_ref().map(|hash| hash.len()), Some(64));
+        assert_eq!(cache.insert_hash(exe, hash.clone()), hash);
+
+        // 같은 실행 파일은 다시 계산하지 않음
+        let info = cache.get(pid).unwrap();
+        assert_eq!(info.exe_sha256, hash);
+        assert_eq!(cache.unhashed(&info), None);
+        assert_eq!(cache.with_ancestors().iter().find(|info| info.pid == pid).unwrap().exe_sha256, hash);
+
+        // 식별자가 다르면 (실행 파일이 바뀜) 계산하지 않음
+        assert_eq!(hash_executable(cache.proc(), pid, ExeId { size: exe.size + 1, ..exe }), None);
+        child.kill().unwrap();
+        child.wait().unwrap();
+    }
+
+    #[test]
+    fn test_build_tree() {
+        let process = |pid: u32, ppid: u32, comm: &str| ProcessInfo {
+            pid,
+            ppid,
+            comm: comm.to_string(),
+            ..Default::default()
+        };
+
+        // web -> sh -> nc, 부모가 목록에 없는 sshd 는 별도 루트
+        let tree = build_tree(vec![
+            process(31401, 31400, "nc"),
+            process(1204, 1, "web"),
+            process(812, 1, "sshd"),
+            process(31400, 1204, "sh"),
+        ]);
+
+        assert_eq!(tree.iter().map(|node| node.process.pid).collect::<Vec<_>>(), vec![812, 1204]);
+        assert_eq!(tree[1].children[0].process.comm, "sh");
+        assert_eq!(tree[1].children[0].children[0].process.comm, "nc");
+
+        let json = serde_json::to_value(&tree[1]).unwrap();
+        assert_eq!(json["comm"], "web");
+        assert_eq!(json["children"][0]["pid"], 31400);
+    }
+
+    #[test]
+    fn test_with_ancestors() {
+        let mut cache = ProcessCache::new();
+        let pid = std::process::id();
+        let ppid = cache.get(pid).unwrap().ppid;
+
+        let processes = cache.with_ancestors();
+        assert!(processes.iter().any(|info| info.pid == pid));
+        assert!(processes.iter().any(|info| info.pid == ppid));
+        assert_eq!(cache.len(), processes.len());
+
+        // 조상 pid 는 부모부터, init 은 제외
+        let ancestors = cache.ancestors(pid);
+        assert!(ppid <= 1 || ancestors[0] == ppid);
+        assert!(!ancestors.contains(&1));
+    }
+}
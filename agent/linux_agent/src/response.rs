@@ -0,0 +1,632 @@
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::net::Ipv4Addr;
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+use std::os::unix::fs::MetadataExt;
+use std::path::Path;
+use std::time::{Duration, Instant};
+use anyhow::Result;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+
+use crate::plugins::reverse_shell::{ReverseShellDetector, ReverseShellEvent};
+use crate::source::ebpf::{FlowKey, VerdictMaps};
+use crate::source::procfs::ProcRoot;
+use crate::suppression::parse_cidr;
+
+/// 감사 이벤트의 규칙 ID (대응 정책의 대상이 될 수 없음)
+pub const AUDIT_RULE_ID: &str = "response_action";
+
+/// cgroup v2 마운트 위치
+const CGROUP_ROOT: &str = "/sys/fs/cgroup";
+
+/// 규칙별 대응 횟수 제한 기간
+const RATE_WINDOW: Duration = Duration::from_secs(60);
+
+/// 같은 대상에 다시 대응하지 않도록 기억할 대상 수 (넘으면 비움)
+const MAX_APPLIED: usize = 4096;
+
+/// 대응 동작
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResponseAction {
+    /// 이벤트를 일으킨 프로세스에 SIGKILL
+    Kill,
+    /// 이벤트의 연결 (5-tuple) 을 XDP 에서 차단
+    DropFlow,
+    /// 이벤트의 원격 주소를 XDP 에서 차단
+    BlockDestination,
+}
+
+/// 탐지 규칙별 대응 정책
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResponsePolicy {
+    /// 대응할 탐지 규칙 ID
+    pub rule_id: String,
+    pub actions: Vec<ResponseAction>,
+    /// 분당 최대 대응 횟수 (넘으면 대응하지 않고 기록만 함)
+    #[serde(default = "default_max_per_minute")]
+    pub max_per_minute: usize,
+}
+
+/// 대응 설정 (정책이 없으면 관찰만 함)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ResponseConfig {
+    /// 대응하지 않고 수행할 동작만 기록
+    #[serde(default)]
+    pub dry_run: bool,
+    #[serde(default)]
+    pub policies: Vec<ResponsePolicy>,
+    /// 커널 LSM 프로그램으로 사전 차단할 연결과 실행
+    #[serde(default)]
+    pub lsm: LsmPolicy,
+}
+
+/// BPF-LSM 차단 목록 (차단 목록이 비어 있으면 LSM 프로그램을 붙이지 않음)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LsmPolicy {
+    /// 적용할 cgroup 경로 (cgroup v2 루트 기준, 예: /system.slice/ctf.service). 비어 있으면 모든 프로세스
+    #[serde(default)]
+    pub cgroups: Vec<String>,
+    /// 연결을 거부할 원격 포트
+    #[serde(default)]
+    pub blocked_ports: Vec<u16>,
+    /// 연결을 거부할 원격 주소 대역
+    #[serde(default)]
+    pub blocked_cidrs: Vec<String>,
+    /// 실행을 거부할 파일 경로 (시작 시 장치, inode 로 변환)
+    #[serde(default)]
+    pub blocked_execs: Vec<String>,
+}
+
+/// 커널 LSM 맵에 넣을 차단 규칙
+#[derive(Debug, Clone, PartialEq)]
+pub enum KernelDenyEntry {
+    /// 적용할 cgroup ID (0 이면 모든 프로세스)
+    Cgroup(u64),
+    Port(u16),
+    Cidr { addr: Ipv4Addr, prefix_len: u32 },
+    /// 실행 파일 (커널 dev_t, inode)
+    Exec { dev: u32, ino: u64 },
+}
+
+/// 대응 대상
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Target {
+    /// 프로세스 (탐지 시점의 시작 시각, 알 수 없으면 None)
+    Process { pid: u32, start_time: Option<u64> },
+    Flow(FlowKey),
+    Destination(u32),
+}
+
+/// 대응 결과
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Outcome {
+    Executed,
+    DryRun,
+    Failed,
+    RateLimited,
+}
+
+/// 대응 기록 (감사 이벤트로 남김)
+#[derive(Debug, Clone)]
+pub struct ActionRecord {
+    pub rule_id: String,
+    pub action: ResponseAction,
+    pub target: Target,
+    pub outcome: Outcome,
+    /// 실패 사유
+    pub error: Option<String>,
+}
+
+/// 대응 동작을 실제로 적용하는 대상
+pub trait Enforcer: Send {
+    /// start_time 이 다르면 (pid 가 재사용됨) 종료하지 않음
+    fn kill(&mut self, pid: u32, start_time: Option<u64>) -> Result<()>;
+    fn drop_flow(&mut self, flow: FlowKey) -> Result<()>;
+    fn block_destination(&mut self, addr: u32) -> Result<()>;
+}
+
+/// 현재 호스트에 적용 (프로세스는 pidfd 로 SIGKILL, 연결과 주소는 XDP 차단 맵)
+#[derive(Default)]
+pub struct HostEnforcer {
+    verdicts: Option<VerdictMaps>,
+    /// 종료 전에 시작 시각을 확인할 /proc
+    proc: ProcRoot,
+}
+
+/// 탐지 이벤트에 대응 정책을 적용하는 엔진
+pub struct ResponseEngine<E: Enforcer = HostEnforcer> {
+    config: ResponseConfig,
+    enforcer: E,
+    /// 규칙별 최근 대응 시각 (횟수 제한용)
+    recent: HashMap<String, VecDeque<Instant>>,
+    /// 이미 대응한 대상
+    applied: HashSet<(ResponseAction, Target)>,
+    /// 동작, 결과별 대응 수
+    counts: BTreeMap<(ResponseAction, Outcome), u64>,
+}
+
+fn default_max_per_minute() -> usize {
+    10
+}
+
+impl ResponseAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ResponseAction::Kill => "kill",
+            ResponseAction::DropFlow => "drop_flow",
+            ResponseAction::BlockDestination => "block_destination",
+        }
+    }
+
+    /// 이벤트에서 대응 대상 추출 (대상이 없는 동작은 None)
+    fn target(&self, event: &ReverseShellEvent, pid: u32) -> Option<Target> {
+        let connection = event.connection_info.as_ref().filter(|connection| connection.remote().is_some());
+        // XDP 맵은 IPv4 전용이라 IPv6 연결은 차단 대상이 없음
+        let ipv4 = connection.filter(|connection| connection.remote_addr6.is_none());
+        match self {
+            ResponseAction::Kill => [pid, connection.map_or(0, |c| c.pid), event.process.as_ref().map_or(0, |p| p.pid)]
+                .into_iter()
+                .find(|&pid| pid != 0)
+                .map(|pid| Target::Process {
+                    pid,
+                    start_time: event.process.as_ref().filter(|process| process.pid == pid).map(|process| process.start_time),
+                }),
+            ResponseAction::DropFlow => ipv4
+                .filter(|c| c.local_port != 0 && c.remote_port != 0)
+                .map(|c| Target::Flow(FlowKey::tcp(c.local_addr, c.local_port, c.remote_addr, c.remote_port))),
+            ResponseAction::BlockDestination => ipv4.map(|c| Target::Destination(c.remote_addr)),
+        }
+    }
+}
+
+impl ResponseConfig {
+    /// 파일에서 대응 설정 읽기 (JSON)
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        let config: ResponseConfig = serde_json::from_str(&content)?;
+
+        for cidr in &config.lsm.blocked_cidrs {
+            parse_cidr(cidr)?;
+        }
+        for policy in &config.policies {
+            if policy.rule_id == AUDIT_RULE_ID {
+                return Err(anyhow::anyhow!("{} events cannot trigger a response", AUDIT_RULE_ID));
+            }
+            if policy.actions.is_empty() {
+                return Err(anyhow::anyhow!("response policy for {} has no actions", policy.rule_id));
+            }
+        }
+
+        info!("Loaded {} response policies from {} ({})", config.policies.len(), path.display(), config.mode());
+        Ok(config)
+    }
+
+    pub fn mode(&self) -> &'static str {
+        match (self.policies.is_empty() && self.lsm.is_empty(), self.dry_run) {
+            (true, _) => "disabled",
+            (false, true) => "dry-run",
+            (false, false) => "enforcing",
+        }
+    }
+}
+
+impl LsmPolicy {
+    pub fn is_empty(&self) -> bool {
+        self.blocked_ports.is_empty() && self.blocked_cidrs.is_empty() && self.blocked_execs.is_empty()
+    }
+
+    /// 커널 LSM 맵에 넣을 규칙 (찾을 수 없는 cgroup, 실행 파일은 건너뜀)
+    pub fn kernel_deny_entries(&self) -> Vec<KernelDenyEntry> {
+        self.kernel_deny_entries_in(Path::new(CGROUP_ROOT))
+    }
+
+    fn kernel_deny_entries_in(&self, cgroup_root: &Path) -> Vec<KernelDenyEntry> {
+        if self.is_empty() {
+            return Vec::new();
+        }
+
+        let mut entries = Vec::new();
+        if self.cgroups.is_empty() {
+            entries.push(KernelDenyEntry::Cgroup(0));
+        }
+        // cgroup v2 의 cgroup ID 는 디렉터리의 inode 번호
+        for cgroup in &self.cgroups {
+            match std::fs::metadata(cgroup_root.join(cgroup.trim_start_matches('/'))) {
+                Ok(metadata) if metadata.is_dir() => entries.push(KernelDenyEntry::Cgroup(metadata.ino())),
+                Ok(_) => warn!("LSM cgroup {} is not a directory, skipped", cgroup),
+                Err(e) => warn!("LSM cgroup {} not found, skipped: {}", cgroup, e),
+            }
+        }
+        entries.extend(self.blocked_ports.iter().map(|&port| KernelDenyEntry::Port(port)));
+        entries.extend(self.blocked_cidrs.iter()
+            .filter_map(|cidr| parse_cidr(cidr).ok())
+            .map(|(addr, prefix_len)| KernelDenyEntry::Cidr { addr, prefix_len }));
+        // 심볼릭 링크는 따라가므로 실제로 실행되는 파일의 inode 가 등록됨
+        for path in &self.blocked_execs {
+            match std::fs::metadata(path) {
+                Ok(metadata) => entries.push(KernelDenyEntry::Exec { dev: kernel_dev(metadata.dev()), ino: metadata.ino() }),
+                Err(e) => warn!("LSM blocked exec {} not found, skipped: {}", path, e),
+            }
+        }
+        entries
+    }
+}
+
+/// 사용자 공간 dev_t 를 커널 내부 dev_t (major << 20 | minor) 로 변환
+fn kernel_dev(dev: u64) -> u32 {
+    (libc::major(dev) << 20) | libc::minor(dev)
+}
+
+impl Target {
+    pub fn describe(&self) -> String {
+        match self {
+            Target::Process { pid, .. } => format!("pid {}", pid),
+            Target::Flow(flow) => format!("flow {}:{} -> {}:{}",
+                ReverseShellDetector::u32_to_ip(flow.local_addr), flow.local_port,
+                ReverseShellDetector::u32_to_ip(flow.remote_addr), flow.remote_port),
+            Target::Destination(addr) => format!("destination {}", ReverseShellDetector::u32_to_ip(*addr)),
+        }
+    }
+}
+
+impl Outcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Outcome::Executed => "executed",
+            Outcome::DryRun => "dry_run",
+            Outcome::Failed => "failed",
+            Outcome::RateLimited => "rate_limited",
+        }
+    }
+}
+
+impl ActionRecord {
+    /// 감사 이벤트 내용 (예: "Response kill pid 31401 for reverse_shell_chain: executed")
+    pub fn describe(&self) -> String {
+        format!("Response {} {} for {}: {}{}", self.action.as_str(), self.target.describe(), self.rule_id,
+            self.outcome.as_str(), self.error.as_ref().map(|e| format!(" ({})", e)).unwrap_or_default())
+    }
+}
+
+impl HostEnforcer {
+    /// eBPF 소스가 꺼낸 XDP 차단 맵 연결
+    pub fn set_verdicts(&mut self, verdicts: VerdictMaps) {
+        self.verdicts = Some(verdicts);
+    }
+
+    fn verdicts(&mut self) -> Result<&mut VerdictMaps> {
+        self.verdicts.as_mut()
+            .ok_or_else(|| anyhow::anyhow!("XDP verdict maps not available (eBPF source with XDP required)"))
+    }
+}
+
+impl Enforcer for HostEnforcer {
+    fn kill(&mut self, pid: u32, start_time: Option<u64>) -> Result<()> {
+        if pid <= 1 || pid == std::process::id() {
+            return Err(anyhow::anyhow!("refusing to kill pid {}", pid));
+        }
+        let start_time = start_time
+            .ok_or_else(|| anyhow::anyhow!("refusing to kill pid {} without a known start time", pid))?;
+
+        // pidfd 를 연 뒤 시작 시각을 확인하면 확인한 프로세스에만 신호가 전달됨 (그 사이 재사용된 pid 는 무시)
+        let pidfd = pidfd_open(pid)?;
+        if self.proc.start_time(pid) != Some(start_time) {
+            return Err(anyhow::anyhow!("pid {} now belongs to another process", pid));
+        }
+        pidfd_send_signal(&pidfd, libc::SIGKILL)
+    }
+
+    fn drop_flow(&mut self, flow: FlowKey) -> Result<()> {
+        self.verdicts()?.block_flow(flow)
+    }
+
+    fn block_destination(&mut self, addr: u32) -> Result<()> {
+        self.verdicts()?.block_destination(addr)
+    }
+}
+
+/// 프로세스를 가리키는 pidfd (Linux 5.3 이상)
+fn pidfd_open(pid: u32) -> Result<OwnedFd> {
+    let fd = unsafe { libc::syscall(libc::SYS_pidfd_open, pid as libc::pid_t, 0) };
+    if fd < 0 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+    Ok(unsafe { OwnedFd::from_raw_fd(fd as i32) })
+}
+
+/// pidfd 가 가리키는 프로세스에 신호 전송 (Linux 5.1 이상)
+fn pidfd_send_signal(pidfd: &OwnedFd, signal: i32) -> Result<()> {
+    let ret = unsafe {
+        libc::syscall(libc::SYS_pidfd_send_signal, pidfd.as_raw_fd(), signal, std::ptr::null::<libc::siginfo_t>(), 0)
+    };
+    if ret < 0 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+    Ok(())
+}
+
+impl ResponseEngine {
+    pub fn new(config: ResponseConfig) -> Self {
+        Self::with_enforcer(config, HostEnforcer::default())
+    }
+}
+
+impl Default for ResponseEngine {
+    fn default() -> Self {
+        Self::new(ResponseConfig::default())
+    }
+}
+
+impl<E: Enforcer> ResponseEngine<E> {
+    pub fn with_enforcer(config: ResponseConfig, enforcer: E) -> Self {
+        Self {
+            config,
+            enforcer,
+            recent: HashMap::new(),
+            applied: HashSet::new(),
+            counts: BTreeMap::new(),
+        }
+    }
+
+    pub fn config(&self) -> &ResponseConfig {
+        &self.config
+    }
+
+    pub fn enforcer_mut(&mut self) -> &mut E {
+        &mut self.enforcer
+    }
+
+    /// 이미 대응한 대상 목록 비우기 (같은 대상에 다시 대응할 수 있음, 커널 차단 맵은 유지)
+    pub fn forget_applied(&mut self) {
+        self.applied.clear();
+    }
+
+    /// 동작, 결과별 대응 수
+    pub fn counts(&self) -> &BTreeMap<(ResponseAction, Outcome), u64> {
+        &self.counts
+    }
+
+    /// 이벤트의 규칙에 대응 정책이 있으면 대응하고 결과 반환 (이미 대응한 대상은 건너뜀)
+    pub fn respond(&mut self, event: &ReverseShellEvent, pid: u32) -> Vec<ActionRecord> {
+        if event.rule_id == AUDIT_RULE_ID {
+            return Vec::new();
+        }
+        let Some(policy) = self.config.policies.iter().find(|policy| policy.rule_id == event.rule_id).cloned() else {
+            return Vec::new();
+        };
+
+        let mut records = Vec::new();
+        for action in policy.actions {
+            let Some(target) = action.target(event, pid) else { continue };
+            if self.applied.contains(&(action, target)) {
+                continue;
+            }
+
+            let (outcome, error) = if !self.within_rate_limit(&policy.rule_id, policy.max_per_minute, event.timestamp) {
+                (Outcome::RateLimited, None)
+            } else if self.config.dry_run {
+                (Outcome::DryRun, None)
+            } else {
+                match self.apply(action, target) {
+                    Ok(()) => (Outcome::Executed, None),
+                    Err(e) => (Outcome::Failed, Some(e.to_string())),
+                }
+            };
+
+            if matches!(outcome, Outcome::Executed | Outcome::DryRun) {
+                if self.applied.len() >= MAX_APPLIED {
+                    self.applied.clear();
+                }
+                self.applied.insert((action, target));
+            }
+            *self.counts.entry((action, outcome)).or_default() += 1;
+
+            let record = ActionRecord { rule_id: policy.rule_id.clone(), action, target, outcome, error };
+            match outcome {
+                Outcome::Executed | Outcome::DryRun => info!("{}", record.describe()),
+                Outcome::Failed | Outcome::RateLimited => warn!("{}", record.describe()),
+            }
+            records.push(record);
+        }
+        records
+    }
+
+    /// 규칙의 최근 1분 대응 횟수가 제한 미만이면 이번 대응을 셈
+    fn within_rate_limit(&mut self, rule_id: &str, max_per_minute: usize, now: Instant) -> bool {
+        let recent = self.recent.entry(rule_id.to_string()).or_default();
+        while recent.front().is_some_and(|&at| now.saturating_duration_since(at) >= RATE_WINDOW) {
+            recent.pop_front();
+        }
+        if recent.len() >= max_per_minute {
+            return false;
+        }
+        recent.push_back(now);
+        true
+    }
+
+    fn apply(&mut self, action: ResponseAction, target: Target) -> Result<()> {
+        match (action, target) {
+            (ResponseAction::Kill, Target::Process { pid, start_time }) => self.enforcer.kill(pid, start_time),
+            (ResponseAction::DropFlow, Target::Flow(flow)) => self.enforcer.drop_flow(flow),
+            (ResponseAction::BlockDestination, Target::Destination(addr)) => self.enforcer.block_destination(addr),
+            _ => Err(anyhow::anyhow!("{} cannot be applied to {}", action.as_str(), target.describe())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::process::ExitStatusExt;
+    use crate::plugins::reverse_shell::{ConnectionInfo, EventType, Severity};
+
+    /// 적용한 동작을 기록만 하는 대상 (pid 4000 종료는 실패)
+    #[derive(Default)]
+    struct RecordingEnforcer {
+        applied: Vec<String>,
+    }
+
+    impl Enforcer for RecordingEnforcer {
+        fn kill(&mut self, pid: u32, _start_time: Option<u64>) -> Result<()> {
+            if pid == 4000 {
+                return Err(anyhow::anyhow!("No such process"));
+            }
+            self.applied.push(format!("kill {}", pid));
+            Ok(())
+        }
+
+        fn drop_flow(&mut self, flow: FlowKey) -> Result<()> {
+            self.applied.push(format!("drop {}", flow.remote_port));
+            Ok(())
+        }
+
+        fn block_destination(&mut self, addr: u32) -> Result<()> {
+            self.applied.push(format!("block {}", ReverseShellDetector::u32_to_ip(addr)));
+            Ok(())
+        }
+    }
+
+    fn event(at: Instant, rule_id: &str, remote_port: u16) -> ReverseShellEvent {
+        ReverseShellEvent {
+            timestamp: at,
+            event_type: EventType::KernelHookTriggered,
+            severity: Severity::Critical,
+            rule_id: rule_id.to_string(),
+            details: format!("{} detected", rule_id),
+            connection_info: Some(ConnectionInfo {
+                local_addr: 0x0A00000A,
+                local_addr6: None,
+                remote_addr: 0xCB00710A,
+                remote_addr6: None,
+                local_port: 51234,
+                remote_port,
+                pid: 0,
+                process_name: "bash".to_string(),
+                first_seen: at,
+                last_seen: at,
+                connection_count: 1,
+                is_suspicious: true,
+            }),
+            process: None,
+            incident_id: None,
+            round_id: None,
+            techniques: Vec::new(),
+        }
+    }
+
+    fn config(dry_run: bool) -> ResponseConfig {
+        serde_json::from_str(&format!(r#"{{
+            "dry_run": {},
+            "policies": [
+                {{"rule_id": "suspicious_connection", "actions": ["kill", "drop_flow", "block_destination"], "max_per_minute": 5}}
+            ]
+        }}"#, dry_run)).unwrap()
+    }
+
+    #[test]
+    fn test_enforce_policy() {
+        let mut engine = ResponseEngine::with_enforcer(config(false), RecordingEnforcer::default());
+        let now = Instant::now();
+
+        let records = engine.respond(&event(now, "suspicious_connection", 9001), 31401);
+        assert_eq!(records.iter().map(ActionRecord::describe).collect::<Vec<_>>(), vec![
+            "Response kill pid 31401 for suspicious_connection: executed",
+            "Response drop_flow flow 10.0.0.10:51234 -> 203.0.113.10:9001 for suspicious_connection: executed",
+            "Response block_destination destination 203.0.113.10 for suspicious_connection: executed",
+        ]);
+
+        // 정책이 없는 규칙과 감사 이벤트에는 대응하지 않음
+        assert!(engine.respond(&event(now, "new_listen_port", 9001), 31401).is_empty());
+        assert!(engine.respond(&event(now, AUDIT_RULE_ID, 9001), 31401).is_empty());
+
+        // 이미 차단한 주소는 건너뛰고, 종료에 실패하면 사유를 기록
+        let records = engine.respond(&event(now, "suspicious_connection", 4444), 4000);
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].outcome, Outcome::Failed);
+        assert_eq!(records[0].describe(), "Response kill pid 4000 for suspicious_connection: failed (No such process)");
+        assert_eq!(engine.enforcer_mut().applied, vec!["kill 31401", "drop 9001", "block 203.0.113.10", "drop 4444"]);
+
+        // 분당 5회를 넘으면 대응하지 않음, 1분이 지나면 다시 대응
+        let records = engine.respond(&event(now, "suspicious_connection", 5555), 5000);
+        assert_eq!(records.iter().map(|r| r.outcome).collect::<Vec<_>>(), vec![Outcome::RateLimited, Outcome::RateLimited]);
+        let records = engine.respond(&event(now + RATE_WINDOW, "suspicious_connection", 5555), 5000);
+        assert!(records.iter().all(|r| r.outcome == Outcome::Executed));
+        assert_eq!(engine.counts()[&(ResponseAction::Kill, Outcome::RateLimited)], 1);
+    }
+
+    #[test]
+    fn test_host_kill_checks_start_time() {
+        let mut child = std::process::Command::new("sleep").arg("30").spawn().unwrap();
+        let pid = child.id();
+        let start_time = ProcRoot::system().start_time(pid).unwrap();
+        let mut enforcer = HostEnforcer::default();
+
+        // 시작 시각을 모르거나 다르면 (pid 재사용) 종료하지 않음
+        assert!(enforcer.kill(pid, None).is_err());
+        assert!(enforcer.kill(pid, Some(start_time + 1)).unwrap_err().to_string().contains("another process"));
+        assert_eq!(child.try_wait().unwrap(), None);
+
+        enforcer.kill(pid, Some(start_time)).unwrap();
+        assert_eq!(child.wait().unwrap().signal(), Some(libc::SIGKILL));
+        assert!(enforcer.kill(1, Some(0)).is_err());
+
+        // 종료 대상은 탐지 시점의 프로세스 시작 시각을 가짐
+        let mut event = event(Instant::now(), "suspicious_connection", 9001);
+        assert_eq!(ResponseAction::Kill.target(&event, 31401), Some(Target::Process { pid: 31401, start_time: None }));
+        event.process = Some(crate::process_info::ProcessInfo { pid: 31401, start_time: 777, ..Default::default() });
+        assert_eq!(ResponseAction::Kill.target(&event, 31401), Some(Target::Process { pid: 31401, start_time: Some(777) }));
+    }
+
+    #[test]
+    fn test_dry_run_and_config() {
+        let mut engine = ResponseEngine::with_enforcer(config(true), RecordingEnforcer::default());
+        let records = engine.respond(&event(Instant::now(), "suspicious_connection", 9001), 31401);
+
+        assert_eq!(records.len(), 3);
+        assert!(records.iter().all(|r| r.outcome == Outcome::DryRun));
+        assert!(engine.enforcer_mut().applied.is_empty());
+        assert_eq!(engine.config().mode(), "dry-run");
+        assert_eq!(ResponseConfig::default().mode(), "disabled");
+
+        let path = std::env::temp_dir().join(format!("response-test-{}.json", std::process::id()));
+        std::fs::write(&path, r#"{"policies": [{"rule_id": "response_action", "actions": ["kill"]}]}"#).unwrap();
+        assert!(ResponseConfig::load(&path).is_err());
+        std::fs::write(&path, r#"{"policies": [{"rule_id": "reverse_shell_chain", "actions": ["kill"]}]}"#).unwrap();
+        let loaded = ResponseConfig::load(&path).unwrap();
+        assert_eq!((loaded.mode(), loaded.policies[0].max_per_minute), ("enforcing", 10));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_lsm_deny_entries() {
+        let root = std::env::temp_dir().join(format!("response-lsm-test-{}", std::process::id()));
+        std::fs::create_dir_all(root.join("system.slice/ctf.service")).unwrap();
+        std::fs::write(root.join("nc"), "").unwrap();
+
+        let policy = LsmPolicy {
+            cgroups: vec!["/system.slice/ctf.service".to_string(), "/missing.slice".to_string()],
+            blocked_ports: vec![4444],
+            blocked_cidrs: vec!["203.0.113.0/24".to_string()],
+            blocked_execs: vec![root.join("nc").display().to_string(), root.join("ncat").display().to_string()],
+        };
+        let cgroup = std::fs::metadata(root.join("system.slice/ctf.service")).unwrap();
+        let nc = std::fs::metadata(root.join("nc")).unwrap();
+
+        // 찾을 수 없는 cgroup 과 실행 파일은 건너뜀
+        assert_eq!(policy.kernel_deny_entries_in(&root), vec![
+            KernelDenyEntry::Cgroup(cgroup.ino()),
+            KernelDenyEntry::Port(4444),
+            KernelDenyEntry::Cidr { addr: Ipv4Addr::new(203, 0, 113, 0), prefix_len: 24 },
+            KernelDenyEntry::Exec { dev: kernel_dev(nc.dev()), ino: nc.ino() },
+        ]);
+
+        // cgroup 을 지정하지 않으면 모든 프로세스, 차단 목록이 없으면 규칙 없음
+        let global = LsmPolicy { cgroups: Vec::new(), ..policy.clone() };
+        assert_eq!(global.kernel_deny_entries_in(&root)[0], KernelDenyEntry::Cgroup(0));
+        assert!(LsmPolicy { cgroups: policy.cgroups, ..LsmPolicy::default() }.kernel_deny_entries_in(&root).is_empty());
+        assert_eq!(kernel_dev(libc::makedev(8, 1)), (8 << 20) | 1);
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,425 @@
+use std::collections::{BTreeSet, HashMap};
+use std::time::{Duration, Instant};
+use serde::Serialize;
+
+use crate::plugins::reverse_shell::Severity;
+
+/// 리버스 쉘 연쇄 (쉘 실행, 외부 연결, 표준 입출력 복제)
+const REVERSE_SHELL_CHAIN: [Signal; 3] = [Signal::ShellExec, Signal::OutboundConnect, Signal::StdioRedirect];
+
+/// 이 점수 아래로 감쇠하고 최근 신호가 없으면 추적 종료
+const PRUNE_SCORE: f64 = 1.0;
+
+/// 공격 단계 신호
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Signal {
+    /// 쉘/인터프리터 또는 공격 도구 실행
+    ShellExec,
+    /// 외부로 나가는 연결
+    OutboundConnect,
+    /// 소켓을 표준 입출력으로 복제
+    StdioRedirect,
+    /// 소켓 리스닝
+    Listen,
+    /// 파일리스 실행
+    Fileless,
+}
+
+/// 탐지 규칙별 점수 가중치
+#[derive(Debug, Clone, Serialize)]
+pub struct RuleWeight {
+    pub rule_id: &'static str,
+    pub weight: f64,
+    pub signal: Option<Signal>,
+}
+
+/// 규칙별 가중치 (목록에 없는 규칙은 심각도 기준 가중치 사용)
+pub const RULE_WEIGHTS: &[RuleWeight] = &[
+    RuleWeight { rule_id: "suspicious_connection", weight: 20.0, signal: Some(Signal::OutboundConnect) },
+    RuleWeight { rule_id: "kernel_suspicious_port", weight: 25.0, signal: Some(Signal::OutboundConnect) },
+    RuleWeight { rule_id: "kernel_external_connection", weight: 15.0, signal: Some(Signal::OutboundConnect) },
+    // 모든 connect 호출은 연쇄 판단에만 사용
+    RuleWeight { rule_id: "kernel_connect", weight: 0.0, signal: Some(Signal::OutboundConnect) },
+    RuleWeight { rule_id: "suspicious_process", weight: 15.0, signal: Some(Signal::ShellExec) },
+    RuleWeight { rule_id: "kernel_suspicious_exec", weight: 15.0, signal: Some(Signal::ShellExec) },
+    RuleWeight { rule_id: "kernel_stdio_redirect", weight: 40.0, signal: Some(Signal::StdioRedirect) },
+    RuleWeight { rule_id: "stdio_socket", weight: 40.0, signal: Some(Signal::StdioRedirect) },
+    RuleWeight { rule_id: "interpreter_listen", weight: 30.0, signal: Some(Signal::Listen) },
+    RuleWeight { rule_id: "kernel_listen", weight: 25.0, signal: Some(Signal::Listen) },
+    RuleWeight { rule_id: "new_listen_port", weight: 10.0, signal: Some(Signal::Listen) },
+    RuleWeight { rule_id: "memfd_executable", weight: 35.0, signal: Some(Signal::Fileless) },
+    RuleWeight { rule_id: "deleted_executable", weight: 25.0, signal: Some(Signal::Fileless) },
+    RuleWeight { rule_id: "kernel_memfd_create", weight: 15.0, signal: Some(Signal::Fileless) },
+    RuleWeight { rule_id: "kernel_execveat", weight: 30.0, signal: Some(Signal::Fileless) },
+    RuleWeight { rule_id: "kernel_hook", weight: 5.0, signal: None },
+];
+
+/// 점수 모델 설정
+#[derive(Debug, Clone, Serialize)]
+pub struct RiskConfig {
+    /// 점수가 절반으로 줄어드는 시간
+    pub half_life: Duration,
+    /// 연쇄로 판단할 신호 간 최대 간격
+    pub chain_window: Duration,
+    /// 프로세스 트리 경보 기준 (High, Critical)
+    pub process_thresholds: (f64, f64),
+    /// 호스트 경보 기준 (High, Critical)
+    pub host_thresholds: (f64, f64),
+}
+
+/// 점수 누적 대상
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Subject {
+    Host,
+    /// 프로세스와 그 하위 프로세스
+    ProcessTree(u32),
+}
+
+/// 경보 종류
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EscalationKind {
+    /// 누적 점수가 경보 기준을 넘음
+    Threshold,
+    /// 리버스 쉘 연쇄가 완성됨
+    ReverseShellChain,
+}
+
+/// 점수 누적으로 발생한 경보
+#[derive(Debug, Clone, PartialEq)]
+pub struct Escalation {
+    pub subject: Subject,
+    pub kind: EscalationKind,
+    pub severity: Severity,
+    pub score: f64,
+    /// 점수에 기여한 규칙
+    pub rules: Vec<String>,
+}
+
+/// 대상별 점수 현황
+#[derive(Debug, Clone, Serialize)]
+pub struct SubjectRisk {
+    /// 프로세스 트리의 루트 pid (호스트면 None)
+    pub pid: Option<u32>,
+    pub score: f64,
+    pub level: Option<Severity>,
+    pub signals: Vec<Signal>,
+    pub rules: Vec<String>,
+}
+
+/// 점수 현황 (조회 API, 리포트용)
+#[derive(Debug, Clone, Serialize)]
+pub struct RiskSummary {
+    pub host: SubjectRisk,
+    /// 점수가 높은 프로세스 트리
+    pub processes: Vec<SubjectRisk>,
+    pub config: RiskConfig,
+}
+
+/// 대상별 누적 상태
+#[derive(Debug, Clone)]
+struct RiskEntry {
+    score: f64,
+    updated: Instant,
+    /// 마지막으로 보고한 경보 수준
+    level: Option<Severity>,
+    /// 신호별 마지막 관찰 시각
+    signals: HashMap<Signal, Instant>,
+    /// (규칙, 원인 pid) 별 마지막 반영 시각 (반복 탐지는 반감기마다 한 번만 반영)
+    hits: HashMap<(String, u32), Instant>,
+    chain_reported: bool,
+}
+
+/// 규칙 탐지를 가중 점수로 누적하는 위험도 추적기 (프로세스 트리, 호스트 단위)
+#[derive(Debug)]
+pub struct RiskTracker {
+    config: RiskConfig,
+    entries: HashMap<Subject, RiskEntry>,
+}
+
+impl Default for RiskConfig {
+    fn default() -> Self {
+        Self {
+            half_life: Duration::from_secs(300),
+            chain_window: Duration::from_secs(120),
+            process_thresholds: (60.0, 100.0),
+            host_thresholds: (150.0, 250.0),
+        }
+    }
+}
+
+impl RiskConfig {
+    fn level(&self, subject: Subject, score: f64) -> Option<Severity> {
+        let (high, critical) = match subject {
+            Subject::Host => self.host_thresholds,
+            Subject::ProcessTree(_) => self.process_thresholds,
+        };
+        if score >= critical {
+            Some(Severity::Critical)
+        } else if score >= high {
+            Some(Severity::High)
+        } else {
+            None
+        }
+    }
+}
+
+/// 규칙의 가중치와 신호
+pub fn rule_weight(rule_id: &str, severity: &Severity) -> (f64, Option<Signal>) {
+    match RULE_WEIGHTS.iter().find(|rule| rule.rule_id == rule_id) {
+        Some(rule) => (rule.weight, rule.signal),
+        None => {
+            let weight = match severity {
+                Severity::Low => 2.0,
+                Severity::Medium => 5.0,
+                Severity::High => 10.0,
+                Severity::Critical => 20.0,
+            };
+            (weight, None)
+        }
+    }
+}
+
+impl RiskEntry {
+    fn new(now: Instant) -> Self {
+        Self {
+            score: 0.0,
+            updated: now,
+            level: None,
+            signals: HashMap::new(),
+            hits: HashMap::new(),
+            chain_reported: false,
+        }
+    }
+
+    /// now 시점까지 지수 감쇠 (경보 수준도 함께 낮춰 다시 경보할 수 있게 함, 연쇄를 보고한 트리는 Critical 유지)
+    fn decay(&mut self, subject: Subject, config: &RiskConfig, now: Instant) {
+        if now > self.updated {
+            let elapsed = now.duration_since(self.updated).as_secs_f64();
+            self.score *= 0.5f64.powf(elapsed / config.half_life.as_secs_f64());
+            self.updated = now;
+        }
+        if !self.chain_reported {
+            self.level = self.level.clone().min(config.level(subject, self.score));
+        }
+    }
+
+    /// 연쇄의 모든 신호가 chain_window 안에 관찰되었는지
+    fn chain_complete(&self, config: &RiskConfig, now: Instant) -> bool {
+        REVERSE_SHELL_CHAIN.iter().all(|signal| {
+            self.signals.get(signal).is_some_and(|seen| now.saturating_duration_since(*seen) <= config.chain_window)
+        })
+    }
+
+    fn rules(&self) -> Vec<String> {
+        self.hits.keys().map(|(rule, _)| rule.clone()).collect::<BTreeSet<_>>().into_iter().collect()
+    }
+
+    fn summary(&self, subject: Subject, config: &RiskConfig, now: Instant) -> SubjectRisk {
+        let mut entry = self.clone();
+        entry.decay(subject, config, now);
+        SubjectRisk {
+            pid: match subject {
+                Subject::Host => None,
+                Subject::ProcessTree(pid) => Some(pid),
+            },
+            score: (entry.score * 10.0).round() / 10.0,
+            level: entry.level.clone(),
+            signals: entry.signals.keys().copied().collect::<BTreeSet<_>>().into_iter().collect(),
+            rules: entry.rules(),
+        }
+    }
+}
+
+impl RiskTracker {
+    pub fn new(config: RiskConfig) -> Self {
+        Self { config, entries: HashMap::new() }
+    }
+
+    /// 모든 점수 초기화 (설정은 유지)
+    pub fn reset(&mut self) {
+        self.entries.clear();
+    }
+
+    /// 규칙 탐지 반영 (pid 와 조상 프로세스 트리, 호스트에 누적) 후 새로 발생한 경보 반환
+    ///
+    /// ancestors 는 가까운 부모부터 순서대로 전달하며, 가까운 트리가 이미 같은 수준으로
+    /// 경보한 경우 상위 트리와 호스트는 경보하지 않음
+    pub fn observe(&mut self, rule_id: &str, severity: &Severity, pid: u32, ancestors: &[u32], now: Instant) -> Vec<Escalation> {
+        let (weight, signal) = rule_weight(rule_id, severity);
+        let hit = (rule_id.to_string(), pid);
+
+        let mut subjects: Vec<Subject> = Vec::new();
+        if pid != 0 {
+            subjects.push(Subject::ProcessTree(pid));
+            subjects.extend(ancestors.iter().filter(|&&ancestor| ancestor > 1).map(|&ancestor| Subject::ProcessTree(ancestor)));
+        }
+        subjects.push(Subject::Host);
+
+        let mut escalations = Vec::new();
+        let mut reported_level: Option<Severity> = None;
+        let mut chain_reported = false;
+
+        for subject in subjects {
+            let config = &self.config;
+            let entry = self.entries.entry(subject).or_insert_with(|| RiskEntry::new(now));
+            entry.decay(subject, config, now);
+
+            let repeated = entry.hits.get(&hit).is_some_and(|last| now.saturating_duration_since(*last) < config.half_life);
+            if !repeated {
+                entry.score += weight;
+                entry.hits.insert(hit.clone(), now);
+            }
+            if let Some(signal) = signal {
+                entry.signals.insert(signal, now);
+            }
+
+            // 연쇄는 프로세스 트리 단위로만 판단 (호스트는 서로 무관한 프로세스가 섞임)
+            if matches!(subject, Subject::ProcessTree(_)) && !entry.chain_reported && entry.chain_complete(config, now) {
+                entry.chain_reported = true;
+                if !chain_reported {
+                    escalations.push(Escalation {
+                        subject,
+                        kind: EscalationKind::ReverseShellChain,
+                        severity: Severity::Critical,
+                        score: entry.score,
+                        rules: entry.rules(),
+                    });
+                    entry.level = Some(Severity::Critical);
+                }
+            }
+            chain_reported |= entry.chain_reported;
+
+            let level = config.level(subject, entry.score);
+            if level > entry.level {
+                if level > reported_level {
+                    escalations.push(Escalation {
+                        subject,
+                        kind: EscalationKind::Threshold,
+                        severity: level.clone().unwrap_or(Severity::High),
+                        score: entry.score,
+                        rules: entry.rules(),
+                    });
+                }
+                entry.level = level;
+            }
+            reported_level = reported_level.max(entry.level.clone());
+        }
+
+        escalations
+    }
+
+    /// 감쇠로 점수가 거의 없어지고 최근 신호도 없는 프로세스 트리 정리
+    pub fn prune(&mut self, now: Instant) {
+        let config = &self.config;
+        self.entries.retain(|subject, entry| {
+            entry.decay(*subject, config, now);
+            entry.hits.retain(|_, last| now.saturating_duration_since(*last) < config.half_life);
+            let recent = entry.signals.values().any(|seen| now.saturating_duration_since(*seen) <= config.chain_window);
+            *subject == Subject::Host || entry.score >= PRUNE_SCORE || recent
+        });
+    }
+
+    /// 호스트 점수와 점수가 높은 프로세스 트리 상위 limit 개
+    pub fn summary(&self, limit: usize, now: Instant) -> RiskSummary {
+        let host = match self.entries.get(&Subject::Host) {
+            Some(entry) => entry.summary(Subject::Host, &self.config, now),
+            None => RiskEntry::new(now).summary(Subject::Host, &self.config, now),
+        };
+
+        let mut processes: Vec<SubjectRisk> = self.entries.iter()
+            .filter(|(subject, _)| matches!(subject, Subject::ProcessTree(_)))
+            .map(|(subject, entry)| entry.summary(*subject, &self.config, now))
+            .filter(|risk| risk.score > 0.0)
+            .collect();
+        processes.sort_by(|a, b| b.score.total_cmp(&a.score).then(a.pid.cmp(&b.pid)));
+        processes.truncate(limit);
+
+        RiskSummary { host, processes, config: self.config.clone() }
+    }
+}
+
+impl Default for RiskTracker {
+    fn default() -> Self {
+        Self::new(RiskConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_score_decays_with_half_life() {
+        let mut tracker = RiskTracker::default();
+        let start = Instant::now();
+
+        assert!(tracker.observe("interpreter_listen", &Severity::Critical, 4242, &[], start).is_empty());
+        let summary = tracker.summary(10, start + Duration::from_secs(300));
+        assert_eq!(summary.processes[0].pid, Some(4242));
+        assert_eq!(summary.processes[0].score, 15.0);
+        assert_eq!(summary.host.score, 15.0);
+
+        // 같은 프로세스의 같은 규칙 반복 탐지는 반감기 안에서는 한 번만 반영
+        tracker.observe("interpreter_listen", &Severity::Critical, 4242, &[], start + Duration::from_secs(10));
+        assert_eq!(tracker.summary(10, start + Duration::from_secs(10)).host.score, 29.3);
+
+        // 점수가 사라지고 최근 신호도 없으면 정리
+        tracker.prune(start + Duration::from_secs(3600));
+        assert_eq!(tracker.entries.len(), 1);
+        assert!(tracker.entries.contains_key(&Subject::Host));
+        assert_eq!(rule_weight("unknown_rule", &Severity::High), (10.0, None));
+    }
+
+    #[test]
+    fn test_threshold_escalation_per_tree() {
+        let mut tracker = RiskTracker::default();
+        let now = Instant::now();
+
+        // 자식 프로세스 두 개의 점수가 부모 트리에 누적되어 경보
+        assert!(tracker.observe("memfd_executable", &Severity::Critical, 31337, &[1204, 1], now).is_empty());
+        let escalations = tracker.observe("interpreter_listen", &Severity::Critical, 31400, &[1204, 1], now);
+        assert_eq!(escalations, vec![Escalation {
+            subject: Subject::ProcessTree(1204),
+            kind: EscalationKind::Threshold,
+            severity: Severity::High,
+            score: 65.0,
+            rules: vec!["interpreter_listen".to_string(), "memfd_executable".to_string()],
+        }]);
+
+        // 이미 보고한 수준은 다시 경보하지 않고, 더 높아지면 경보
+        assert!(tracker.observe("deleted_executable", &Severity::High, 31401, &[1204, 1], now).is_empty());
+        let escalations = tracker.observe("kernel_execveat", &Severity::Critical, 31402, &[1204, 1], now);
+        assert_eq!(escalations.len(), 1);
+        assert_eq!((escalations[0].subject, escalations[0].severity.clone()), (Subject::ProcessTree(1204), Severity::Critical));
+
+        // 감쇠로 기준 아래로 내려가면 다시 경보할 수 있음
+        let later = now + Duration::from_secs(1800);
+        tracker.observe("kernel_hook", &Severity::High, 31403, &[1204], later);
+        assert_eq!(tracker.summary(10, later).processes[0].level, None);
+    }
+
+    #[test]
+    fn test_reverse_shell_chain() {
+        let mut tracker = RiskTracker::default();
+        let now = Instant::now();
+
+        // python 이 연결 후 dup2 하고, 자식 sh 를 실행하는 순서
+        assert!(tracker.observe("kernel_connect", &Severity::Medium, 5000, &[4000], now).is_empty());
+        assert!(tracker.observe("kernel_stdio_redirect", &Severity::High, 5000, &[4000], now).is_empty());
+        let escalations = tracker.observe("kernel_suspicious_exec", &Severity::High, 5001, &[5000, 4000], now + Duration::from_secs(1));
+
+        // 연쇄가 처음 완성된 트리 (5000) 에서만 보고
+        assert_eq!(escalations.len(), 1);
+        assert_eq!(escalations[0].kind, EscalationKind::ReverseShellChain);
+        assert_eq!(escalations[0].subject, Subject::ProcessTree(5000));
+        assert_eq!(escalations[0].severity, Severity::Critical);
+
+        // 신호 간격이 chain_window 를 넘으면 연쇄가 아님
+        let mut tracker = RiskTracker::default();
+        tracker.observe("kernel_connect", &Severity::Medium, 6000, &[], now);
+        tracker.observe("stdio_socket", &Severity::High, 6000, &[], now);
+        let escalations = tracker.observe("suspicious_process", &Severity::High, 6000, &[], now + Duration::from_secs(600));
+        assert!(escalations.iter().all(|escalation| escalation.kind != EscalationKind::ReverseShellChain));
+    }
+}
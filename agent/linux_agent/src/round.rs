@@ -0,0 +1,313 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use anyhow::Result;
+use clap::ValueEnum;
+use log::{info, warn};
+use serde::{Serialize, Serializer};
+
+use crate::plugins::reverse_shell::{serialize_instant, unix_millis, ReverseShellDetector, ReverseShellEvent};
+
+/// 라운드 신호 파일 확인 간격
+const ROUND_FILE_POLL: Duration = Duration::from_secs(2);
+
+/// 새 라운드를 시작할 때 초기화할 상태
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum RoundReset {
+    /// 이미 본 연결, 리스닝 소켓, 보고한 프로세스, 대응한 대상 (같은 공격을 새 라운드에서 다시 탐지)
+    Dedup,
+    /// 학습 모드로 시작한 기준선 (처음부터 다시 학습), 기준선이 없을 때 첫 스캔에서 잡은 리스닝 포트
+    Baseline,
+    Risk,
+    Incidents,
+    Scoreboard,
+}
+
+/// 라운드 설정
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RoundConfig {
+    pub reset: BTreeSet<RoundReset>,
+    /// 라운드가 끝날 때 라운드 리포트를 쓸 디렉터리
+    pub report_dir: Option<PathBuf>,
+}
+
+/// 라운드 (에포크)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Round {
+    pub id: u64,
+    #[serde(serialize_with = "serialize_instant")]
+    pub started_at: Instant,
+    /// 진행 중이면 None
+    #[serde(serialize_with = "serialize_optional_instant")]
+    pub ended_at: Option<Instant>,
+}
+
+/// 라운드 시작/종료 신호 (`start [id]`, `stop`, 또는 라운드 번호만, 빈 내용은 신호가 아님)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RoundSignal {
+    Start(Option<u64>),
+    Stop,
+}
+
+/// 라운드에 탐지된 이벤트 요약
+#[derive(Debug, Clone, Serialize)]
+pub struct RoundSummary {
+    #[serde(flatten)]
+    pub round: Round,
+    pub events: usize,
+    pub severities: BTreeMap<&'static str, usize>,
+    pub rules: BTreeMap<String, usize>,
+    pub techniques: BTreeSet<&'static str>,
+    pub incidents: BTreeSet<u64>,
+}
+
+/// 라운드 시작/종료 기록
+#[derive(Debug)]
+pub struct RoundTracker {
+    config: RoundConfig,
+    rounds: Vec<Round>,
+}
+
+fn serialize_optional_instant<S: Serializer>(instant: &Option<Instant>, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+    match instant {
+        Some(instant) => serializer.serialize_some(&unix_millis(*instant)),
+        None => serializer.serialize_none(),
+    }
+}
+
+impl RoundReset {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RoundReset::Dedup => "dedup",
+            RoundReset::Baseline => "baseline",
+            RoundReset::Risk => "risk",
+            RoundReset::Incidents => "incidents",
+            RoundReset::Scoreboard => "scoreboard",
+        }
+    }
+}
+
+impl Round {
+    /// 진행 시간 (진행 중이면 now 까지)
+    pub fn duration(&self, now: Instant) -> Duration {
+        self.ended_at.unwrap_or(now).saturating_duration_since(self.started_at)
+    }
+}
+
+impl RoundSignal {
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut words = text.split_whitespace();
+        let signal = match (words.next(), words.next()) {
+            (None, _) => return Err(anyhow::anyhow!("empty round signal")),
+            (Some("stop"), None) => RoundSignal::Stop,
+            (Some("start"), None) => RoundSignal::Start(None),
+            (Some("start"), Some(id)) => RoundSignal::Start(Some(id.parse()?)),
+            (Some(id), None) => RoundSignal::Start(Some(id.parse()?)),
+            _ => return Err(anyhow::anyhow!("invalid round signal: {}", text.trim())),
+        };
+        if words.next().is_some() {
+            return Err(anyhow::anyhow!("invalid round signal: {}", text.trim()));
+        }
+        Ok(signal)
+    }
+}
+
+impl RoundSummary {
+    /// 라운드 ID 가 붙은 이벤트로 요약
+    pub fn new(round: Round, events: &[ReverseShellEvent]) -> Self {
+        let mut summary = Self {
+            round,
+            events: 0,
+            severities: BTreeMap::new(),
+            rules: BTreeMap::new(),
+            techniques: BTreeSet::new(),
+            incidents: BTreeSet::new(),
+        };
+        for event in events.iter().filter(|event| event.round_id == Some(summary.round.id)) {
+            summary.events += 1;
+            *summary.severities.entry(event.severity.as_str()).or_default() += 1;
+            *summary.rules.entry(event.rule_id.clone()).or_default() += 1;
+            summary.techniques.extend(event.techniques.iter().map(|technique| technique.id));
+            summary.incidents.extend(event.incident_id);
+        }
+        summary
+    }
+}
+
+impl RoundTracker {
+    pub fn new(config: RoundConfig) -> Self {
+        Self { config, rounds: Vec::new() }
+    }
+
+    pub fn config(&self) -> &RoundConfig {
+        &self.config
+    }
+
+    pub fn resets(&self, reset: RoundReset) -> bool {
+        self.config.reset.contains(&reset)
+    }
+
+    /// 진행 중인 라운드
+    pub fn current(&self) -> Option<&Round> {
+        self.rounds.last().filter(|round| round.ended_at.is_none())
+    }
+
+    pub fn current_id(&self) -> Option<u64> {
+        self.current().map(|round| round.id)
+    }
+
+    /// 지금까지 시작한 라운드 수 (분석기가 새 라운드 시작을 알아채는 데 사용)
+    pub fn started(&self) -> usize {
+        self.rounds.len()
+    }
+
+    pub fn rounds(&self) -> &[Round] {
+        &self.rounds
+    }
+
+    pub fn get(&self, id: u64) -> Option<&Round> {
+        self.rounds.iter().find(|round| round.id == id)
+    }
+
+    /// 라운드 시작 (진행 중인 라운드는 끝냄), 끝난 라운드와 시작한 라운드 반환
+    ///
+    /// id 가 없으면 지금까지 가장 큰 라운드 번호 + 1
+    pub fn start(&mut self, id: Option<u64>, now: Instant) -> Result<(Option<Round>, Round)> {
+        let id = id.unwrap_or_else(|| self.rounds.iter().map(|round| round.id).max().unwrap_or(0) + 1);
+        if self.get(id).is_some() {
+            return Err(anyhow::anyhow!("round {} was already started", id));
+        }
+
+        let ended = self.stop(now);
+        let round = Round { id, started_at: now, ended_at: None };
+        self.rounds.push(round.clone());
+        Ok((ended, round))
+    }
+
+    /// 진행 중인 라운드 종료
+    pub fn stop(&mut self, now: Instant) -> Option<Round> {
+        let round = self.rounds.last_mut().filter(|round| round.ended_at.is_none())?;
+        round.ended_at = Some(now);
+        Some(round.clone())
+    }
+}
+
+/// 라운드 리포트 파일 경로
+pub fn report_path(dir: &Path, id: u64) -> PathBuf {
+    dir.join(format!("round-{}.txt", id))
+}
+
+/// 신호 파일 내용이 바뀔 때마다 라운드 시작/종료 (파일이 없으면 무시)
+pub async fn watch_file(path: PathBuf, detector: Arc<ReverseShellDetector>) {
+    info!("Watching {} for round signals", path.display());
+
+    let mut last: Option<String> = None;
+    loop {
+        if let Ok(content) = tokio::fs::read_to_string(&path).await {
+            match changed_signal(&mut last, content) {
+                Some(Ok(signal)) => if let Err(e) = detector.apply_round_signal(signal).await {
+                    warn!("Ignoring round signal from {}: {}", path.display(), e);
+                },
+                Some(Err(e)) => warn!("Ignoring round signal from {}: {}", path.display(), e),
+                None => {}
+            }
+        }
+        tokio::time::sleep(ROUND_FILE_POLL).await;
+    }
+}
+
+/// 마지막으로 처리한 내용과 다르면 신호로 해석
+///
+/// `echo 4 > round` 처럼 파일을 비운 뒤 쓰는 도중에 읽으면 빈 내용이 보이므로, 빈 내용은
+/// 처리한 것으로 치지 않고 무시함 (다음 확인에서 쓰인 내용을 처리)
+fn changed_signal(last: &mut Option<String>, content: String) -> Option<Result<RoundSignal>> {
+    if content.trim().is_empty() || last.as_deref() == Some(content.as_str()) {
+        return None;
+    }
+    let signal = RoundSignal::parse(&content);
+    *last = Some(content);
+    Some(signal)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::attack;
+    use crate::plugins::reverse_shell::{EventType, Severity};
+
+    #[test]
+    fn test_round_lifecycle() {
+        let mut tracker = RoundTracker::new(RoundConfig { reset: BTreeSet::from([RoundReset::Dedup]), report_dir: None });
+        let start = Instant::now();
+        let at = |secs: u64| start + Duration::from_secs(secs);
+
+        assert_eq!(tracker.current_id(), None);
+        let (ended, first) = tracker.start(None, at(0)).unwrap();
+        assert_eq!((ended, first.id), (None, 1));
+
+        // 진행 중에 다음 라운드를 시작하면 이전 라운드는 끝남
+        let (ended, second) = tracker.start(Some(5), at(300)).unwrap();
+        assert_eq!(ended.unwrap().duration(at(999)), Duration::from_secs(300));
+        assert_eq!((second.id, tracker.current_id(), tracker.started()), (5, Some(5), 2));
+        assert!(tracker.start(Some(1), at(310)).is_err());
+
+        assert_eq!(tracker.stop(at(420)).map(|round| round.id), Some(5));
+        assert_eq!(tracker.stop(at(430)), None);
+        assert_eq!(tracker.start(None, at(500)).unwrap().1.id, 6);
+        assert!(tracker.resets(RoundReset::Dedup) && !tracker.resets(RoundReset::Scoreboard));
+
+        let json = serde_json::to_value(tracker.get(5).unwrap()).unwrap();
+        assert!(json["ended_at"].as_u64().unwrap() > json["started_at"].as_u64().unwrap());
+        assert!(serde_json::to_value(tracker.current().unwrap()).unwrap()["ended_at"].is_null());
+    }
+
+    #[test]
+    fn test_signal_and_summary() {
+        assert_eq!(RoundSignal::parse("start 3\n").unwrap(), RoundSignal::Start(Some(3)));
+        assert_eq!(RoundSignal::parse("start").unwrap(), RoundSignal::Start(None));
+        assert_eq!(RoundSignal::parse("7").unwrap(), RoundSignal::Start(Some(7)));
+        assert_eq!(RoundSignal::parse("stop").unwrap(), RoundSignal::Stop);
+        assert!(RoundSignal::parse(" \n").is_err());
+        assert!(RoundSignal::parse("start three").is_err());
+        assert!(RoundSignal::parse("stop 3").is_err());
+
+        // 쓰는 도중의 빈 파일은 무시하고, 같은 내용이 다시 보여도 한 번만 처리
+        let mut last = None;
+        assert_eq!(changed_signal(&mut last, "4\n".to_string()).unwrap().unwrap(), RoundSignal::Start(Some(4)));
+        assert!(changed_signal(&mut last, String::new()).is_none());
+        assert!(changed_signal(&mut last, "4\n".to_string()).is_none());
+        assert_eq!(changed_signal(&mut last, "stop\n".to_string()).unwrap().unwrap(), RoundSignal::Stop);
+        assert!(changed_signal(&mut last, "bogus".to_string()).unwrap().is_err());
+
+        let event = |round_id: Option<u64>, rule_id: &str, severity: Severity| ReverseShellEvent {
+            timestamp: Instant::now(),
+            event_type: EventType::ReverseShellDetected,
+            severity,
+            rule_id: rule_id.to_string(),
+            details: String::new(),
+            connection_info: None,
+            process: None,
+            incident_id: Some(2),
+            round_id,
+            techniques: vec![attack::UNIX_SHELL],
+        };
+        let events = vec![
+            event(Some(1), "suspicious_connection", Severity::Critical),
+            event(Some(2), "suspicious_connection", Severity::Critical),
+            event(Some(2), "kernel_memfd_create", Severity::High),
+            event(None, "new_listen_port", Severity::Medium),
+        ];
+
+        let round = Round { id: 2, started_at: Instant::now(), ended_at: None };
+        let summary = RoundSummary::new(round, &events);
+        assert_eq!(summary.events, 2);
+        assert_eq!(summary.severities, BTreeMap::from([("critical", 1), ("high", 1)]));
+        assert_eq!(summary.rules.keys().cloned().collect::<Vec<_>>(), vec!["kernel_memfd_create", "suspicious_connection"]);
+        assert_eq!(summary.techniques, BTreeSet::from(["T1059.004"]));
+        assert_eq!(summary.incidents, BTreeSet::from([2]));
+        assert_eq!(serde_json::to_value(&summary).unwrap()["id"], 2);
+        assert_eq!(report_path(Path::new("/tmp/rounds"), 2), PathBuf::from("/tmp/rounds/round-2.txt"));
+    }
+}
@@ -0,0 +1,382 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+use std::time::{Duration, Instant};
+use anyhow::Result;
+use log::info;
+use serde::{Deserialize, Serialize};
+
+use crate::plugins::reverse_shell::{ReverseShellEvent, Severity};
+use crate::response;
+use crate::suppression;
+
+/// 호스트 이름 (설정에 host 가 없을 때)
+const HOSTNAME_PATH: &str = "/proc/sys/kernel/hostname";
+
+/// 팀과 팀에 속한 호스트, cgroup
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TeamMapping {
+    pub team: String,
+    /// 호스트 이름
+    #[serde(default)]
+    pub hosts: Vec<String>,
+    /// cgroup 경로 접두사 (한 호스트에서 여러 팀의 컨테이너를 실행할 때)
+    #[serde(default)]
+    pub cgroups: Vec<String>,
+}
+
+/// 심각도별 감점
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SeverityPoints {
+    pub low: u64,
+    pub medium: u64,
+    pub high: u64,
+    pub critical: u64,
+}
+
+/// 팀별 감점 설정
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScoringConfig {
+    /// 이 에이전트의 호스트 이름 (없으면 커널 호스트 이름)
+    pub host: Option<String>,
+    pub teams: Vec<TeamMapping>,
+    pub severity_points: SeverityPoints,
+    /// 규칙별 감점 (심각도별 감점보다 우선)
+    pub rule_points: BTreeMap<String, u64>,
+    /// 같은 팀, 같은 규칙은 이 시간(초) 안에 한 번만 감점
+    pub cooldown_secs: u64,
+    /// 팀별 규칙당 최대 감점
+    pub rule_cap: Option<u64>,
+    /// 팀별 최대 감점
+    pub team_cap: Option<u64>,
+}
+
+/// 규칙별 탐지 수와 감점
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct RuleScore {
+    pub detections: u64,
+    pub penalty: u64,
+}
+
+/// 팀 순위 (CTFd 스코어보드 항목 형식, score 는 감점의 음수)
+#[derive(Debug, Clone, Serialize)]
+pub struct Standing {
+    pub pos: usize,
+    pub name: String,
+    pub score: i64,
+    pub penalty: u64,
+    pub detections: u64,
+    pub rules: BTreeMap<String, RuleScore>,
+}
+
+/// 스코어보드 피드 (CTFd `/api/v1/scoreboard` 응답 형식)
+#[derive(Debug, Clone, Serialize)]
+pub struct ScoreboardFeed {
+    pub success: bool,
+    pub data: Vec<Standing>,
+}
+
+/// 탐지 이벤트를 팀별 감점으로 집계
+#[derive(Debug, Default)]
+pub struct Scoreboard {
+    config: ScoringConfig,
+    host: String,
+    teams: BTreeMap<String, BTreeMap<String, RuleScore>>,
+    /// 팀, 규칙별 마지막 감점 시각
+    last_penalty: HashMap<(String, String), Instant>,
+}
+
+impl Default for SeverityPoints {
+    fn default() -> Self {
+        Self { low: 1, medium: 5, high: 20, critical: 50 }
+    }
+}
+
+impl SeverityPoints {
+    pub fn get(&self, severity: &Severity) -> u64 {
+        match severity {
+            Severity::Low => self.low,
+            Severity::Medium => self.medium,
+            Severity::High => self.high,
+            Severity::Critical => self.critical,
+        }
+    }
+}
+
+impl Default for ScoringConfig {
+    fn default() -> Self {
+        Self {
+            host: None,
+            teams: Vec::new(),
+            severity_points: SeverityPoints::default(),
+            rule_points: BTreeMap::new(),
+            cooldown_secs: 60,
+            rule_cap: None,
+            team_cap: None,
+        }
+    }
+}
+
+impl ScoringConfig {
+    /// JSON 파일에서 감점 설정 불러오기
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        let config: ScoringConfig = serde_json::from_str(&content)?;
+
+        let mut names = HashSet::new();
+        for mapping in &config.teams {
+            if mapping.team.is_empty() {
+                return Err(anyhow::anyhow!("team name must not be empty"));
+            }
+            if !names.insert(mapping.team.as_str()) {
+                return Err(anyhow::anyhow!("duplicate team: {}", mapping.team));
+            }
+        }
+
+        info!("Loaded scoring for {} teams from {}", config.teams.len(), path.display());
+        Ok(config)
+    }
+
+    fn points(&self, event: &ReverseShellEvent) -> u64 {
+        self.rule_points.get(&event.rule_id)
+            .copied()
+            .unwrap_or_else(|| self.severity_points.get(&event.severity))
+    }
+}
+
+impl Scoreboard {
+    pub fn new(config: ScoringConfig) -> Self {
+        let host = config.host.clone().unwrap_or_else(|| {
+            std::fs::read_to_string(HOSTNAME_PATH).map(|name| name.trim().to_string()).unwrap_or_default()
+        });
+        let teams = config.teams.iter().map(|mapping| (mapping.team.clone(), BTreeMap::new())).collect();
+        Self { config, host, teams, last_penalty: HashMap::new() }
+    }
+
+    /// 팀별 감점과 쿨다운 초기화 (설정은 유지)
+    pub fn reset(&mut self) {
+        for rules in self.teams.values_mut() {
+            rules.clear();
+        }
+        self.last_penalty.clear();
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.config.teams.is_empty()
+    }
+
+    /// 이벤트의 팀 (경로 단위로 가장 길게 일치하는 cgroup 접두사, 없으면 호스트 이름으로 판별)
+    fn team_of(&self, event: &ReverseShellEvent) -> Option<&str> {
+        let cgroup = event.process.as_ref().and_then(|process| process.cgroup.as_deref());
+        let by_cgroup = cgroup.and_then(|cgroup| {
+            self.config.teams.iter()
+                .flat_map(|mapping| mapping.cgroups.iter().map(move |prefix| (mapping, prefix)))
+                .filter(|(_, prefix)| suppression::cgroup_within(cgroup, prefix))
+                .max_by_key(|(_, prefix)| prefix.len())
+                .map(|(mapping, _)| mapping.team.as_str())
+        });
+
+        by_cgroup.or_else(|| {
+            self.config.teams.iter()
+                .find(|mapping| mapping.hosts.contains(&self.host))
+                .map(|mapping| mapping.team.as_str())
+        })
+    }
+
+    /// 이벤트를 팀 점수에 반영하고 (팀, 감점) 반환 (대응 감사 이벤트와 팀이 없는 이벤트는 제외)
+    pub fn record(&mut self, event: &ReverseShellEvent) -> Option<(String, u64)> {
+        if event.rule_id == response::AUDIT_RULE_ID {
+            return None;
+        }
+        let team = self.team_of(event)?.to_string();
+
+        let key = (team.clone(), event.rule_id.clone());
+        let cooldown = Duration::from_secs(self.config.cooldown_secs);
+        let cooling = self.last_penalty.get(&key)
+            .is_some_and(|last| event.timestamp.saturating_duration_since(*last) < cooldown);
+
+        let rules = self.teams.entry(team.clone()).or_default();
+        let team_penalty: u64 = rules.values().map(|rule| rule.penalty).sum();
+        let rule = rules.entry(event.rule_id.clone()).or_default();
+        rule.detections += 1;
+        if cooling {
+            return Some((team, 0));
+        }
+
+        let mut points = self.config.points(event);
+        if let Some(cap) = self.config.rule_cap {
+            points = points.min(cap.saturating_sub(rule.penalty));
+        }
+        if let Some(cap) = self.config.team_cap {
+            points = points.min(cap.saturating_sub(team_penalty));
+        }
+        rule.penalty += points;
+        self.last_penalty.insert(key, event.timestamp);
+        Some((team, points))
+    }
+
+    /// 팀 순위 (감점이 적은 순, 같으면 팀 이름 순)
+    pub fn standings(&self) -> Vec<Standing> {
+        let mut standings: Vec<Standing> = self.teams.iter()
+            .map(|(team, rules)| {
+                let penalty = rules.values().map(|rule| rule.penalty).sum::<u64>();
+                Standing {
+                    pos: 0,
+                    name: team.clone(),
+                    score: -(penalty as i64),
+                    penalty,
+                    detections: rules.values().map(|rule| rule.detections).sum(),
+                    rules: rules.clone(),
+                }
+            })
+            .collect();
+        standings.sort_by(|a, b| a.penalty.cmp(&b.penalty).then_with(|| a.name.cmp(&b.name)));
+        for (index, standing) in standings.iter_mut().enumerate() {
+            standing.pos = index + 1;
+        }
+        standings
+    }
+
+    pub fn feed(&self) -> ScoreboardFeed {
+        ScoreboardFeed { success: true, data: self.standings() }
+    }
+
+    /// CSV 형식 순위 (머리글 포함)
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("pos,team,score,penalty,detections\n");
+        for standing in self.standings() {
+            csv.push_str(&format!("{},{},{},{},{}\n",
+                standing.pos, csv_field(&standing.name), standing.score, standing.penalty, standing.detections));
+        }
+        csv
+    }
+
+    /// 순위를 파일로 내보내기 (확장자가 .csv 면 CSV, 아니면 JSON 피드)
+    pub fn export(&self, path: &Path) -> Result<()> {
+        let content = if path.extension().is_some_and(|ext| ext == "csv") {
+            self.to_csv()
+        } else {
+            serde_json::to_string_pretty(&self.feed())?
+        };
+
+        // 스코어보드가 반쯤 쓰인 파일을 읽지 않도록 임시 파일에 쓴 뒤 교체
+        let temp = path.with_extension("tmp");
+        std::fs::write(&temp, content)?;
+        std::fs::rename(&temp, path)?;
+        Ok(())
+    }
+}
+
+/// 쉼표, 따옴표, 줄바꿈이 있으면 따옴표로 감싼 CSV 필드
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugins::reverse_shell::EventType;
+    use crate::process_info::ProcessInfo;
+
+    fn config() -> ScoringConfig {
+        ScoringConfig {
+            host: Some("vuln-3".to_string()),
+            teams: vec![
+                TeamMapping { team: "team-3".to_string(), hosts: vec!["vuln-3".to_string()], cgroups: Vec::new() },
+                TeamMapping {
+                    team: "team-4".to_string(),
+                    hosts: vec!["vuln-4".to_string()],
+                    cgroups: vec!["/docker/team4".to_string()],
+                },
+            ],
+            rule_points: BTreeMap::from([("memfd_executable".to_string(), 80)]),
+            ..ScoringConfig::default()
+        }
+    }
+
+    fn event(at: Instant, rule_id: &str, severity: Severity, cgroup: Option<&str>) -> ReverseShellEvent {
+        ReverseShellEvent {
+            timestamp: at,
+            event_type: EventType::ReverseShellDetected,
+            severity,
+            rule_id: rule_id.to_string(),
+            details: String::new(),
+            connection_info: None,
+            process: cgroup.map(|cgroup| ProcessInfo { cgroup: Some(cgroup.to_string()), ..ProcessInfo::default() }),
+            incident_id: None,
+            round_id: None,
+            techniques: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_team_mapping_and_cooldown() {
+        let mut scoreboard = Scoreboard::new(config());
+        let start = Instant::now();
+        let at = |secs: u64| start + Duration::from_secs(secs);
+
+        // 호스트 이름으로 team-3, cgroup 접두사로 team-4
+        assert_eq!(scoreboard.record(&event(at(0), "suspicious_connection", Severity::Critical, None)),
+            Some(("team-3".to_string(), 50)));
+        assert_eq!(scoreboard.record(&event(at(0), "memfd_executable", Severity::Critical, Some("/docker/team4/web"))),
+            Some(("team-4".to_string(), 80)));
+
+        // 쿨다운 안의 같은 규칙은 탐지 수만 늘어남
+        assert_eq!(scoreboard.record(&event(at(30), "suspicious_connection", Severity::Critical, None)),
+            Some(("team-3".to_string(), 0)));
+        assert_eq!(scoreboard.record(&event(at(61), "suspicious_connection", Severity::Critical, None)),
+            Some(("team-3".to_string(), 50)));
+
+        // 대응 감사 이벤트는 감점하지 않음
+        assert_eq!(scoreboard.record(&event(at(62), "response_action", Severity::Critical, None)), None);
+
+        let standings = scoreboard.standings();
+        assert_eq!((standings[0].name.as_str(), standings[0].score, standings[0].detections), ("team-4", -80, 1));
+        assert_eq!((standings[1].pos, standings[1].penalty, standings[1].detections), (2, 100, 3));
+        assert_eq!(standings[1].rules["suspicious_connection"], RuleScore { detections: 3, penalty: 100 });
+
+        // 팀이 없는 호스트에서는 아무것도 기록하지 않음
+        let mut other = Scoreboard::new(ScoringConfig { host: Some("scorebot".to_string()), ..config() });
+        assert_eq!(other.record(&event(at(0), "suspicious_connection", Severity::Critical, None)), None);
+
+        // 접두사는 경로 단위로 비교하므로 /docker/team40 은 team-4 가 아님
+        assert_eq!(other.record(&event(at(0), "memfd_executable", Severity::Critical, Some("/docker/team40/web"))), None);
+        assert_eq!(other.record(&event(at(0), "memfd_executable", Severity::Critical, Some("/docker/team4"))),
+            Some(("team-4".to_string(), 80)));
+    }
+
+    #[test]
+    fn test_caps_and_export() {
+        let mut scoreboard = Scoreboard::new(ScoringConfig {
+            cooldown_secs: 0,
+            rule_cap: Some(120),
+            team_cap: Some(150),
+            ..config()
+        });
+        let at = Instant::now();
+
+        let penalties: Vec<u64> = ["memfd_executable", "memfd_executable", "kernel_memfd_create", "kernel_memfd_create"]
+            .iter()
+            .filter_map(|rule_id| scoreboard.record(&event(at, rule_id, Severity::High, None)))
+            .map(|(_, points)| points)
+            .collect();
+        assert_eq!(penalties, vec![80, 40, 20, 10]);
+
+        let feed = serde_json::to_value(scoreboard.feed()).unwrap();
+        assert_eq!(feed["success"], true);
+        assert_eq!(feed["data"][0]["name"], "team-4");
+        assert_eq!(feed["data"][1]["score"], -150);
+        assert_eq!(scoreboard.to_csv(), "pos,team,score,penalty,detections\n1,team-4,0,0,0\n2,team-3,-150,150,4\n");
+        assert_eq!(csv_field("red, \"blue\""), "\"red, \"\"blue\"\"\"");
+
+        let path = std::env::temp_dir().join(format!("scoreboard-test-{}.csv", std::process::id()));
+        scoreboard.export(&path).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), scoreboard.to_csv());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
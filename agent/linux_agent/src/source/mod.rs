@@ -0,0 +1,327 @@
+pub mod ebpf;
+pub mod procfs;
+pub mod record;
+pub mod replay;
+
+use std::net::Ipv6Addr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use anyhow::Result;
+use aya::Bpf;
+use serde::{Deserialize, Serialize};
+
+use procfs::ProcRoot;
+use tokio::sync::mpsc::Sender;
+use tokio::task::JoinHandle;
+
+use ebpf::VerdictMaps;
+
+/// 이벤트 소스에서 분석기로 가는 채널 크기
+pub const SOURCE_CHANNEL_SIZE: usize = 4096;
+
+/// 수집 시각이 붙은 이벤트 (기록 파일의 한 줄)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimedEvent {
+    /// 이벤트 소스 시작 후 경과 시간 (ms)
+    pub offset_ms: u64,
+    pub event: SourceEvent,
+}
+
+/// 탐지 로직에 입력되는 이벤트
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "data", rename_all = "snake_case")]
+pub enum SourceEvent {
+    /// ESTABLISHED 연결 스캔 결과
+    ConnectionScan(Vec<SocketSnapshot>),
+    /// LISTEN 소켓 스캔 결과 (전체 목록)
+    ListenScan(Vec<SocketSnapshot>),
+    /// 프로세스 스캔 결과 (전체 목록)
+    ProcessScan(Vec<ProcessSnapshot>),
+    /// eBPF 프로그램이 보낸 이벤트
+    Kernel(KernelEvent),
+}
+
+/// 소켓 정보 (/proc/net/tcp 한 줄)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SocketSnapshot {
+    pub local_addr: u32,
+    /// IPv4 로 나타낼 수 없는 IPv6 로컬 주소 (있으면 local_addr 는 0)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub local_addr6: Option<Ipv6Addr>,
+    pub local_port: u16,
+    pub remote_addr: u32,
+    /// IPv4 로 나타낼 수 없는 IPv6 원격 주소 (있으면 remote_addr 는 0)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remote_addr6: Option<Ipv6Addr>,
+    pub remote_port: u16,
+    pub pid: u32,
+    pub process_name: String,
+}
+
+/// 프로세스 정보 (/proc/<pid>)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProcessSnapshot {
+    pub pid: u32,
+    /// cmdline 의 첫 번째 토큰
+    pub name: String,
+    /// /proc/<pid>/exe 링크 대상
+    pub exe: Option<String>,
+    /// 표준 입력과 출력이 소켓인지 (dup2 로 소켓을 연결한 리버스 쉘)
+    #[serde(default)]
+    pub stdio_socket: bool,
+    /// 부팅 후 프로세스 시작 시각 (clock ticks, 알 수 없으면 0, pid 재사용 구분용)
+    #[serde(default)]
+    pub start_time: u64,
+}
+
+/// 커널 이벤트 종류 (eBPF reverse_shell_event.event_type)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KernelEventKind {
+    SuspiciousPort,
+    ExternalConnection,
+    SuspiciousExec,
+    Socket,
+    Connect,
+    Listen,
+    MemfdCreate,
+    Execveat,
+    /// 소켓을 표준 입출력 (fd 0~2) 으로 복제 (remote_port 에 대상 fd)
+    StdioRedirect,
+    /// LSM 프로그램이 차단 목록의 포트, 주소 대역으로의 연결을 거부
+    ConnectDenied,
+    /// LSM 프로그램이 차단 목록의 실행 파일 실행을 거부
+    ExecDenied,
+    Unknown(u8),
+}
+
+/// eBPF 프로그램이 보낸 이벤트
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KernelEvent {
+    pub kind: KernelEventKind,
+    /// 0: Low, 1: Medium, 2: High, 3: Critical
+    pub severity: u8,
+    pub pid: u32,
+    pub local_addr: u32,
+    pub remote_addr: u32,
+    pub local_port: u16,
+    pub remote_port: u16,
+    /// bpf_ktime_get_ns() 값
+    pub timestamp_ns: u64,
+}
+
+impl KernelEventKind {
+    pub fn from_raw(event_type: u8) -> Self {
+        match event_type {
+            1 => Self::SuspiciousPort,
+            2 => Self::ExternalConnection,
+            3 => Self::SuspiciousExec,
+            4 => Self::Socket,
+            5 => Self::Connect,
+            6 => Self::Listen,
+            7 => Self::MemfdCreate,
+            8 => Self::Execveat,
+            9 => Self::StdioRedirect,
+            10 => Self::ConnectDenied,
+            11 => Self::ExecDenied,
+            other => Self::Unknown(other),
+        }
+    }
+}
+
+/// 이벤트 소스 (eBPF, procfs, 재생)
+pub trait EventSource {
+    /// 소스 이름
+    fn name(&self) -> &'static str;
+
+    /// 프로세스 정보를 보강할 /proc (재생 시에는 현재 호스트의 프로세스가 아니므로 None)
+    fn proc_root(&self) -> Option<ProcRoot> {
+        Some(ProcRoot::system())
+    }
+
+    /// 수집 시작 (수집 태스크는 내부에서 실행)
+    fn start(self: Box<Self>, tx: EventSender) -> Result<SourceHandle>;
+}
+
+/// 이벤트에 수집 시각을 붙여 분석기로 보내는 채널
+#[derive(Clone)]
+pub struct EventSender {
+    tx: Sender<TimedEvent>,
+    started: Instant,
+    metrics: Arc<SourceMetrics>,
+}
+
+/// 이벤트 소스 지표 (수집 태스크가 기록하고 메트릭 엔드포인트가 조회)
+#[derive(Debug, Default)]
+pub struct SourceMetrics {
+    /// perf 버퍼에서 읽은 커널 이벤트 수
+    pub kernel_events_read: AtomicU64,
+    /// perf 버퍼가 가득 차서 잃은 커널 이벤트 수
+    pub kernel_events_lost: AtomicU64,
+    /// 연결 스캔 (/proc/net/tcp, udp)
+    pub network_connection_scans: ScanTimer,
+    /// 리스닝 소켓 스캔
+    pub listen_socket_scans: ScanTimer,
+    /// 프로세스 스캔
+    pub process_creation_scans: ScanTimer,
+}
+
+/// 스캔 횟수 및 소요 시간 누적
+#[derive(Debug, Default)]
+pub struct ScanTimer {
+    count: AtomicU64,
+    total_micros: AtomicU64,
+}
+
+impl ScanTimer {
+    /// 스캔 한 번의 소요 시간 기록
+    pub fn observe(&self, elapsed: Duration) {
+        self.count.fetch_add(1, Ordering::Relaxed);
+        self.total_micros.fetch_add(elapsed.as_micros() as u64, Ordering::Relaxed);
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count.load(Ordering::Relaxed)
+    }
+
+    /// 누적 소요 시간 (초)
+    pub fn total_seconds(&self) -> f64 {
+        self.total_micros.load(Ordering::Relaxed) as f64 / 1_000_000.0
+    }
+}
+
+impl EventSender {
+    pub fn new(tx: Sender<TimedEvent>, metrics: Arc<SourceMetrics>) -> Self {
+        Self { tx, started: Instant::now(), metrics }
+    }
+
+    /// 수집 지표
+    pub fn metrics(&self) -> &SourceMetrics {
+        &self.metrics
+    }
+
+    /// 현재 시각으로 이벤트 전송 (분석기가 종료되었으면 false)
+    pub async fn send(&self, event: SourceEvent) -> bool {
+        let offset_ms = self.started.elapsed().as_millis() as u64;
+        self.send_at(offset_ms, event).await
+    }
+
+    /// 지정한 시각으로 이벤트 전송 (재생용)
+    pub async fn send_at(&self, offset_ms: u64, event: SourceEvent) -> bool {
+        self.tx.send(TimedEvent { offset_ms, event }).await.is_ok()
+    }
+}
+
+/// 실행 중인 이벤트 소스 (drop 되면 eBPF 프로그램이 분리됨)
+pub struct SourceHandle {
+    pub name: &'static str,
+    pub bpf: Option<Bpf>,
+    /// 커널 후킹 부착 결과 (eBPF 소스만 해당)
+    pub hooks: Vec<HookStatus>,
+    /// 대응 엔진용 XDP 차단 맵 (eBPF 소스이고 XDP 가 붙은 경우만)
+    pub verdicts: Option<VerdictMaps>,
+    pub tasks: Vec<JoinHandle<()>>,
+}
+
+/// 커널 후킹 종류
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HookKind {
+    Xdp,
+    TracePoint,
+    KProbe,
+    Lsm,
+}
+
+/// 커널 후킹 부착 결과 (시작 시 기능 확인용)
+#[derive(Debug, Clone, Serialize)]
+pub struct HookStatus {
+    /// eBPF 프로그램 이름
+    pub program: &'static str,
+    pub kind: HookKind,
+    /// 부착 대상 (인터페이스, tracepoint, 커널 함수)
+    pub target: String,
+    /// 부착 실패 사유
+    pub error: Option<String>,
+}
+
+impl HookStatus {
+    pub fn new(program: &'static str, kind: HookKind, target: String, result: Result<()>) -> Self {
+        Self {
+            program,
+            kind,
+            target,
+            error: result.err().map(|e| e.to_string()),
+        }
+    }
+
+    pub fn is_attached(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+impl SourceHandle {
+    /// 커널 후킹 요약 (예: "7/8 attached, failed: reverse_shell_detector")
+    pub fn hook_summary(&self) -> Option<String> {
+        if self.hooks.is_empty() {
+            return None;
+        }
+
+        let attached = self.hooks.iter().filter(|hook| hook.is_attached()).count();
+        let failed: Vec<&str> = self.hooks.iter()
+            .filter(|hook| !hook.is_attached())
+            .map(|hook| hook.program)
+            .collect();
+
+        Some(if failed.is_empty() {
+            format!("{}/{} attached", attached, self.hooks.len())
+        } else {
+            format!("{}/{} attached, failed: {}", attached, self.hooks.len(), failed.join(", "))
+        })
+    }
+}
+
+impl HookKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HookKind::Xdp => "xdp",
+            HookKind::TracePoint => "tracepoint",
+            HookKind::KProbe => "kprobe",
+            HookKind::Lsm => "lsm",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hook_summary() {
+        let mut handle = SourceHandle {
+            name: "ebpf",
+            bpf: None,
+            hooks: Vec::new(),
+            verdicts: None,
+            tasks: Vec::new(),
+        };
+        assert_eq!(handle.hook_summary(), None);
+
+        handle.hooks = vec![
+            HookStatus::new("reverse_shell_detector", HookKind::Xdp, "eth0".to_string(), Err(anyhow::anyhow!("No such device"))),
+            HookStatus::new("trace_listen", HookKind::TracePoint, "syscalls/sys_enter_listen".to_string(), Ok(())),
+        ];
+        assert_eq!(handle.hook_summary().unwrap(), "1/2 attached, failed: reverse_shell_detector");
+    }
+
+    #[test]
+    fn test_scan_timer() {
+        let timer = ScanTimer::default();
+        timer.observe(Duration::from_millis(250));
+        timer.observe(Duration::from_millis(750));
+
+        assert_eq!(timer.count(), 2);
+        assert_eq!(timer.total_seconds(), 1.0);
+    }
+}
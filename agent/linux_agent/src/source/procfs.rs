@@ -0,0 +1,632 @@
+use std::collections::HashMap;
+use std::net::Ipv6Addr;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use anyhow::Result;
+use log::{debug, error, info};
+use tokio::time::sleep;
+
+use crate::process_info::{self, ProcStat};
+use super::{EventSender, EventSource, ProcessSnapshot, SocketSnapshot, SourceEvent, SourceHandle};
+
+/// 연결 스캔에 사용하는 /proc/net 테이블
+const CONNECTION_TABLES: [&str; 4] = ["tcp", "tcp6", "udp", "udp6"];
+/// 리스닝 소켓 스캔에 사용하는 /proc/net 테이블
+const LISTEN_TABLES: [&str; 2] = ["tcp", "tcp6"];
+/// IPv4-mapped IPv6 주소 (::ffff:a.b.c.d) 의 앞 96비트
+const V4_MAPPED_PREFIX: &str = "0000000000000000FFFF0000";
+
+/// /proc 파일 시스템 읽기 (테스트에서는 픽스처 디렉토리를 가리킴)
+///
+/// 스캔 중에 프로세스가 종료되면 해당 파일 읽기가 실패하므로, 프로세스 단위 읽기는
+/// 모두 Option 으로 반환하고 호출하는 쪽에서 건너뜀
+#[derive(Debug, Clone)]
+pub struct ProcRoot {
+    root: PathBuf,
+}
+
+/// 소켓 소유 프로세스 (pid, 프로세스 이름)
+type SocketOwners = HashMap<String, (u32, String)>;
+/// 소켓 주소 (IPv4 주소, IPv4 로 나타낼 수 없는 IPv6 주소, 포트)
+type Endpoint = (u32, Option<Ipv6Addr>, u16);
+/// 스캔 결과와 소요 시간
+type TimedScan = (Vec<SocketSnapshot>, Duration);
+
+impl ProcRoot {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// 현재 호스트의 /proc
+    pub fn system() -> Self {
+        Self::new("/proc")
+    }
+
+    /// 실행 중인 프로세스 ID 목록
+    pub fn pids(&self) -> Result<Vec<u32>> {
+        let mut pids = Vec::new();
+        for entry in std::fs::read_dir(&self.root)?.flatten() {
+            if let Some(pid) = entry.file_name().to_str().and_then(|name| name.parse::<u32>().ok()) {
+                pids.push(pid);
+            }
+        }
+        pids.sort_unstable();
+        Ok(pids)
+    }
+
+    /// /proc/net/<table> 읽기 (IPv6 가 비활성화된 경우 등 없으면 None)
+    pub fn net_table(&self, table: &str) -> Option<String> {
+        std::fs::read_to_string(self.root.join("net").join(table)).ok()
+    }
+
+    /// cmdline 의 첫 번째 토큰 (커널 스레드나 argv 를 지운 프로세스는 빈 문자열)
+    pub fn cmdline(&self, pid: u32) -> Option<String> {
+        let cmdline = std::fs::read_to_string(self.pid_path(pid).join("cmdline")).ok()?;
+        Some(cmdline.split('\0').next().unwrap_or_default().to_string())
+    }
+
+    /// status 의 Name (comm)
+    pub fn status_name(&self, pid: u32) -> Option<String> {
+        let status = std::fs::read_to_string(self.pid_path(pid).join("status")).ok()?;
+        status.lines()
+            .find_map(|line| line.strip_prefix("Name:"))
+            .map(|name| name.trim().to_string())
+    }
+
+    /// 프로세스 이름 (cmdline 이 비어 있으면 status 의 Name 사용)
+    pub fn process_name(&self, pid: u32) -> Option<String> {
+        match self.cmdline(pid) {
+            Some(name) if !name.is_empty() => Some(name),
+            _ => self.status_name(pid),
+        }
+    }
+
+    /// stat 의 부모 pid, 시작 시각 등
+    pub(crate) fn stat(&self, pid: u32) -> Option<ProcStat> {
+        let stat = std::fs::read_to_string(self.pid_path(pid).join("stat")).ok()?;
+        process_info::parse_stat(&stat).ok()
+    }
+
+    /// 부팅 후 프로세스 시작 시각 (stat 22번째 필드)
+    pub fn start_time(&self, pid: u32) -> Option<u64> {
+        self.stat(pid).map(|stat| stat.start_time)
+    }
+
+    /// exe 링크 대상 (커널 스레드나 이미 종료된 프로세스는 None)
+    pub fn exe(&self, pid: u32) -> Option<String> {
+        std::fs::read_link(self.pid_path(pid).join("exe"))
+            .ok()
+            .map(|target| target.to_string_lossy().to_string())
+    }
+
+    /// 프로세스가 연 소켓의 inode 목록 (fd 링크는 "socket:[inode]" 형식)
+    pub fn socket_inodes(&self, pid: u32) -> Vec<String> {
+        let Ok(links) = std::fs::read_dir(self.pid_path(pid).join("fd")) else {
+            return Vec::new();
+        };
+
+        links.flatten()
+            .filter_map(|link| std::fs::read_link(link.path()).ok())
+            .filter_map(|target| {
+                let target = target.to_string_lossy();
+                target.strip_prefix("socket:[")
+                    .and_then(|rest| rest.strip_suffix(']'))
+                    .map(|inode| inode.to_string())
+            })
+            .collect()
+    }
+
+    /// 표준 입력 (fd 0) 과 표준 출력 (fd 1) 이 모두 소켓인지
+    pub fn stdio_is_socket(&self, pid: u32) -> bool {
+        ["0", "1"].iter().all(|fd| {
+            std::fs::read_link(self.pid_path(pid).join("fd").join(fd))
+                .is_ok_and(|target| target.to_string_lossy().starts_with("socket:["))
+        })
+    }
+
+    /// 소켓 inode 별 소유 프로세스 (스캔 한 번에 /proc 을 한 번만 순회)
+    fn socket_owners(&self) -> Result<SocketOwners> {
+        let mut owners = HashMap::new();
+
+        for pid in self.pids()? {
+            let inodes = self.socket_inodes(pid);
+            if inodes.is_empty() {
+                continue;
+            }
+
+            // fd 를 읽은 뒤 종료된 프로세스는 이름 없이 pid 만 남김
+            let process_name = self.process_name(pid).unwrap_or_else(|| "unknown".to_string());
+            for inode in inodes {
+                owners.entry(inode).or_insert_with(|| (pid, process_name.clone()));
+            }
+        }
+
+        Ok(owners)
+    }
+
+    /// /proc/<pid> 경로
+    pub fn pid_path(&self, pid: u32) -> PathBuf {
+        self.root.join(pid.to_string())
+    }
+}
+
+impl Default for ProcRoot {
+    fn default() -> Self {
+        Self::system()
+    }
+}
+
+/// /proc 폴링 기반 이벤트 소스 (권한이 없거나 eBPF 를 쓸 수 없는 환경용)
+pub struct ProcfsSource {
+    /// 읽을 /proc 경로
+    pub proc: ProcRoot,
+    /// 연결 및 리스닝 소켓 스캔 주기
+    pub socket_interval: Duration,
+    /// 프로세스 스캔 주기
+    pub process_interval: Duration,
+}
+
+impl ProcfsSource {
+    pub fn new() -> Self {
+        Self::with_root(ProcRoot::system())
+    }
+
+    /// 지정한 /proc 경로를 읽는 소스 생성
+    pub fn with_root(proc: ProcRoot) -> Self {
+        Self {
+            proc,
+            socket_interval: Duration::from_secs(5),
+            process_interval: Duration::from_secs(10),
+        }
+    }
+
+    /// 연결과 리스닝 소켓을 함께 스캔 (소켓 소유 프로세스는 /proc 을 한 번만 순회해 공유)
+    ///
+    /// 각 스캔의 소요 시간을 함께 반환하며, 소유 프로세스 순회는 연결 스캔 시간에 포함
+    pub fn scan_sockets(&self) -> Result<(TimedScan, TimedScan)> {
+        let started = Instant::now();
+        let owners = self.proc.socket_owners()?;
+        let connections = self.connections(&owners);
+        let connections_elapsed = started.elapsed();
+
+        let started = Instant::now();
+        let listeners = self.listeners(&owners);
+        Ok(((connections, connections_elapsed), (listeners, started.elapsed())))
+    }
+
+    /// 네트워크 연결 (TCP ESTABLISHED, connect 된 UDP)
+    fn connections(&self, owners: &SocketOwners) -> Vec<SocketSnapshot> {
+        let mut connections = Vec::new();
+
+        for table in CONNECTION_TABLES {
+            let Some(content) = self.proc.net_table(table) else { continue };
+
+            for line in content.lines().skip(1) {
+                let Some(((local_addr, local_addr6, local_port), (remote_addr, remote_addr6, remote_port), inode)) = Self::parse_connection_line(line) else {
+                    continue;
+                };
+
+                // 연결 직후 종료된 소켓은 소유 프로세스를 찾지 못할 수 있음
+                let (pid, process_name) = owners.get(&inode)
+                    .cloned()
+                    .unwrap_or((0, "unknown".to_string()));
+
+                connections.push(SocketSnapshot {
+                    local_addr,
+                    local_addr6,
+                    local_port,
+                    remote_addr,
+                    remote_addr6,
+                    remote_port,
+                    pid,
+                    process_name,
+                });
+            }
+        }
+
+        connections
+    }
+
+    /// 리스닝 소켓
+    fn listeners(&self, owners: &SocketOwners) -> Vec<SocketSnapshot> {
+        let mut listeners = Vec::new();
+
+        for table in LISTEN_TABLES {
+            let Some(content) = self.proc.net_table(table) else { continue };
+
+            for line in content.lines().skip(1) {
+                if let Some((local_addr, local_addr6, local_port, inode)) = Self::parse_listen_line(line) {
+                    let (pid, process_name) = owners.get(&inode)
+                        .cloned()
+                        .unwrap_or((0, "unknown".to_string()));
+
+                    listeners.push(SocketSnapshot {
+                        local_addr,
+                        local_addr6,
+                        local_port,
+                        remote_addr: 0,
+                        remote_addr6: None,
+                        remote_port: 0,
+                        pid,
+                        process_name,
+                    });
+                }
+            }
+        }
+
+        listeners
+    }
+
+    /// 프로세스 스캔
+    pub fn scan_processes(&self) -> Result<Vec<ProcessSnapshot>> {
+        let mut processes = Vec::new();
+
+        for pid in self.proc.pids()? {
+            // 스캔 중 종료된 프로세스는 cmdline 을 읽을 수 없음
+            let Some(cmdline) = self.proc.cmdline(pid) else { continue };
+            let exe = self.proc.exe(pid);
+
+            // 커널 스레드는 cmdline 과 exe 링크가 모두 없음
+            if cmdline.is_empty() && exe.is_none() {
+                continue;
+            }
+
+            let name = if cmdline.is_empty() {
+                self.proc.status_name(pid).unwrap_or_default()
+            } else {
+                cmdline
+            };
+
+            let stdio_socket = self.proc.stdio_is_socket(pid);
+            let start_time = self.proc.start_time(pid).unwrap_or(0);
+            processes.push(ProcessSnapshot { pid, name, exe, stdio_socket, start_time });
+        }
+
+        Ok(processes)
+    }
+
+    /// 연결 상태 라인 파싱 (로컬 주소, 원격 주소, inode)
+    fn parse_connection_line(line: &str) -> Option<(Endpoint, Endpoint, String)> {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if parts.len() < 10 {
+            return None;
+        }
+
+        // ESTABLISHED 상태(01)만 처리 (UDP 는 connect 된 소켓)
+        if parts[3] != "01" {
+            return None;
+        }
+
+        let local = match Self::parse_addr_port(parts[1]) {
+            Ok(addr_port) => addr_port,
+            Err(e) => {
+                debug!("Skipping connection line ({}): {}", e, line.trim());
+                return None;
+            }
+        };
+        let remote = match Self::parse_addr_port(parts[2]) {
+            Ok(addr_port) => addr_port,
+            Err(e) => {
+                debug!("Skipping connection line ({}): {}", e, line.trim());
+                return None;
+            }
+        };
+
+        Some((local, remote, parts[9].to_string()))
+    }
+
+    /// LISTEN 상태 라인 파싱 (IPv4 주소, IPv6 주소, 포트, inode)
+    fn parse_listen_line(line: &str) -> Option<(u32, Option<Ipv6Addr>, u16, String)> {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if parts.len() < 10 {
+            return None;
+        }
+
+        // LISTEN 상태(0A)만 처리
+        if parts[3] != "0A" {
+            return None;
+        }
+
+        let (local_addr, local_addr6, local_port) = Self::parse_addr_port(parts[1]).ok()?;
+        Some((local_addr, local_addr6, local_port, parts[9].to_string()))
+    }
+
+    /// 주소:포트 파싱
+    fn parse_addr_port(addr_port: &str) -> Result<Endpoint> {
+        let parts: Vec<&str> = addr_port.split(':').collect();
+        if parts.len() != 2 {
+            return Err(anyhow::anyhow!("Invalid address:port format"));
+        }
+
+        let (addr, addr6) = Self::parse_hex_addr(parts[0])?;
+        let port = u16::from_str_radix(parts[1], 16)?;
+
+        Ok((addr, addr6, port))
+    }
+
+    /// 16진수 주소 변환 (IPv4-mapped 가 아닌 IPv6 주소는 :: 를 포함해 IPv4 와 구분되도록 IPv6 로)
+    fn parse_hex_addr(hex: &str) -> Result<(u32, Option<Ipv6Addr>)> {
+        if hex.len() == 32 && !hex[..24].eq_ignore_ascii_case(V4_MAPPED_PREFIX) {
+            Ok((0, Some(Self::hex_to_ipv6(hex)?)))
+        } else {
+            Ok((Self::hex_to_ip_u32(hex)?, None))
+        }
+    }
+
+    /// 16진수 주소를 IP 주소로 변환 (u32, IPv6 는 IPv4-mapped 주소만 지원)
+    fn hex_to_ip_u32(hex: &str) -> Result<u32> {
+        let hex = match hex.len() {
+            8 => hex,
+            32 if hex.eq_ignore_ascii_case("00000000000000000000000000000000") => "00000000",
+            32 if hex[..24].eq_ignore_ascii_case(V4_MAPPED_PREFIX) => &hex[24..],
+            32 => return Err(anyhow::anyhow!("IPv6 address is not IPv4-mapped")),
+            _ => return Err(anyhow::anyhow!("Invalid hex address length")),
+        };
+
+        let bytes: Vec<u8> = (0..8)
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i+2], 16))
+            .collect::<Result<Vec<u8>, _>>()?;
+
+        // 리틀 엔디안으로 저장된 주소를 변환
+        Ok(((bytes[3] as u32) << 24) |
+           ((bytes[2] as u32) << 16) |
+           ((bytes[1] as u32) << 8) |
+           (bytes[0] as u32))
+    }
+
+    /// 16진수 주소를 IPv6 주소로 변환 (32비트 워드마다 리틀 엔디안)
+    fn hex_to_ipv6(hex: &str) -> Result<Ipv6Addr> {
+        if hex.len() != 32 {
+            return Err(anyhow::anyhow!("Invalid hex address length"));
+        }
+
+        let mut octets = [0u8; 16];
+        for (word, chunk) in octets.chunks_mut(4).enumerate() {
+            let value = u32::from_str_radix(&hex[word * 8..word * 8 + 8], 16)?;
+            chunk.copy_from_slice(&value.to_le_bytes());
+        }
+        Ok(Ipv6Addr::from(octets))
+    }
+}
+
+/// /proc 순회는 파일 읽기가 많으므로 블로킹 스레드에서 실행
+async fn blocking_scan<T: Send + 'static>(scan: impl FnOnce() -> Result<T> + Send + 'static) -> Result<T> {
+    tokio::task::spawn_blocking(scan).await?
+}
+
+impl Default for ProcfsSource {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EventSource for ProcfsSource {
+    fn name(&self) -> &'static str {
+        "procfs"
+    }
+
+    fn proc_root(&self) -> Option<ProcRoot> {
+        Some(self.proc.clone())
+    }
+
+    fn start(self: Box<Self>, tx: EventSender) -> Result<SourceHandle> {
+        info!("Starting procfs event source");
+
+        let source = Arc::new(*self);
+        let mut tasks = Vec::new();
+
+        // 연결 및 리스닝 소켓 스캔
+        let socket_tx = tx.clone();
+        let socket_source = Arc::clone(&source);
+        tasks.push(tokio::spawn(async move {
+            loop {
+                let scan_source = Arc::clone(&socket_source);
+                match blocking_scan(move || scan_source.scan_sockets()).await {
+                    Ok(((connections, connections_elapsed), (listeners, listeners_elapsed))) => {
+                        socket_tx.metrics().network_connection_scans.observe(connections_elapsed);
+                        socket_tx.metrics().listen_socket_scans.observe(listeners_elapsed);
+                        if !socket_tx.send(SourceEvent::ConnectionScan(connections)).await
+                            || !socket_tx.send(SourceEvent::ListenScan(listeners)).await
+                        {
+                            break;
+                        }
+                    }
+                    Err(e) => error!("Error scanning sockets: {}", e),
+                }
+
+                sleep(socket_source.socket_interval).await;
+            }
+        }));
+
+        // 프로세스 스캔
+        tasks.push(tokio::spawn(async move {
+            loop {
+                let started = Instant::now();
+                let scan_source = Arc::clone(&source);
+                let processes = blocking_scan(move || scan_source.scan_processes()).await;
+                tx.metrics().process_creation_scans.observe(started.elapsed());
+                match processes {
+                    Ok(processes) => {
+                        if !tx.send(SourceEvent::ProcessScan(processes)).await {
+                            break;
+                        }
+                    }
+                    Err(e) => error!("Error scanning process creation: {}", e),
+                }
+
+                sleep(source.process_interval).await;
+            }
+        }));
+
+        Ok(SourceHandle {
+            name: "procfs",
+            bpf: None,
+            hooks: Vec::new(),
+            verdicts: None,
+            tasks,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// tests/fixtures/proc 픽스처를 읽는 소스
+    fn fixture_source() -> ProcfsSource {
+        let root = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/proc");
+        ProcfsSource::with_root(ProcRoot::new(root))
+    }
+
+    #[test]
+    fn test_parse_listen_line() {
+        let listen = "   0: 00000000:115C 00000000:0000 0A 00000000:00000000 00:00000000 00000000     0        0 31337 1 0000000000000000 100 0 0 10 0";
+        assert_eq!(
+            ProcfsSource::parse_listen_line(listen),
+            Some((0, None, 4444, "31337".to_string()))
+        );
+
+        let listen6 = "   0: 00000000000000000000000000000000:0016 00000000000000000000000000000000:0000 0A 00000000:00000000 00:00000000 00000000     0        0 1234 1 0000000000000000 100 0 0 10 0";
+        assert_eq!(
+            ProcfsSource::parse_listen_line(listen6),
+            Some((0, Some(Ipv6Addr::UNSPECIFIED), 22, "1234".to_string()))
+        );
+
+        let loopback6 = "   1: 00000000000000000000000001000000:0016 00000000000000000000000000000000:0000 0A 00000000:00000000 00:00000000 00000000     0        0 1235 1 0000000000000000 100 0 0 10 0";
+        assert_eq!(
+            ProcfsSource::parse_listen_line(loopback6),
+            Some((0, Some(Ipv6Addr::LOCALHOST), 22, "1235".to_string()))
+        );
+        assert_eq!(
+            ProcfsSource::hex_to_ipv6("B80D0120000000000000000001000000").unwrap(),
+            "2001:db8::1".parse::<Ipv6Addr>().unwrap()
+        );
+
+        let established = "   1: 0100007F:115C 0100007F:A2F0 01 00000000:00000000 00:00000000 00000000     0        0 4321 1 0000000000000000 20 4 30 10 -1";
+        assert_eq!(ProcfsSource::parse_listen_line(established), None);
+    }
+
+    #[test]
+    fn test_parse_addr_port() {
+        assert_eq!(ProcfsSource::parse_addr_port("0100007F:115C").unwrap(), (0x7F000001, None, 4444));
+        assert!(ProcfsSource::parse_addr_port("0100007F").is_err());
+        assert_eq!(
+            ProcfsSource::parse_addr_port("0000000000000000FFFF00000A00000A:1F90").unwrap(),
+            (0x0A00000A, None, 8080)
+        );
+        assert_eq!(
+            ProcfsSource::parse_addr_port("B80D0120000000000000000001000000:0050").unwrap(),
+            (0, Some("2001:db8::1".parse().unwrap()), 80)
+        );
+    }
+
+    #[test]
+    fn test_parse_malformed_lines() {
+        let malformed = [
+            "",
+            "garbage",
+            "   4: 0100007F:1F90",
+            "   5: ZZZZZZZZ:0050 0A7100CB:0050 01 00000000:00000000 00:00000000 00000000     0        0 4002 1",
+            "   6: 0100007F:0050 0A7100CB 01 00000000:00000000 00:00000000 00000000     0        0 4003 1",
+            "   7: 0100007F:GGGG 0A7100CB:0050 01 00000000:00000000 00:00000000 00000000     0        0 4004 1",
+        ];
+
+        for line in malformed {
+            assert_eq!(ProcfsSource::parse_connection_line(line), None, "{:?}", line);
+            assert_eq!(ProcfsSource::parse_listen_line(line), None, "{:?}", line);
+        }
+    }
+
+    #[test]
+    fn test_scan_connections_fixture() {
+        let mut connections = fixture_source().scan_sockets().unwrap().0.0;
+        connections.sort_by_key(|conn| conn.local_port);
+
+        let summary: Vec<(u16, u16, u32, &str)> = connections.iter()
+            .map(|conn| (conn.local_port, conn.remote_port, conn.pid, conn.process_name.as_str()))
+            .collect();
+        assert_eq!(summary, vec![
+            // 소유 프로세스가 종료된 IPv6 연결
+            (22, 53248, 0, "unknown"),
+            // 웹 서비스의 IPv4-mapped tcp6 연결
+            (8080, 40000, 400, "/opt/service/bin/web"),
+            // 소유 프로세스가 종료된 연결
+            (50000, 443, 0, "unknown"),
+            // bash 리버스 쉘
+            (51234, 9001, 300, "bash"),
+            // connect 된 UDP 소켓
+            (53000, 53, 500, "nc"),
+        ]);
+        assert_eq!(connections[0].remote_addr6, Some("2001:db8::2".parse().unwrap()));
+        assert_eq!(connections[1].remote_addr6, None);
+        assert_eq!(connections[3].remote_addr, 0xCB00710A);
+    }
+
+    #[test]
+    fn test_scan_listeners_fixture() {
+        let mut listeners = fixture_source().scan_sockets().unwrap().1.0;
+        listeners.sort_by_key(|conn| conn.local_port);
+
+        let summary: Vec<(u16, u32, &str)> = listeners.iter()
+            .map(|conn| (conn.local_port, conn.pid, conn.process_name.as_str()))
+            .collect();
+        assert_eq!(summary, vec![
+            (22, 100, "/usr/sbin/sshd"),
+            (4444, 200, "nc"),
+            (8080, 400, "/opt/service/bin/web"),
+        ]);
+        assert_eq!(listeners[0].local_addr6, None);
+        assert_eq!(listeners[2].local_addr6, Some(Ipv6Addr::UNSPECIFIED));
+    }
+
+    #[test]
+    fn test_scan_processes_fixture() {
+        let processes = fixture_source().scan_processes().unwrap();
+        let summary: Vec<(u32, &str, Option<&str>)> = processes.iter()
+            .map(|process| (process.pid, process.name.as_str(), process.exe.as_deref()))
+            .collect();
+
+        // 커널 스레드(600)와 좀비(700)는 제외, argv 를 지운 프로세스(510)는 status 의 Name 사용
+        assert_eq!(summary, vec![
+            (100, "/usr/sbin/sshd", Some("/usr/sbin/sshd")),
+            (200, "nc", Some("/usr/bin/nc.openbsd")),
+            (300, "bash", Some("/usr/bin/bash")),
+            (400, "/opt/service/bin/web", Some("/opt/service/bin/web")),
+            (500, "nc", Some("/usr/bin/nc.openbsd")),
+            (510, "kworker/u8:2", Some("/memfd:payload (deleted)")),
+        ]);
+
+        // 표준 입출력이 소켓인 bash (300) 만 표시
+        let stdio_sockets: Vec<u32> = processes.iter().filter(|process| process.stdio_socket).map(|process| process.pid).collect();
+        assert_eq!(stdio_sockets, vec![300]);
+
+        // stat 이 있는 프로세스만 시작 시각을 알 수 있음
+        assert_eq!((processes[1].start_time, processes[0].start_time), (987654, 0));
+    }
+
+    #[test]
+    fn test_scan_tolerates_exited_processes() {
+        // readdir 이후 종료된 프로세스: pid 디렉토리만 남아 있거나 fd 링크가 끊긴 경우
+        let root = std::env::temp_dir().join(format!("procfs-race-test-{}", std::process::id()));
+        std::fs::create_dir_all(root.join("net")).unwrap();
+        std::fs::create_dir_all(root.join("4242")).unwrap();
+        std::fs::create_dir_all(root.join("4343/fd")).unwrap();
+        std::os::unix::fs::symlink("socket:[9999]", root.join("4343/fd/3")).unwrap();
+        std::fs::write(
+            root.join("net/tcp"),
+            "  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode\n   \
+             0: 00000000:115C 00000000:0000 0A 00000000:00000000 00:00000000 00000000     0        0 9999 1 0000000000000000 100 0 0 10 0\n",
+        ).unwrap();
+
+        let source = ProcfsSource::with_root(ProcRoot::new(&root));
+        assert!(source.scan_processes().unwrap().is_empty());
+        let ((connections, _), (listeners, _)) = source.scan_sockets().unwrap();
+        assert!(connections.is_empty());
+        assert_eq!(listeners.len(), 1);
+        assert_eq!((listeners[0].pid, listeners[0].process_name.as_str()), (4343, "unknown"));
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,72 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use anyhow::Result;
+use log::info;
+
+use super::TimedEvent;
+
+/// 분석기에 입력되는 이벤트를 JSON Lines 파일로 기록 (replay 소스로 재생 가능)
+pub struct StreamRecorder {
+    writer: BufWriter<File>,
+    count: u64,
+}
+
+impl StreamRecorder {
+    /// 기록 파일 생성 (기존 파일은 덮어씀)
+    pub fn create(path: &Path) -> Result<Self> {
+        let file = File::create(path)?;
+        info!("Recording source events to {}", path.display());
+
+        Ok(Self {
+            writer: BufWriter::new(file),
+            count: 0,
+        })
+    }
+
+    /// 이벤트 한 건 기록 (에이전트가 중단되어도 기록이 남도록 매번 flush)
+    pub fn write(&mut self, event: &TimedEvent) -> Result<()> {
+        serde_json::to_writer(&mut self.writer, event)?;
+        self.writer.write_all(b"\n")?;
+        self.writer.flush()?;
+        self.count += 1;
+        Ok(())
+    }
+
+    /// 기록한 이벤트 수
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::source::{KernelEvent, KernelEventKind, SourceEvent};
+    use crate::source::replay::ReplaySource;
+
+    #[test]
+    fn test_recorded_stream_round_trip() {
+        let path = std::env::temp_dir().join(format!("record-test-{}.jsonl", std::process::id()));
+        let event = TimedEvent {
+            offset_ms: 1500,
+            event: SourceEvent::Kernel(KernelEvent {
+                kind: KernelEventKind::MemfdCreate,
+                severity: 2,
+                pid: 31337,
+                local_addr: 0,
+                remote_addr: 0,
+                local_port: 0,
+                remote_port: 0,
+                timestamp_ns: 42,
+            }),
+        };
+
+        let mut recorder = StreamRecorder::create(&path).unwrap();
+        recorder.write(&event).unwrap();
+        assert_eq!(recorder.count(), 1);
+
+        assert_eq!(ReplaySource::read_events(&path).unwrap(), vec![event]);
+        std::fs::remove_file(path).unwrap();
+    }
+}
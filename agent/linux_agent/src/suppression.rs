@@ -0,0 +1,329 @@
+use std::net::Ipv4Addr;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+use anyhow::Result;
+use log::info;
+use serde::{Deserialize, Serialize};
+
+use crate::plugins::reverse_shell::ReverseShellEvent;
+
+/// 억제 규칙 (지정된 조건을 모두 만족하는 이벤트를 무시)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Suppression {
+    /// 규칙 식별자
+    pub id: String,
+    /// 탐지 규칙 ID
+    #[serde(default)]
+    pub rule_id: Option<String>,
+    /// 실행 파일 경로 (/proc/<pid>/exe)
+    #[serde(default)]
+    pub exe_path: Option<String>,
+    /// 실행 파일 SHA-256
+    #[serde(default)]
+    pub exe_sha256: Option<String>,
+    /// 실제 UID
+    #[serde(default)]
+    pub uid: Option<u32>,
+    /// cgroup 경로 접두사 (경로 단위로 비교, /ctf/team-1 은 /ctf/team-10 에 해당하지 않음)
+    #[serde(default)]
+    pub cgroup: Option<String>,
+    /// 원격 주소 대역 (예: 10.0.0.0/8)
+    #[serde(default)]
+    pub cidr: Option<String>,
+    /// 원격 포트 (리스닝 소켓은 로컬 포트)
+    #[serde(default)]
+    pub port: Option<u16>,
+    /// 만료 시각 (유닉스 초)
+    #[serde(default)]
+    pub expires_at: Option<u64>,
+    #[serde(default)]
+    pub comment: Option<String>,
+}
+
+/// 커널 allow 맵에 넣을 수 있는 규칙
+#[derive(Debug, Clone, PartialEq)]
+pub enum KernelAllowEntry {
+    Cidr { addr: Ipv4Addr, prefix_len: u32 },
+    Port(u16),
+    Uid(u32),
+}
+
+/// 억제 규칙 목록
+#[derive(Debug, Default)]
+pub struct SuppressionList {
+    rules: Vec<Suppression>,
+}
+
+impl Suppression {
+    /// 만료 여부 확인
+    pub fn is_expired(&self, now: u64) -> bool {
+        self.expires_at.is_some_and(|expires_at| now >= expires_at)
+    }
+
+    fn needs_process(&self) -> bool {
+        self.exe_path.is_some() || self.exe_sha256.is_some() || self.uid.is_some() || self.cgroup.is_some()
+    }
+
+    /// 커널 맵으로 옮길 수 있는 단일 조건 규칙인지 확인
+    fn kernel_allow_entry(&self) -> Option<KernelAllowEntry> {
+        // 만료되는 규칙은 유저스페이스에서만 처리
+        if self.expires_at.is_some()
+            || self.rule_id.is_some()
+            || self.exe_path.is_some()
+            || self.exe_sha256.is_some()
+            || self.cgroup.is_some()
+        {
+            return None;
+        }
+
+        match (&self.cidr, self.port, self.uid) {
+            (Some(cidr), None, None) => {
+                let (addr, prefix_len) = parse_cidr(cidr).ok()?;
+                Some(KernelAllowEntry::Cidr { addr, prefix_len })
+            }
+            (None, Some(port), None) => Some(KernelAllowEntry::Port(port)),
+            (None, None, Some(uid)) => Some(KernelAllowEntry::Uid(uid)),
+            _ => None,
+        }
+    }
+}
+
+impl SuppressionList {
+    /// 파일에서 억제 규칙 읽기 (JSON 배열)
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        let rules: Vec<Suppression> = serde_json::from_str(&content)?;
+
+        for rule in &rules {
+            if let Some(cidr) = &rule.cidr {
+                parse_cidr(cidr)?;
+            }
+        }
+
+        info!("Loaded {} suppression rules from {}", rules.len(), path.display());
+        Ok(Self::new(rules))
+    }
+
+    pub fn new(rules: Vec<Suppression>) -> Self {
+        Self { rules }
+    }
+
+    /// 전체 규칙
+    pub fn rules(&self) -> &[Suppression] {
+        &self.rules
+    }
+
+    /// 커널 allow 맵에 넣을 규칙 목록
+    pub fn kernel_allow_entries(&self) -> Vec<KernelAllowEntry> {
+        self.rules.iter().filter_map(|rule| rule.kernel_allow_entry()).collect()
+    }
+
+    /// 이벤트에 해당하는 억제 규칙 찾기
+    pub fn find_match(&self, event: &ReverseShellEvent) -> Option<&Suppression> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+        let (remote_addr, port) = match &event.connection_info {
+            // CIDR 규칙은 IPv4 전용이라 IPv6 원격 주소는 포트로만 비교
+            Some(conn) if conn.remote_addr6.is_some() => (None, Some(conn.remote_port)),
+            Some(conn) if conn.remote_addr != 0 => (Some(conn.remote_addr), Some(conn.remote_port)),
+            Some(conn) => (None, Some(conn.local_port)),
+            None => (None, None),
+        };
+
+        self.rules.iter().find(|rule| {
+            if rule.is_expired(now) {
+                return false;
+            }
+            if rule.rule_id.as_ref().is_some_and(|id| id != &event.rule_id) {
+                return false;
+            }
+            if rule.port.is_some_and(|p| Some(p) != port) {
+                return false;
+            }
+            if let Some(cidr) = &rule.cidr {
+                let in_range = match (parse_cidr(cidr), remote_addr) {
+                    (Ok((net, prefix_len)), Some(addr)) => cidr_contains(net, prefix_len, addr),
+                    _ => false,
+                };
+                if !in_range {
+                    return false;
+                }
+            }
+
+            if !rule.needs_process() {
+                return true;
+            }
+
+            // 프로세스 조건은 식별 정보가 있는 이벤트에만 적용
+            let Some(process) = &event.process else { return false };
+
+            if rule.exe_path.is_some() && rule.exe_path != process.exe {
+                return false;
+            }
+            if rule.uid.is_some_and(|uid| uid != process.uid) {
+                return false;
+            }
+            if let Some(prefix) = &rule.cgroup {
+                if !process.cgroup.as_ref().is_some_and(|c| cgroup_within(c, prefix)) {
+                    return false;
+                }
+            }
+            if let Some(expected) = &rule.exe_sha256 {
+                if !process.exe_sha256.as_ref().is_some_and(|h| h.eq_ignore_ascii_case(expected)) {
+                    return false;
+                }
+            }
+
+            true
+        })
+    }
+}
+
+/// cgroup 이 접두사 경로와 같거나 그 하위 경로인지
+pub fn cgroup_within(cgroup: &str, prefix: &str) -> bool {
+    let prefix = prefix.trim_end_matches('/');
+    cgroup == prefix || cgroup.strip_prefix(prefix).is_some_and(|rest| rest.starts_with('/'))
+}
+
+/// "a.b.c.d/n" 또는 단일 주소 파싱
+pub fn parse_cidr(cidr: &str) -> Result<(Ipv4Addr, u32)> {
+    let (addr, prefix_len) = match cidr.split_once('/') {
+        Some((addr, prefix_len)) => (addr, prefix_len.parse::<u32>()?),
+        None => (cidr, 32),
+    };
+
+    if prefix_len > 32 {
+        return Err(anyhow::anyhow!("Invalid CIDR prefix length: {}", cidr));
+    }
+
+    Ok((addr.parse()?, prefix_len))
+}
+
+/// 주소(u32)가 대역에 포함되는지 확인
+fn cidr_contains(net: Ipv4Addr, prefix_len: u32, addr: u32) -> bool {
+    let mask = if prefix_len == 0 { 0 } else { u32::MAX << (32 - prefix_len) };
+    (u32::from(net) & mask) == (addr & mask)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+    use crate::plugins::reverse_shell::{ConnectionInfo, EventType, Severity};
+    use crate::process_info::ProcessInfo;
+
+    fn connection_event(rule_id: &str, remote_addr: u32, remote_port: u16) -> ReverseShellEvent {
+        ReverseShellEvent {
+            timestamp: Instant::now(),
+            event_type: EventType::KernelHookTriggered,
+            severity: Severity::Critical,
+            rule_id: rule_id.to_string(),
+            details: String::new(),
+            process: None,
+            incident_id: None,
+            round_id: None,
+            techniques: Vec::new(),
+            connection_info: Some(ConnectionInfo {
+                local_addr: 0,
+                local_addr6: None,
+                remote_addr,
+                remote_addr6: None,
+                local_port: 40000,
+                remote_port,
+                pid: 0,
+                process_name: "unknown".to_string(),
+                first_seen: Instant::now(),
+                last_seen: Instant::now(),
+                connection_count: 1,
+                is_suspicious: true,
+            }),
+        }
+    }
+
+    #[test]
+    fn test_cidr_and_port_match() {
+        let list = SuppressionList::new(vec![Suppression {
+            id: "scoreboard".to_string(),
+            cidr: Some("10.10.0.0/16".to_string()),
+            port: Some(8080),
+            ..Default::default()
+        }]);
+
+        let scoreboard = u32::from(Ipv4Addr::new(10, 10, 3, 4));
+        assert!(list.find_match(&connection_event("suspicious_connection", scoreboard, 8080)).is_some());
+        assert!(list.find_match(&connection_event("suspicious_connection", scoreboard, 4444)).is_none());
+        assert!(list.find_match(&connection_event("suspicious_connection", 0x08080808, 8080)).is_none());
+    }
+
+    #[test]
+    fn test_rule_id_and_expiry() {
+        let list = SuppressionList::new(vec![
+            Suppression {
+                id: "expired".to_string(),
+                port: Some(4444),
+                expires_at: Some(1),
+                ..Default::default()
+            },
+            Suppression {
+                id: "rule".to_string(),
+                rule_id: Some("bind_shell".to_string()),
+                ..Default::default()
+            },
+        ]);
+
+        assert!(list.find_match(&connection_event("suspicious_connection", 0x08080808, 4444)).is_none());
+        assert_eq!(list.find_match(&connection_event("bind_shell", 0x08080808, 4444)).map(|s| s.id.as_str()), Some("rule"));
+    }
+
+    #[test]
+    fn test_process_scoped_match() {
+        let list = SuppressionList::new(vec![Suppression {
+            id: "sshd".to_string(),
+            exe_path: Some("/usr/sbin/sshd".to_string()),
+            uid: Some(0),
+            ..Default::default()
+        }]);
+
+        let mut event = connection_event("suspicious_connection", 0x08080808, 22);
+        assert!(list.find_match(&event).is_none());
+
+        event.process = Some(ProcessInfo {
+            pid: 812,
+            exe: Some("/usr/sbin/sshd".to_string()),
+            uid: 0,
+            ..Default::default()
+        });
+        assert!(list.find_match(&event).is_some());
+
+        event.process.as_mut().unwrap().uid = 1000;
+        assert!(list.find_match(&event).is_none());
+
+        // cgroup 접두사는 경로 단위로 비교
+        let list = SuppressionList::new(vec![Suppression {
+            id: "team-1".to_string(),
+            cgroup: Some("/ctf/team-1".to_string()),
+            ..Default::default()
+        }]);
+        for (cgroup, suppressed) in [("/ctf/team-1", true), ("/ctf/team-1/web", true), ("/ctf/team-10/web", false)] {
+            event.process.as_mut().unwrap().cgroup = Some(cgroup.to_string());
+            assert_eq!(list.find_match(&event).is_some(), suppressed, "{}", cgroup);
+        }
+        assert!(cgroup_within("/ctf/team-1/web", "/ctf/team-1/"));
+        assert!(cgroup_within("/system.slice", "/"));
+    }
+
+    #[test]
+    fn test_kernel_allow_entries() {
+        let list = SuppressionList::new(vec![
+            Suppression { id: "a".to_string(), cidr: Some("192.168.0.0/16".to_string()), ..Default::default() },
+            Suppression { id: "b".to_string(), port: Some(22), ..Default::default() },
+            Suppression { id: "c".to_string(), port: Some(22), uid: Some(0), ..Default::default() },
+            Suppression { id: "d".to_string(), uid: Some(1000), expires_at: Some(u64::MAX), ..Default::default() },
+        ]);
+
+        assert_eq!(list.kernel_allow_entries(), vec![
+            KernelAllowEntry::Cidr { addr: Ipv4Addr::new(192, 168, 0, 0), prefix_len: 16 },
+            KernelAllowEntry::Port(22),
+        ]);
+        assert!(parse_cidr("10.0.0.0/33").is_err());
+    }
+}
@@ -0,0 +1,147 @@
+mod state;
+mod ui;
+
+use std::time::Duration;
+use anyhow::Result;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use tokio::sync::mpsc;
+
+use crate::http;
+use state::{ConnectionRow, ConnectionTable, Dashboard, DashboardEvent, KeyAction};
+
+/// 연결 테이블 갱신 주기
+const REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// 스트림이 끊겼을 때 재연결 대기 시간
+const RECONNECT_DELAY: Duration = Duration::from_secs(2);
+
+/// 화면 갱신 요청
+enum Update {
+    History(Vec<DashboardEvent>),
+    Event(DashboardEvent),
+    Connections(Vec<ConnectionRow>),
+    Status(String),
+}
+
+/// 실행 중인 에이전트의 조회 API 에 연결해 대시보드 표시
+pub async fn run(address: String) -> Result<()> {
+    // 터미널을 바꾸기 전에 연결을 확인해 오류를 그대로 출력
+    http::get(&address, "/health").await?;
+
+    let (update_tx, mut updates) = mpsc::channel(256);
+    tokio::spawn(stream_events(address.clone(), update_tx.clone()));
+    tokio::spawn(poll_connections(address.clone(), update_tx));
+    let mut keys = read_keys();
+
+    let mut terminal = ratatui::init();
+    let mut dashboard = Dashboard::new();
+    let mut redraw = tokio::time::interval(REFRESH_INTERVAL);
+
+    let result = loop {
+        if let Err(e) = terminal.draw(|frame| ui::draw(frame, &dashboard, &address)) {
+            break Err(e.into());
+        }
+
+        tokio::select! {
+            update = updates.recv() => match update {
+                Some(Update::History(events)) => dashboard.load_history(events),
+                Some(Update::Event(event)) => dashboard.push_event(event),
+                Some(Update::Connections(rows)) => dashboard.connections = rows,
+                Some(Update::Status(status)) => dashboard.status = status,
+                None => break Ok(()),
+            },
+            key = keys.recv() => match key {
+                Some(key) if dashboard.editing_filter => match key {
+                    KeyCode::Enter => dashboard.editing_filter = false,
+                    KeyCode::Esc => {
+                        dashboard.editing_filter = false;
+                        dashboard.filter.clear();
+                    }
+                    KeyCode::Backspace => dashboard.edit_filter(None),
+                    KeyCode::Char(c) => dashboard.edit_filter(Some(c)),
+                    _ => {}
+                },
+                Some(KeyCode::Char(c)) => {
+                    if dashboard.handle_key(c) == KeyAction::Quit {
+                        break Ok(());
+                    }
+                }
+                Some(KeyCode::Esc) => break Ok(()),
+                Some(_) => {}
+                None => break Ok(()),
+            },
+            _ = redraw.tick() => {}
+        }
+    };
+
+    ratatui::restore();
+    result
+}
+
+/// 터미널 키 입력을 별도 스레드에서 읽음 (crossterm 입력은 블로킹)
+fn read_keys() -> mpsc::Receiver<KeyCode> {
+    let (tx, rx) = mpsc::channel(32);
+    std::thread::spawn(move || {
+        while !tx.is_closed() {
+            match event::poll(Duration::from_millis(200)) {
+                Ok(true) => {}
+                Ok(false) => continue,
+                Err(_) => break,
+            }
+            if let Ok(Event::Key(key)) = event::read() {
+                if key.kind == KeyEventKind::Press && tx.blocking_send(key.code).is_err() {
+                    break;
+                }
+            }
+        }
+    });
+    rx
+}
+
+/// 탐지 이벤트 스트림 구독 (구독 후 이전 이벤트를 조회해 빠지는 이벤트가 없도록 함)
+async fn stream_events(address: String, tx: mpsc::Sender<Update>) {
+    loop {
+        let result: Result<()> = async {
+            let mut lines = http::get_lines(&address, "/events/stream").await?;
+            let history: Vec<DashboardEvent> = serde_json::from_slice(&http::get(&address, "/events").await?)?;
+            let _ = tx.send(Update::History(history)).await;
+            let _ = tx.send(Update::Status("live".to_string())).await;
+
+            while let Some(line) = lines.next_line().await? {
+                if let Ok(event) = serde_json::from_str(&line) {
+                    if tx.send(Update::Event(event)).await.is_err() {
+                        return Ok(());
+                    }
+                }
+            }
+            Ok(())
+        }.await;
+
+        let status = match result {
+            Ok(()) => "agent stopped streaming, reconnecting".to_string(),
+            Err(e) => format!("disconnected ({}), reconnecting", e),
+        };
+        if tx.send(Update::Status(status)).await.is_err() {
+            return;
+        }
+        tokio::time::sleep(RECONNECT_DELAY).await;
+    }
+}
+
+/// 연결 테이블 주기적 조회
+async fn poll_connections(address: String, tx: mpsc::Sender<Update>) {
+    let mut interval = tokio::time::interval(REFRESH_INTERVAL);
+    loop {
+        interval.tick().await;
+
+        let update = match http::get(&address, "/connections").await
+            .and_then(|body| Ok(serde_json::from_slice::<ConnectionTable>(&body)?))
+        {
+            Ok(table) => Update::Connections(table.connections),
+            Err(e) => Update::Status(format!("failed to fetch connections: {}", e)),
+        };
+        if tx.send(update).await.is_err() {
+            return;
+        }
+    }
+}
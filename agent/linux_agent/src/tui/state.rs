@@ -0,0 +1,368 @@
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use serde::Deserialize;
+
+/// 화면에 유지할 최근 탐지 이벤트 수
+const MAX_EVENTS: usize = 500;
+
+/// 조회 API 의 탐지 이벤트 (화면에 필요한 필드만)
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct DashboardEvent {
+    /// 유닉스 밀리초
+    pub timestamp: u64,
+    pub event_type: String,
+    pub severity: String,
+    pub rule_id: String,
+    pub details: String,
+}
+
+/// 조회 API 의 연결 테이블 행
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ConnectionRow {
+    pub local_addr: String,
+    /// IPv4 로 나타낼 수 없는 IPv6 로컬 주소
+    #[serde(default)]
+    pub local_addr6: Option<String>,
+    pub local_port: u16,
+    pub remote_addr: String,
+    /// IPv4 로 나타낼 수 없는 IPv6 원격 주소
+    #[serde(default)]
+    pub remote_addr6: Option<String>,
+    pub remote_port: u16,
+    pub pid: u32,
+    pub process_name: String,
+    /// 유닉스 밀리초
+    pub last_seen: u64,
+    pub connection_count: u32,
+    pub is_suspicious: bool,
+}
+
+impl ConnectionRow {
+    /// 로컬 주소:포트 (IPv6 는 [주소]:포트)
+    pub fn local_endpoint(&self) -> String {
+        endpoint(&self.local_addr, self.local_addr6.as_deref(), self.local_port)
+    }
+
+    /// 원격 주소 (IPv6 주소가 있으면 IPv6)
+    pub fn remote_ip(&self) -> &str {
+        self.remote_addr6.as_deref().unwrap_or(&self.remote_addr)
+    }
+
+    /// 원격 주소:포트 (IPv6 는 [주소]:포트)
+    pub fn remote_endpoint(&self) -> String {
+        endpoint(&self.remote_addr, self.remote_addr6.as_deref(), self.remote_port)
+    }
+}
+
+fn endpoint(addr: &str, addr6: Option<&str>, port: u16) -> String {
+    match addr6 {
+        Some(addr6) => format!("[{}]:{}", addr6, port),
+        None => format!("{}:{}", addr, port),
+    }
+}
+
+/// `GET /connections` 응답
+#[derive(Debug, Clone, Deserialize)]
+pub struct ConnectionTable {
+    pub connections: Vec<ConnectionRow>,
+}
+
+/// 연결 테이블 정렬 기준
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SortKey {
+    #[default]
+    LastSeen,
+    Count,
+    Remote,
+    Process,
+    Pid,
+}
+
+/// 원격 주소별 연결 집계
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Talker {
+    pub remote_addr: String,
+    /// 서로 다른 연결 수
+    pub connections: usize,
+    /// 스캔에서 관찰된 횟수 합계
+    pub observations: u64,
+}
+
+/// 입력 처리 결과
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyAction {
+    None,
+    Quit,
+}
+
+/// 대시보드 화면 상태
+#[derive(Debug, Default)]
+pub struct Dashboard {
+    /// 최근 탐지 이벤트 (최신이 앞)
+    pub events: VecDeque<DashboardEvent>,
+    /// 규칙별 탐지 수
+    pub rule_hits: BTreeMap<String, u64>,
+    pub connections: Vec<ConnectionRow>,
+    pub sort: SortKey,
+    pub descending: bool,
+    /// 연결 테이블 필터 (프로세스, 주소, 포트, pid 부분 일치)
+    pub filter: String,
+    /// 필터 입력 중
+    pub editing_filter: bool,
+    /// 에이전트 연결 상태
+    pub status: String,
+    /// 이력 조회와 스트림에 모두 들어온 이벤트 (스트림 쪽을 무시)
+    history_keys: HashSet<(u64, String, String)>,
+}
+
+impl SortKey {
+    pub fn next(self) -> Self {
+        match self {
+            SortKey::LastSeen => SortKey::Count,
+            SortKey::Count => SortKey::Remote,
+            SortKey::Remote => SortKey::Process,
+            SortKey::Process => SortKey::Pid,
+            SortKey::Pid => SortKey::LastSeen,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SortKey::LastSeen => "last seen",
+            SortKey::Count => "count",
+            SortKey::Remote => "remote",
+            SortKey::Process => "process",
+            SortKey::Pid => "pid",
+        }
+    }
+}
+
+impl Dashboard {
+    pub fn new() -> Self {
+        Self {
+            descending: true,
+            status: "connecting".to_string(),
+            ..Self::default()
+        }
+    }
+
+    /// (재)연결 시 조회한 전체 탐지 이벤트 (오래된 순, 기존 화면 내용을 대체)
+    pub fn load_history(&mut self, events: Vec<DashboardEvent>) {
+        self.events.clear();
+        self.rule_hits.clear();
+        self.history_keys.clear();
+
+        for event in events {
+            if !self.history_keys.insert(Self::key(&event)) {
+                continue;
+            }
+            self.add(event);
+        }
+    }
+
+    /// 스트림으로 받은 이벤트
+    pub fn push_event(&mut self, event: DashboardEvent) {
+        if self.history_keys.remove(&Self::key(&event)) {
+            return;
+        }
+        self.add(event);
+    }
+
+    fn add(&mut self, event: DashboardEvent) {
+        *self.rule_hits.entry(event.rule_id.clone()).or_default() += 1;
+        self.events.push_front(event);
+        self.events.truncate(MAX_EVENTS);
+    }
+
+    fn key(event: &DashboardEvent) -> (u64, String, String) {
+        (event.timestamp, event.rule_id.clone(), event.details.clone())
+    }
+
+    /// 탐지 수가 많은 규칙 순
+    pub fn top_rules(&self) -> Vec<(&str, u64)> {
+        let mut rules: Vec<(&str, u64)> = self.rule_hits.iter().map(|(rule, hits)| (rule.as_str(), *hits)).collect();
+        rules.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+        rules
+    }
+
+    /// 필터와 정렬을 적용한 연결 목록
+    pub fn visible_connections(&self) -> Vec<&ConnectionRow> {
+        let filter = self.filter.to_lowercase();
+        let mut rows: Vec<&ConnectionRow> = self.connections.iter()
+            .filter(|row| {
+                filter.is_empty()
+                    || row.process_name.to_lowercase().contains(&filter)
+                    || row.remote_endpoint().contains(&filter)
+                    || row.local_endpoint().contains(&filter)
+                    || row.pid.to_string() == filter
+            })
+            .collect();
+
+        rows.sort_by(|a, b| {
+            let ordering = match self.sort {
+                SortKey::LastSeen => a.last_seen.cmp(&b.last_seen),
+                SortKey::Count => a.connection_count.cmp(&b.connection_count),
+                SortKey::Remote => (a.remote_ip(), a.remote_port).cmp(&(b.remote_ip(), b.remote_port)),
+                SortKey::Process => a.process_name.cmp(&b.process_name),
+                SortKey::Pid => a.pid.cmp(&b.pid),
+            };
+            if self.descending { ordering.reverse() } else { ordering }
+        });
+        rows
+    }
+
+    /// 연결이 많은 원격 주소 상위 n 개
+    pub fn top_talkers(&self, n: usize) -> Vec<Talker> {
+        let mut talkers: HashMap<&str, Talker> = HashMap::new();
+        for row in &self.connections {
+            let talker = talkers.entry(row.remote_ip()).or_insert_with(|| Talker {
+                remote_addr: row.remote_ip().to_string(),
+                connections: 0,
+                observations: 0,
+            });
+            talker.connections += 1;
+            talker.observations += row.connection_count as u64;
+        }
+
+        let mut talkers: Vec<Talker> = talkers.into_values().collect();
+        talkers.sort_by(|a, b| {
+            b.connections.cmp(&a.connections)
+                .then(b.observations.cmp(&a.observations))
+                .then(a.remote_addr.cmp(&b.remote_addr))
+        });
+        talkers.truncate(n);
+        talkers
+    }
+
+    /// 키 입력 처리 (`q` 종료, `s` 정렬 기준, `r` 정렬 방향, `/` 필터 입력)
+    pub fn handle_key(&mut self, key: char) -> KeyAction {
+        match key {
+            'q' => return KeyAction::Quit,
+            's' => self.sort = self.sort.next(),
+            'r' => self.descending = !self.descending,
+            '/' => {
+                self.editing_filter = true;
+                self.filter.clear();
+            }
+            _ => {}
+        }
+        KeyAction::None
+    }
+
+    /// 필터 입력 (None 이면 마지막 문자 삭제)
+    pub fn edit_filter(&mut self, key: Option<char>) {
+        match key {
+            Some(c) => self.filter.push(c),
+            None => {
+                self.filter.pop();
+            }
+        }
+    }
+}
+
+/// 유닉스 밀리초를 UTC 시각 (HH:MM:SS) 으로 표시
+pub fn clock(millis: u64) -> String {
+    let secs = millis / 1000 % 86_400;
+    format!("{:02}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(timestamp: u64, rule_id: &str) -> DashboardEvent {
+        DashboardEvent {
+            timestamp,
+            event_type: "bind_shell_detected".to_string(),
+            severity: "critical".to_string(),
+            rule_id: rule_id.to_string(),
+            details: format!("{} at {}", rule_id, timestamp),
+        }
+    }
+
+    fn connection(remote_addr: &str, remote_port: u16, process_name: &str, pid: u32, count: u32) -> ConnectionRow {
+        ConnectionRow {
+            local_addr: "10.0.0.10".to_string(),
+            local_addr6: None,
+            local_port: 40000,
+            remote_addr: remote_addr.to_string(),
+            remote_addr6: None,
+            remote_port,
+            pid,
+            process_name: process_name.to_string(),
+            last_seen: pid as u64,
+            connection_count: count,
+            is_suspicious: false,
+        }
+    }
+
+    #[test]
+    fn test_history_and_stream_counted_once() {
+        let mut dashboard = Dashboard::new();
+        dashboard.load_history(vec![event(1, "interpreter_listen"), event(2, "memfd_executable")]);
+
+        // 스트림 구독 후 이력을 조회하므로 겹치는 이벤트는 한 번만 집계
+        dashboard.push_event(event(2, "memfd_executable"));
+        dashboard.push_event(event(3, "interpreter_listen"));
+
+        assert_eq!(dashboard.events.len(), 3);
+        assert_eq!(dashboard.events[0].timestamp, 3);
+        assert_eq!(dashboard.top_rules(), vec![("interpreter_listen", 2), ("memfd_executable", 1)]);
+
+        // 재연결 시 조회한 이력으로 대체
+        dashboard.load_history(vec![event(1, "interpreter_listen")]);
+        assert_eq!(dashboard.events.len(), 1);
+        assert_eq!(dashboard.top_rules(), vec![("interpreter_listen", 1)]);
+    }
+
+    #[test]
+    fn test_connection_sort_and_filter() {
+        let mut dashboard = Dashboard::new();
+        dashboard.connections = vec![
+            connection("203.0.113.10", 9001, "/bin/bash", 31401, 3),
+            connection("198.51.100.7", 443, "curl", 2200, 9),
+            connection("203.0.113.10", 4444, "nc", 31500, 1),
+        ];
+
+        let pids = |dashboard: &Dashboard| dashboard.visible_connections().iter().map(|row| row.pid).collect::<Vec<_>>();
+        assert_eq!(pids(&dashboard), vec![31500, 31401, 2200]);
+
+        dashboard.handle_key('s');
+        assert_eq!(dashboard.sort, SortKey::Count);
+        assert_eq!(pids(&dashboard), vec![2200, 31401, 31500]);
+        dashboard.handle_key('r');
+        assert_eq!(pids(&dashboard), vec![31500, 31401, 2200]);
+
+        dashboard.handle_key('/');
+        for c in "203.0.113.10:9001".chars() {
+            dashboard.edit_filter(Some(c));
+        }
+        assert_eq!(pids(&dashboard), vec![31401]);
+        dashboard.filter = "BASH".to_string();
+        assert_eq!(pids(&dashboard), vec![31401]);
+        assert_eq!(dashboard.handle_key('q'), KeyAction::Quit);
+    }
+
+    #[test]
+    fn test_top_talkers() {
+        let mut dashboard = Dashboard::new();
+        dashboard.connections = vec![
+            connection("203.0.113.10", 9001, "/bin/bash", 31401, 3),
+            connection("198.51.100.7", 443, "curl", 2200, 9),
+            connection("203.0.113.10", 4444, "nc", 31500, 1),
+        ];
+
+        let talkers = dashboard.top_talkers(1);
+        assert_eq!(talkers, vec![Talker { remote_addr: "203.0.113.10".to_string(), connections: 2, observations: 4 }]);
+
+        // IPv6 연결은 remote_addr 가 0.0.0.0 이므로 IPv6 주소로 집계
+        let row: ConnectionRow = serde_json::from_value(serde_json::json!({
+            "local_addr": "0.0.0.0", "local_addr6": "2001:db8::1", "local_port": 40000,
+            "remote_addr": "0.0.0.0", "remote_addr6": "2001:db8::2", "remote_port": 9001,
+            "pid": 4343, "process_name": "bash", "last_seen": 0, "connection_count": 1, "is_suspicious": true,
+        })).unwrap();
+        assert_eq!(row.remote_endpoint(), "[2001:db8::2]:9001");
+        dashboard.connections.push(row);
+        assert_eq!(dashboard.top_talkers(3)[2].remote_addr, "2001:db8::2");
+        assert_eq!(clock(86_400_000 + 3_723_000), "01:02:03");
+    }
+}
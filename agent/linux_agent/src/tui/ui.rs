@@ -0,0 +1,159 @@
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, List, ListItem, Paragraph, Row, Table};
+use ratatui::Frame;
+
+use crate::plugins::reverse_shell::Severity;
+use crate::tui::state::{clock, Dashboard};
+
+/// 상위 원격 주소 표시 개수
+const TOP_TALKERS: usize = 10;
+
+/// 심각도별 색상
+fn severity_style(severity: &str) -> Style {
+    match Severity::parse(severity) {
+        Some(Severity::Critical) => Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+        Some(Severity::High) => Style::default().fg(Color::LightRed),
+        Some(Severity::Medium) => Style::default().fg(Color::Yellow),
+        Some(Severity::Low) => Style::default().fg(Color::Green),
+        None => Style::default(),
+    }
+}
+
+/// 대시보드 그리기
+pub fn draw(frame: &mut Frame, dashboard: &Dashboard, address: &str) {
+    let [header, top, bottom, footer] = Layout::vertical([
+        Constraint::Length(1),
+        Constraint::Percentage(50),
+        Constraint::Fill(1),
+        Constraint::Length(1),
+    ]).areas(frame.area());
+    let [detections, side] = Layout::horizontal([Constraint::Percentage(60), Constraint::Fill(1)]).areas(top);
+    let [rules, talkers] = Layout::vertical([Constraint::Percentage(50), Constraint::Fill(1)]).areas(side);
+
+    frame.render_widget(Paragraph::new(Line::from(vec![
+        Span::styled("telemagnifier ", Style::default().add_modifier(Modifier::BOLD)),
+        Span::raw(format!("{} | {} events | {} connections | {}",
+            address, dashboard.rule_hits.values().sum::<u64>(), dashboard.connections.len(), dashboard.status)),
+    ])), header);
+
+    let items: Vec<ListItem> = dashboard.events.iter()
+        .map(|event| {
+            ListItem::new(Line::from(vec![
+                Span::raw(format!("{} ", clock(event.timestamp))),
+                Span::styled(format!("{:<8} ", event.severity), severity_style(&event.severity)),
+                Span::styled(format!("[{}] ", event.rule_id), Style::default().fg(Color::Cyan)),
+                Span::raw(event.details.clone()),
+            ]))
+        })
+        .collect();
+    frame.render_widget(List::new(items).block(Block::default().borders(Borders::ALL).title(" Detections ")), detections);
+
+    let rule_rows = dashboard.top_rules().into_iter()
+        .map(|(rule, hits)| Row::new(vec![rule.to_string(), hits.to_string()]));
+    frame.render_widget(
+        Table::new(rule_rows, [Constraint::Fill(1), Constraint::Length(6)])
+            .header(Row::new(vec!["rule", "hits"]).style(Style::default().add_modifier(Modifier::BOLD)))
+            .block(Block::default().borders(Borders::ALL).title(" Rule hits ")),
+        rules,
+    );
+
+    let talker_rows = dashboard.top_talkers(TOP_TALKERS).into_iter()
+        .map(|talker| Row::new(vec![talker.remote_addr, talker.connections.to_string(), talker.observations.to_string()]));
+    frame.render_widget(
+        Table::new(talker_rows, [Constraint::Fill(1), Constraint::Length(6), Constraint::Length(8)])
+            .header(Row::new(vec!["remote", "conns", "seen"]).style(Style::default().add_modifier(Modifier::BOLD)))
+            .block(Block::default().borders(Borders::ALL).title(" Top talkers ")),
+        talkers,
+    );
+
+    let connection_rows = dashboard.visible_connections().into_iter().map(|row| {
+        let style = if row.is_suspicious { Style::default().fg(Color::Red) } else { Style::default() };
+        Row::new(vec![
+            clock(row.last_seen),
+            row.local_endpoint(),
+            row.remote_endpoint(),
+            row.pid.to_string(),
+            row.process_name.clone(),
+            row.connection_count.to_string(),
+        ]).style(style)
+    });
+    let title = format!(" Connections (sort: {} {}{}) ",
+        dashboard.sort.label(),
+        if dashboard.descending { "desc" } else { "asc" },
+        if dashboard.filter.is_empty() { String::new() } else { format!(", filter: {}", dashboard.filter) });
+    frame.render_widget(
+        Table::new(connection_rows, [
+            Constraint::Length(8),
+            Constraint::Length(21),
+            Constraint::Length(21),
+            Constraint::Length(7),
+            Constraint::Fill(1),
+            Constraint::Length(6),
+        ])
+            .header(Row::new(vec!["last", "local", "remote", "pid", "process", "count"])
+                .style(Style::default().add_modifier(Modifier::BOLD)))
+            .block(Block::default().borders(Borders::ALL).title(title)),
+        bottom,
+    );
+
+    let help = if dashboard.editing_filter {
+        format!("filter: {}_  (Enter apply, Esc clear)", dashboard.filter)
+    } else {
+        "q quit  s sort  r reverse  / filter".to_string()
+    };
+    frame.render_widget(Paragraph::new(help).style(Style::default().fg(Color::DarkGray)), footer);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::backend::TestBackend;
+    use ratatui::Terminal;
+    use crate::tui::state::{ConnectionRow, DashboardEvent};
+
+    #[test]
+    fn test_draw_dashboard() {
+        let mut dashboard = Dashboard::new();
+        dashboard.load_history(vec![DashboardEvent {
+            timestamp: 3_723_000,
+            event_type: "bind_shell_detected".to_string(),
+            severity: "critical".to_string(),
+            rule_id: "interpreter_listen".to_string(),
+            details: "Possible bind shell: nc (pid 31400) listening on 0.0.0.0:4444".to_string(),
+        }]);
+        dashboard.connections = vec![ConnectionRow {
+            local_addr: "10.0.0.10".to_string(),
+            local_addr6: None,
+            local_port: 51234,
+            remote_addr: "203.0.113.10".to_string(),
+            remote_addr6: None,
+            remote_port: 9001,
+            pid: 31401,
+            process_name: "/bin/bash".to_string(),
+            last_seen: 3_723_000,
+            connection_count: 1,
+            is_suspicious: true,
+        }];
+
+        let mut terminal = Terminal::new(TestBackend::new(140, 30)).unwrap();
+        terminal.draw(|frame| draw(frame, &dashboard, "/run/telemagnifier/api.sock")).unwrap();
+
+        let buffer = terminal.backend().buffer();
+        let screen: String = (0..buffer.area.height)
+            .map(|y| (0..buffer.area.width).map(|x| buffer[(x, y)].symbol()).collect::<String>() + "\n")
+            .collect();
+        assert!(screen.contains("1 events | 1 connections"));
+        assert!(screen.contains("01:02:03 critical [interpreter_listen] Possible bind shell"));
+        assert!(screen.contains("203.0.113.10:9001"));
+        assert!(screen.contains("sort: last seen desc"));
+
+        // Critical 은 빨간색 굵은 글씨
+        let row = (0..buffer.area.height)
+            .find(|&y| (0..buffer.area.width).map(|x| buffer[(x, y)].symbol()).collect::<String>().contains("critical"))
+            .unwrap();
+        let x = (0..buffer.area.width).find(|&x| buffer[(x, row)].symbol() == "c" && buffer[(x + 1, row)].symbol() == "r").unwrap();
+        assert_eq!(buffer[(x, row)].fg, Color::Red);
+    }
+}